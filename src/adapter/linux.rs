@@ -0,0 +1,57 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::adapter::Adapter;
+use crate::internet_connectivity::LinuxInternetConnectivity;
+
+/// `ARPHRD_LOOPBACK`, the value of `/sys/class/net/<iface>/type` for loopback interfaces.
+const ARPHRD_LOOPBACK: &str = "772";
+
+/// Address reported by interfaces without a hardware address, such as tunnels.
+const NO_ADDRESS: &str = "00:00:00:00:00:00";
+
+/// Returns the non-loopback adapters listed in `/sys/class/net`, sorted by name.
+pub fn adapters() -> io::Result<Vec<Adapter>> {
+    adapters_under("/")
+}
+
+/// Returns the non-loopback adapters listed in `sys/class/net` under `root`, sorted by name.
+///
+/// # Arguments
+/// - `root`: Directory containing the `sys/class/net` entries.
+pub fn adapters_under(root: impl AsRef<Path>) -> io::Result<Vec<Adapter>> {
+    let net = root.as_ref().join("sys/class/net");
+
+    let mut adapters: Vec<Adapter> = fs::read_dir(&net)?
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|name| adapter(&net.join(&name), name))
+        .collect();
+    adapters.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(adapters)
+}
+
+fn adapter(dir: &Path, name: String) -> Option<Adapter> {
+    let read = |file: &str| {
+        fs::read_to_string(dir.join(file))
+            .map(|value| value.trim().to_string())
+            .ok()
+            .filter(|value| !value.is_empty())
+    };
+
+    if read("type").as_deref() == Some(ARPHRD_LOOPBACK) {
+        return None;
+    }
+
+    Some(Adapter {
+        description: fs::read_link(dir.join("device/driver"))
+            .ok()
+            .and_then(|driver| Some(driver.file_name()?.to_string_lossy().into_owned())),
+        mac: read("address").filter(|address| address != NO_ADDRESS),
+        guid: None,
+        up: LinuxInternetConnectivity::is_link_up(dir),
+        wireless: dir.join("wireless").exists() || dir.join("phy80211").exists(),
+        name,
+    })
+}
@@ -0,0 +1,56 @@
+//! Module for listing the network adapters present on the system.
+//!
+//! This module defines the [`Adapter`] struct, an `adapters` function for the current OS, and the
+//! [`InterfaceSelector`] which chooses the adapters to check and reconnect.
+
+mod selector;
+
+pub use selector::{InterfaceMatch, InterfaceSelector};
+
+cfg_if::cfg_if! {
+    if #[cfg(target_os = "windows")] {
+        mod windows;
+        pub use windows::adapters;
+    } else if #[cfg(target_os = "linux")] {
+        mod linux;
+        pub use linux::{adapters, adapters_under};
+    }
+}
+
+/// A network adapter present on the system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Adapter {
+    /// Name of the adapter, such as `wlan0` on Linux or `Wi-Fi` on Windows.
+    pub name: String,
+
+    /// Description of the adapter, such as its driver or product name, if known.
+    pub description: Option<String>,
+
+    /// Hardware (MAC) address of the adapter, formatted as lowercase colon-separated hex.
+    pub mac: Option<String>,
+
+    /// GUID identifying the adapter, on platforms which assign one.
+    pub guid: Option<String>,
+
+    /// Whether the adapter's link is up.
+    pub up: bool,
+
+    /// Whether the adapter is a wireless adapter.
+    pub wireless: bool,
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`Adapter`] to enable easy printing.
+impl std::fmt::Display for Adapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            self.name,
+            if self.up { "up" } else { "down" },
+            if self.wireless { "wireless" } else { "wired" },
+            self.mac.as_deref().unwrap_or("-"),
+            self.guid.as_deref().unwrap_or("-"),
+            self.description.as_deref().unwrap_or("-"),
+        )
+    }
+}
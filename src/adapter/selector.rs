@@ -0,0 +1,194 @@
+use std::io;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::adapter::{Adapter, adapters};
+
+/// The property an [`InterfaceSelector`] matches adapters by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterfaceMatch {
+    /// The adapter name, such as `wlan0` or `Wi-Fi`, compared exactly.
+    Name(String),
+
+    /// The hardware (MAC) address, compared ignoring case and whether `:` or `-` separates bytes.
+    Mac(String),
+
+    /// A substring of the adapter description, compared ignoring case.
+    Description(String),
+
+    /// The adapter GUID, compared ignoring case and surrounding braces.
+    Guid(String),
+}
+
+/// Selects the network adapters to check and reconnect.
+///
+/// By default only the first matching adapter is selected, in the order the adapters are listed.
+/// In "all" mode, every matching adapter is selected.
+///
+/// Selectors are written as `<kind>:<value>`, where the kind is `name`, `mac`, `description` or
+/// `guid`; a value without a kind is matched by name, e.g. `wlan0`, `mac:02:00:00:00:01:00` or
+/// `description:Intel`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct InterfaceSelector {
+    matcher: InterfaceMatch,
+    all: bool,
+}
+
+impl InterfaceSelector {
+    /// Creates a new instance of [`InterfaceSelector`] selecting the first adapter which matches.
+    ///
+    /// # Arguments
+    /// - `matcher`: The property adapters are matched by.
+    pub fn new(matcher: InterfaceMatch) -> Self {
+        Self {
+            matcher,
+            all: false,
+        }
+    }
+
+    /// Creates a new instance of [`InterfaceSelector`] matching adapters by name.
+    pub fn name(name: impl Into<String>) -> Self {
+        Self::new(InterfaceMatch::Name(name.into()))
+    }
+
+    /// Sets whether every matching adapter is selected, rather than only the first.
+    pub fn with_all(mut self, all: bool) -> Self {
+        self.all = all;
+        self
+    }
+
+    /// Returns the property adapters are matched by.
+    pub fn matcher(&self) -> &InterfaceMatch {
+        &self.matcher
+    }
+
+    /// Returns `true` if every matching adapter is selected.
+    pub fn is_all(&self) -> bool {
+        self.all
+    }
+
+    /// Returns the adapter name, if this selector picks a single adapter by name.
+    ///
+    /// Such a selector can be used without listing the adapters present.
+    pub fn single_name(&self) -> Option<&str> {
+        match &self.matcher {
+            InterfaceMatch::Name(name) if !self.all => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if `adapter` matches this selector.
+    pub fn matches(&self, adapter: &Adapter) -> bool {
+        match &self.matcher {
+            InterfaceMatch::Name(name) => adapter.name == *name,
+            InterfaceMatch::Mac(mac) => adapter
+                .mac
+                .as_deref()
+                .is_some_and(|candidate| normalize_mac(candidate) == normalize_mac(mac)),
+            InterfaceMatch::Description(description) => {
+                adapter.description.as_deref().is_some_and(|candidate| {
+                    candidate
+                        .to_lowercase()
+                        .contains(&description.to_lowercase())
+                })
+            }
+            InterfaceMatch::Guid(guid) => adapter
+                .guid
+                .as_deref()
+                .is_some_and(|candidate| normalize_guid(candidate) == normalize_guid(guid)),
+        }
+    }
+
+    /// Returns the selected adapters among `adapters`, keeping their order.
+    ///
+    /// # Arguments
+    /// - `adapters`: The candidate adapters.
+    pub fn select<'a>(&self, adapters: impl IntoIterator<Item = &'a Adapter>) -> Vec<&'a Adapter> {
+        let matching = adapters.into_iter().filter(|adapter| self.matches(adapter));
+        match self.all {
+            true => matching.collect(),
+            false => matching.take(1).collect(),
+        }
+    }
+
+    /// Returns the selected adapters among those present on the system.
+    pub fn resolve(&self) -> io::Result<Vec<Adapter>> {
+        Ok(self.select(&adapters()?).into_iter().cloned().collect())
+    }
+
+    /// Returns the names of the selected adapters, without listing the adapters if the selector
+    /// picks a single adapter by name.
+    pub fn names(&self) -> io::Result<Vec<String>> {
+        match self.single_name() {
+            Some(name) => Ok(vec![name.to_string()]),
+            None => Ok(self
+                .resolve()?
+                .into_iter()
+                .map(|adapter| adapter.name)
+                .collect()),
+        }
+    }
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`InterfaceSelector`] to enable easy printing.
+impl std::fmt::Display for InterfaceSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.matcher {
+            InterfaceMatch::Name(name) => write!(f, "{name}")?,
+            InterfaceMatch::Mac(mac) => write!(f, "mac:{mac}")?,
+            InterfaceMatch::Description(description) => write!(f, "description:{description}")?,
+            InterfaceMatch::Guid(guid) => write!(f, "guid:{guid}")?,
+        }
+        if self.all {
+            write!(f, " (all)")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for InterfaceSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let matcher = match s.split_once(':') {
+            Some(("name", name)) => InterfaceMatch::Name(name.to_string()),
+            Some(("mac", mac)) => InterfaceMatch::Mac(mac.to_string()),
+            Some(("description", description)) => {
+                InterfaceMatch::Description(description.to_string())
+            }
+            Some(("guid", guid)) => InterfaceMatch::Guid(guid.to_string()),
+            _ => InterfaceMatch::Name(s.to_string()),
+        };
+
+        match &matcher {
+            InterfaceMatch::Name(value)
+            | InterfaceMatch::Mac(value)
+            | InterfaceMatch::Description(value)
+            | InterfaceMatch::Guid(value)
+                if value.is_empty() =>
+            {
+                Err(format!("interface selector {s:?} has no value"))
+            }
+            _ => Ok(Self::new(matcher)),
+        }
+    }
+}
+
+impl TryFrom<String> for InterfaceSelector {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+fn normalize_mac(mac: &str) -> String {
+    mac.to_ascii_lowercase().replace('-', ":")
+}
+
+fn normalize_guid(guid: &str) -> String {
+    guid.trim_matches(|c| c == '{' || c == '}')
+        .to_ascii_lowercase()
+}
@@ -0,0 +1,76 @@
+use std::io;
+
+use windows::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, ERROR_SUCCESS};
+use windows::Win32::NetworkManagement::IpHelper::{
+    GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER, GAA_FLAG_SKIP_MULTICAST, GetAdaptersAddresses,
+    IP_ADAPTER_ADDRESSES_LH,
+};
+use windows::Win32::NetworkManagement::Ndis::IfOperStatusUp;
+
+use crate::adapter::Adapter;
+
+/// `AF_UNSPEC`, requesting adapters with either IPv4 or IPv6 addresses.
+const AF_UNSPEC: u32 = 0;
+
+/// `IF_TYPE_SOFTWARE_LOOPBACK`, the interface type of the loopback adapter.
+const IF_TYPE_SOFTWARE_LOOPBACK: u32 = 24;
+
+/// `IF_TYPE_IEEE80211`, the interface type of wireless adapters.
+const IF_TYPE_IEEE80211: u32 = 71;
+
+/// Number of attempts made if the adapter list grows between sizing and filling the buffer.
+const MAX_ATTEMPTS: usize = 3;
+
+/// Returns the non-loopback adapters reported by `GetAdaptersAddresses`, sorted by name.
+pub fn adapters() -> io::Result<Vec<Adapter>> {
+    let flags = GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST | GAA_FLAG_SKIP_DNS_SERVER;
+    let mut size = 16 * 1024u32;
+
+    for _ in 0..MAX_ATTEMPTS {
+        // u64 elements keep the buffer aligned for IP_ADAPTER_ADDRESSES_LH.
+        let mut buffer = vec![0u64; (size as usize).div_ceil(8)];
+        let first = buffer.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES_LH;
+
+        let result =
+            unsafe { GetAdaptersAddresses(AF_UNSPEC, flags, None, Some(first), &mut size) };
+
+        if result == ERROR_BUFFER_OVERFLOW.0 {
+            continue;
+        }
+        if result != ERROR_SUCCESS.0 {
+            return Err(io::Error::from_raw_os_error(result as i32));
+        }
+
+        let mut adapters = Vec::new();
+        let mut current = first as *const IP_ADAPTER_ADDRESSES_LH;
+        while let Some(entry) = unsafe { current.as_ref() } {
+            if entry.IfType != IF_TYPE_SOFTWARE_LOOPBACK {
+                adapters.push(adapter(entry));
+            }
+            current = entry.Next;
+        }
+        adapters.sort_by(|a, b| a.name.cmp(&b.name));
+        return Ok(adapters);
+    }
+
+    Err(io::Error::from_raw_os_error(ERROR_BUFFER_OVERFLOW.0 as i32))
+}
+
+fn adapter(entry: &IP_ADAPTER_ADDRESSES_LH) -> Adapter {
+    let mac_len = (entry.PhysicalAddressLength as usize).min(entry.PhysicalAddress.len());
+    let mac = &entry.PhysicalAddress[..mac_len];
+
+    Adapter {
+        name: unsafe { entry.FriendlyName.to_string() }.unwrap_or_default(),
+        description: unsafe { entry.Description.to_string() }.ok(),
+        mac: (!mac.is_empty()).then(|| {
+            mac.iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<Vec<_>>()
+                .join(":")
+        }),
+        guid: unsafe { entry.AdapterName.to_string() }.ok(),
+        up: entry.OperStatus == IfOperStatusUp,
+        wireless: entry.IfType == IF_TYPE_IEEE80211,
+    }
+}
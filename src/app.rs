@@ -3,11 +3,27 @@
 //! This module defines the [`NetworkApp`] struct, which utilizes the [`InternetConnectivity`] and [`NetworkManager`] traits to monitor and manage network connectivity.
 //! ! It provides functionality to poll the network status and attempt reconnections when necessary.
 
-use crate::internet_connectivity::InternetConnectivity;
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+use tracing::{debug, info, warn};
+
+use crate::cancellation::CancellationToken;
+use crate::diagnosis::{
+    DiagnosisReport, Layer, LayerCheck, LayerResult, LayerStatus, NetworkDiagnostics,
+};
+use crate::error::Result;
+use crate::events::{EventSubscriber, NetworkEvent, ProbeKind};
+use crate::hysteresis::{Debouncer, Hysteresis, LinkState, Transition};
+use crate::internet_connectivity::{
+    ConnectionQuality, InternetAccess, InternetConnectivity, QualityIssue, QualityReport,
+    QualityThresholds,
+};
 use crate::network_manager::NetworkManager;
+use crate::remediation::{RemediationAttempt, RemediationLadder};
 
 /// Represents the network status.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum NetworkStatus {
     /// Connected to both network and internet
     Connected,
@@ -17,6 +33,29 @@ pub enum NetworkStatus {
 
     /// Not connected to network
     Disconnected,
+
+    /// Connected to network, but traffic is intercepted by a captive portal which requires logging in
+    CaptivePortal {
+        /// The login page of the portal, if it could be determined
+        login_url: Option<String>,
+    },
+
+    /// Connected to both network and internet, but the connection quality exceeds the thresholds
+    Degraded {
+        /// The ways in which the connection quality exceeded the thresholds
+        issues: Vec<QualityIssue>,
+    },
+}
+
+impl NetworkStatus {
+    /// Returns `true` if the internet is unavailable, which is the case for every status other
+    /// than [`NetworkStatus::Connected`] and [`NetworkStatus::Degraded`].
+    pub fn is_outage(&self) -> bool {
+        !matches!(
+            self,
+            NetworkStatus::Connected | NetworkStatus::Degraded { .. }
+        )
+    }
 }
 
 /// Implementing the [`std::fmt::Display`] trait for [`NetworkStatus`] to enable easy printing.
@@ -26,10 +65,126 @@ impl std::fmt::Display for NetworkStatus {
             NetworkStatus::Connected => write!(f, "Connected"),
             NetworkStatus::NetworkOnly => write!(f, "NetworkOnly"),
             NetworkStatus::Disconnected => write!(f, "Disconnected"),
+            NetworkStatus::CaptivePortal { login_url: None } => write!(f, "CaptivePortal"),
+            NetworkStatus::CaptivePortal {
+                login_url: Some(url),
+            } => write!(f, "CaptivePortal ({url})"),
+            NetworkStatus::Degraded { issues } => {
+                let issues: Vec<String> = issues.iter().map(ToString::to_string).collect();
+                write!(f, "Degraded ({})", issues.join(", "))
+            }
+        }
+    }
+}
+
+/// The details of a single poll, returned by [`NetworkApp::poll_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PollReport {
+    /// The debounced network status.
+    pub status: NetworkStatus,
+
+    /// The raw result of the internet check, if it was performed.
+    pub internet: Option<bool>,
+
+    /// The debounced internet state after this poll.
+    pub state: LinkState,
+
+    /// The change of debounced state caused by this poll, if any.
+    pub transition: Option<Transition>,
+
+    /// The remediation attempted during this poll, if any.
+    pub remediation: Option<RemediationAttempt>,
+
+    /// The verification performed after a successful remediation, if any.
+    pub verification: Option<VerificationResult>,
+
+    /// The connection quality measured during this poll, if it was measured.
+    pub quality: Option<QualityReport>,
+}
+
+/// Settings for re-probing the internet after a remediation reports success.
+///
+/// Remediations such as reconnecting often only initiate a connection, so the internet is
+/// re-probed every `interval` until it returns or `timeout` passes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecoveryVerification {
+    /// The longest time to wait for the internet to return.
+    pub timeout: Duration,
+
+    /// Time to wait between probes.
+    pub interval: Duration,
+}
+
+impl Default for RecoveryVerification {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            interval: Duration::from_secs(2),
         }
     }
 }
 
+/// The result of verifying the internet returned after a remediation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerificationResult {
+    /// Whether the internet returned before the timeout.
+    pub recovered: bool,
+
+    /// Time from the end of the remediation to the internet returning, or to giving up.
+    pub elapsed: Duration,
+
+    /// Number of probes performed.
+    pub probes: u32,
+}
+
+/// Summary of a supervision run started by [`NetworkApp::run`] or [`NetworkApp::run_until`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunSummary {
+    /// Total number of polls performed.
+    pub polls: u64,
+
+    /// Number of polls that returned [`NetworkStatus::Connected`].
+    pub connected: u64,
+
+    /// Number of polls that returned [`NetworkStatus::NetworkOnly`].
+    pub network_only: u64,
+
+    /// Number of polls that returned [`NetworkStatus::Disconnected`].
+    pub disconnected: u64,
+
+    /// Number of polls that returned [`NetworkStatus::CaptivePortal`].
+    pub captive_portal: u64,
+
+    /// Number of polls that returned [`NetworkStatus::Degraded`].
+    pub degraded: u64,
+
+    /// Number of times the status differed from the previous poll.
+    pub status_changes: u64,
+
+    /// Status returned by the most recent poll, if any poll was performed.
+    pub last_status: Option<NetworkStatus>,
+
+    /// Wall-clock time spent in the supervision loop.
+    pub elapsed: Duration,
+}
+
+impl RunSummary {
+    fn record(&mut self, status: &NetworkStatus) {
+        self.polls += 1;
+        match status {
+            NetworkStatus::Connected => self.connected += 1,
+            NetworkStatus::NetworkOnly => self.network_only += 1,
+            NetworkStatus::Disconnected => self.disconnected += 1,
+            NetworkStatus::CaptivePortal { .. } => self.captive_portal += 1,
+            NetworkStatus::Degraded { .. } => self.degraded += 1,
+        }
+        if self.last_status.as_ref().is_some_and(|last| last != status) {
+            self.status_changes += 1;
+        }
+        self.last_status = Some(status.clone());
+    }
+}
+
 /// The main application struct that uses the [`InternetConnectivity`] and [`NetworkManager`] traits.
 ///
 /// # Type Parameters
@@ -38,6 +193,16 @@ impl std::fmt::Display for NetworkStatus {
 pub struct NetworkApp<C: InternetConnectivity, M: NetworkManager> {
     checker: C,
     manager: M,
+    ladder: RemediationLadder,
+    debouncer: RefCell<Debouncer>,
+    verification: RecoveryVerification,
+    quality: Option<Box<dyn ConnectionQuality>>,
+    thresholds: QualityThresholds,
+    remediate_degraded: bool,
+    degraded: Cell<bool>,
+    diagnostics: Option<Box<dyn NetworkDiagnostics>>,
+    subscribers: Vec<Box<dyn EventSubscriber>>,
+    last_status: RefCell<Option<NetworkStatus>>,
 }
 
 /// Implementation of the [`NetworkApp`] struct.
@@ -48,52 +213,612 @@ impl<C: InternetConnectivity, M: NetworkManager> NetworkApp<C, M> {
     /// - `checker`: An instance of a type that implements the [`InternetConnectivity`] trait.
     /// - `manager`: An instance of a type that implements the [`NetworkManager`] trait
     pub fn new(checker: C, manager: M) -> Self {
-        Self { checker, manager }
+        Self {
+            checker,
+            manager,
+            ladder: RemediationLadder::new(),
+            debouncer: RefCell::new(Debouncer::new(Hysteresis::new())),
+            verification: RecoveryVerification::default(),
+            quality: None,
+            thresholds: QualityThresholds::default(),
+            remediate_degraded: false,
+            degraded: Cell::new(false),
+            diagnostics: None,
+            subscribers: Vec::new(),
+            last_status: RefCell::new(None),
+        }
     }
 
-    /// Polls the network status and attempts to reconnect if necessary.
+    /// Sets how the internet is re-probed after a remediation reports success.
+    pub fn with_recovery_verification(mut self, verification: RecoveryVerification) -> Self {
+        self.verification = verification;
+        self
+    }
+
+    /// Sets the [`Hysteresis`] used to debounce raw internet checks before declaring an outage or recovery.
+    pub fn with_hysteresis(self, hysteresis: Hysteresis) -> Self {
+        self.debouncer.replace(Debouncer::new(hysteresis));
+        self
+    }
+
+    /// Sets the [`RemediationLadder`] to escalate through when the internet is unavailable.
     ///
-    /// Returns the current [`NetworkStatus`].
-    pub fn poll(&self) -> NetworkStatus {
-        match (
-            self.checker.is_connected_to_network(),
-            self.checker.is_connected_to_internet(),
-        ) {
-            (true, true) => NetworkStatus::Connected,
-            (false, _) => NetworkStatus::Disconnected,
-            (true, false) => {
-                println!("Network connected, but no internet, attempting reconnect...");
-                let success = self.manager.reconnect();
-
-                match success {
-                    true => {
-                        println!("Reconnected successfully");
-                        NetworkStatus::Connected
-                    }
-                    false => {
-                        println!("Reconnect failed");
-                        NetworkStatus::NetworkOnly
-                    }
+    /// Reconnecting with the [`NetworkManager`] is always the first rung, followed by the rungs of `ladder`.
+    pub fn with_remediation_ladder(mut self, ladder: RemediationLadder) -> Self {
+        self.ladder = ladder;
+        self
+    }
+
+    /// Sets the [`ConnectionQuality`] probe measured while the internet is available, and the
+    /// [`QualityThresholds`] beyond which the connection is reported as [`NetworkStatus::Degraded`].
+    ///
+    /// # Arguments
+    /// - `probe`: The probe measuring round-trip times, jitter and loss.
+    /// - `thresholds`: The limits beyond which the connection is degraded.
+    pub fn with_quality_probe(
+        mut self,
+        probe: impl ConnectionQuality + 'static,
+        thresholds: QualityThresholds,
+    ) -> Self {
+        self.quality = Some(Box::new(probe));
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Sets whether a degraded connection is remediated the same way as one without internet.
+    ///
+    /// Disabled by default, so a degraded connection is only reported.
+    pub fn with_degraded_remediation(mut self, remediate: bool) -> Self {
+        self.remediate_degraded = remediate;
+        self
+    }
+
+    /// Sets the [`NetworkDiagnostics`] used by [`NetworkApp::diagnose`] to check the link, address,
+    /// gateway and DNS layers.
+    pub fn with_diagnostics(mut self, diagnostics: impl NetworkDiagnostics + 'static) -> Self {
+        self.diagnostics = Some(Box::new(diagnostics));
+        self
+    }
+
+    /// Registers an [`EventSubscriber`] to receive the [`NetworkEvent`]s emitted while polling.
+    ///
+    /// Any number of subscribers can be registered; each receives every event, in the order they
+    /// were registered.
+    pub fn with_subscriber(mut self, subscriber: impl EventSubscriber + 'static) -> Self {
+        self.subscribers.push(Box::new(subscriber));
+        self
+    }
+
+    /// Carries on from where `previous` left off, taking over its debounced internet state,
+    /// remediation rung, last status and subscribers.
+    ///
+    /// Used when the app is rebuilt with new settings, such as after reloading the configuration,
+    /// so an ongoing outage is neither reported again nor remediated from the first rung, and
+    /// subscribers keep receiving events without being recreated. Subscribers registered on this
+    /// app receive events after those of `previous`.
+    pub fn resume(mut self, previous: Self) -> Self {
+        let settings = self.debouncer.get_mut().settings();
+        self.debouncer = RefCell::new(previous.debouncer.into_inner().with_settings(settings));
+        self.ladder.resume_at(previous.ladder.level());
+        self.degraded = previous.degraded;
+        self.last_status = previous.last_status;
+
+        let mut subscribers = previous.subscribers;
+        subscribers.append(&mut self.subscribers);
+        self.subscribers = subscribers;
+        self
+    }
+
+    /// Returns the [`RemediationLadder`] used when the internet is unavailable.
+    pub fn remediation_ladder(&self) -> &RemediationLadder {
+        &self.ladder
+    }
+
+    /// Checks the network status once, without debouncing or remediating.
+    ///
+    /// Returns the raw [`NetworkStatus`] reported by the [`InternetConnectivity`] checker.
+    pub fn check(&self) -> NetworkStatus {
+        if !self.checker.is_connected_to_network() {
+            return NetworkStatus::Disconnected;
+        }
+
+        match self.checker.internet_access() {
+            InternetAccess::CaptivePortal(portal) => {
+                return NetworkStatus::CaptivePortal {
+                    login_url: portal.login_url,
+                };
+            }
+            InternetAccess::Unreachable => return NetworkStatus::NetworkOnly,
+            InternetAccess::Reachable => {}
+        }
+
+        match self.measure_quality() {
+            Some((_, issues)) if !issues.is_empty() => NetworkStatus::Degraded { issues },
+            _ => NetworkStatus::Connected,
+        }
+    }
+
+    /// Checks each layer of the network stack in order, from the link up to the internet, without
+    /// debouncing or remediating.
+    ///
+    /// The link, address, gateway and DNS layers are checked with the [`NetworkDiagnostics`] set by
+    /// [`NetworkApp::with_diagnostics`]. Without diagnostics, the link layer falls back to the
+    /// network check of the [`InternetConnectivity`] checker, and the address, gateway and DNS
+    /// layers are skipped. The internet layer fails if a captive portal is detected or the internet
+    /// check fails.
+    ///
+    /// Once a layer fails, the layers above it are skipped.
+    ///
+    /// Returns a [`DiagnosisReport`] naming the first failing layer, if any, with the evidence for each layer.
+    pub fn diagnose(&self) -> DiagnosisReport {
+        let mut report = DiagnosisReport::default();
+        let mut failed = None;
+
+        for layer in Layer::ALL {
+            if let Some(failed) = failed {
+                report.layers.push(LayerResult::skipped(
+                    layer,
+                    format!("not checked, {failed} failed"),
+                ));
+                continue;
+            }
+
+            let result = match self.check_layer(layer) {
+                Some(check) => LayerResult::checked(layer, check),
+                None => LayerResult::skipped(layer, "not available"),
+            };
+            debug!(%layer, status = %result.status, evidence = %result.evidence, "Layer checked");
+            if result.status == LayerStatus::Failed {
+                failed = Some(layer);
+            }
+            report.layers.push(result);
+        }
+
+        report
+    }
+
+    /// Checks a single layer, returning `None` if no check is available for it.
+    fn check_layer(&self, layer: Layer) -> Option<LayerCheck> {
+        let diagnostics = self.diagnostics.as_ref();
+        match layer {
+            Layer::Link => Some(match diagnostics {
+                Some(diagnostics) => diagnostics.check_link(),
+                None if self.checker.is_connected_to_network() => {
+                    LayerCheck::passed("connected to a network")
+                }
+                None => LayerCheck::failed("not connected to a network"),
+            }),
+            Layer::Address => diagnostics.map(|diagnostics| diagnostics.check_address()),
+            Layer::Gateway => diagnostics.map(|diagnostics| diagnostics.check_gateway()),
+            Layer::Dns => diagnostics.map(|diagnostics| diagnostics.check_dns()),
+            Layer::Internet => Some(match self.checker.internet_access() {
+                InternetAccess::CaptivePortal(portal) => {
+                    LayerCheck::failed(match portal.login_url {
+                        Some(url) => format!("captive portal, log in at {url}"),
+                        None => "captive portal".to_string(),
+                    })
                 }
+                InternetAccess::Reachable => LayerCheck::passed("internet reachable"),
+                InternetAccess::Unreachable => LayerCheck::failed("internet unreachable"),
+            }),
+        }
+    }
+
+    /// Measures the connection quality, if a probe is set, returning the report and the issues
+    /// exceeding the thresholds.
+    fn measure_quality(&self) -> Option<(QualityReport, Vec<QualityIssue>)> {
+        let report = self.quality.as_ref()?.measure();
+        let issues = self.thresholds.issues(&report);
+        Some((report, issues))
+    }
+
+    /// Reconnects with the [`NetworkManager`] regardless of the network status, then verifies the
+    /// internet returns as described by [`RecoveryVerification`].
+    ///
+    /// Returns the [`VerificationResult`], or the error the reconnect failed with.
+    pub fn reconnect(&self) -> Result<VerificationResult> {
+        self.manager.reconnect()?;
+        Ok(self.verify_recovery(&CancellationToken::new()))
+    }
+
+    /// Polls the network status and attempts to remediate if necessary.
+    ///
+    /// Returns the current [`NetworkStatus`]. See [`NetworkApp::poll_report`] for details.
+    pub fn poll(&self) -> NetworkStatus {
+        self.poll_report().status
+    }
+
+    /// Polls the network status, attempts to remediate if necessary, and reports the details.
+    ///
+    /// Raw internet checks are debounced using the [`Hysteresis`] settings, and remediation is only
+    /// attempted once an outage has been declared. Each consecutive poll during an outage attempts
+    /// the next rung of the [`RemediationLadder`], starting with reconnecting. The ladder returns to
+    /// the first rung once recovery is declared.
+    ///
+    /// A remediation which reports success is only trusted once the internet is re-probed
+    /// successfully, as described by [`RecoveryVerification`]. The probes are debounced like any
+    /// other internet check, so recovery is only declared once the [`Hysteresis`] settings allow.
+    ///
+    /// A captive portal is reported as [`NetworkStatus::CaptivePortal`] without reconnecting, as
+    /// reconnecting cannot log in to the portal.
+    ///
+    /// While the internet is available, the connection quality is measured if a probe is set, and
+    /// reported as [`NetworkStatus::Degraded`] if it exceeds the thresholds. A degraded connection
+    /// is only remediated if enabled with [`NetworkApp::with_degraded_remediation`], climbing the
+    /// same ladder as an outage; the quality is measured again on the next poll.
+    ///
+    /// Every step is reported to the subscribers registered with [`NetworkApp::with_subscriber`].
+    ///
+    /// Returns a [`PollReport`] with the debounced status and the raw result it was derived from.
+    pub fn poll_report(&self) -> PollReport {
+        self.poll_cancellable(&CancellationToken::new())
+    }
+
+    /// Polls like [`NetworkApp::poll_report`], giving up verifying a remediation once `token` is
+    /// cancelled.
+    fn poll_cancellable(&self, token: &CancellationToken) -> PollReport {
+        self.emit(NetworkEvent::PollStarted);
+        let report = self.poll_checks(token);
+        self.record_status(&report.status);
+        report
+    }
+
+    /// Records the status of the current poll, emitting [`NetworkEvent::StatusChanged`] if it
+    /// differs from the last status recorded.
+    ///
+    /// When an outage starts, the layers are diagnosed and reported with
+    /// [`NetworkEvent::Diagnosed`], if diagnostics are set and anyone is subscribed.
+    fn record_status(&self, status: &NetworkStatus) {
+        let previous = self.last_status.replace(Some(status.clone()));
+        if previous.as_ref() == Some(status) {
+            return;
+        }
+
+        let outage_started =
+            status.is_outage() && !previous.as_ref().is_some_and(NetworkStatus::is_outage);
+        self.emit(NetworkEvent::StatusChanged {
+            from: previous,
+            to: status.clone(),
+        });
+        if outage_started && self.diagnostics.is_some() && !self.subscribers.is_empty() {
+            self.emit(NetworkEvent::Diagnosed {
+                report: self.diagnose(),
+            });
+        }
+    }
+
+    /// Performs the checks and remediation of a single poll.
+    fn poll_checks(&self, token: &CancellationToken) -> PollReport {
+        let mut report = PollReport {
+            status: NetworkStatus::Disconnected,
+            internet: None,
+            state: self.debouncer.borrow().state(),
+            transition: None,
+            remediation: None,
+            verification: None,
+            quality: None,
+        };
+
+        let network = self.checker.is_connected_to_network();
+        self.emit_probe(ProbeKind::Network, network);
+        if !network {
+            return report;
+        }
+
+        let access = self.checker.internet_access();
+        self.emit_probe(
+            ProbeKind::CaptivePortal,
+            !matches!(access, InternetAccess::CaptivePortal(_)),
+        );
+        let internet = match access {
+            InternetAccess::CaptivePortal(portal) => {
+                warn!(
+                    login_url = portal.login_url.as_deref(),
+                    "Captive portal detected, not reconnecting"
+                );
+                report.status = NetworkStatus::CaptivePortal {
+                    login_url: portal.login_url,
+                };
+                return report;
             }
+            InternetAccess::Reachable => true,
+            InternetAccess::Unreachable => false,
+        };
+        self.emit_probe(ProbeKind::Internet, internet);
+        report.internet = Some(internet);
+        report.transition = self.record_internet(internet);
+        report.state = self.debouncer.borrow().state();
+
+        if report.state == LinkState::Up {
+            report.status = NetworkStatus::Connected;
+            return self.poll_quality(report, token);
+        }
+        self.degraded.set(false);
+
+        if internet {
+            debug!("Internet responding, waiting to declare recovery");
+            report.status = NetworkStatus::NetworkOnly;
+            return report;
+        }
+
+        warn!(
+            rung = self.ladder.level(),
+            "Network connected, but no internet, attempting remediation"
+        );
+        report.status = NetworkStatus::NetworkOnly;
+        self.remediate(report, token)
+    }
+
+    /// Feeds a raw internet check to the [`Debouncer`], returning the [`Transition`] it caused.
+    ///
+    /// The [`RemediationLadder`] returns to the first rung once recovery is declared.
+    fn record_internet(&self, internet: bool) -> Option<Transition> {
+        let transition = self.debouncer.borrow_mut().record(internet)?;
+        info!(
+            from = %transition.from,
+            to = %transition.to,
+            "Internet state changed"
+        );
+        if transition.to == LinkState::Up {
+            self.ladder.reset();
         }
+        Some(transition)
+    }
+
+    /// Measures the connection quality while the internet is available, reporting and optionally
+    /// remediating a degraded connection.
+    fn poll_quality(&self, mut report: PollReport, token: &CancellationToken) -> PollReport {
+        let Some((quality, issues)) = self.measure_quality() else {
+            return report;
+        };
+        debug!(%quality, "Connection quality measured");
+        self.emit_probe(ProbeKind::Quality, issues.is_empty());
+        report.quality = Some(quality);
+
+        if issues.is_empty() {
+            if self.degraded.replace(false) {
+                info!("Connection quality restored");
+                self.ladder.reset();
+            }
+            return report;
+        }
+
+        self.degraded.set(true);
+        report.status = NetworkStatus::Degraded { issues };
+        if !self.remediate_degraded {
+            warn!(status = %report.status, "Connection degraded");
+            return report;
+        }
+
+        warn!(
+            status = %report.status,
+            rung = self.ladder.level(),
+            "Connection degraded, attempting remediation"
+        );
+        self.remediate(report, token)
+    }
+
+    /// Attempts the next rung of the [`RemediationLadder`] and verifies the internet returns.
+    ///
+    /// The status of `report` is kept if the remediation fails or the internet does not return,
+    /// and becomes [`NetworkStatus::Connected`] once it does. A degraded connection is measured
+    /// again instead, and stays degraded until its quality is within the thresholds.
+    ///
+    /// The status is recorded before remediating, so subscribers learn of an outage before the
+    /// remediation it causes. The verification probes are fed to the [`Debouncer`], so the state
+    /// and transition of `report` include any recovery they declare.
+    fn remediate(&self, mut report: PollReport, token: &CancellationToken) -> PollReport {
+        self.record_status(&report.status);
+        self.emit(NetworkEvent::RemediationAttempted {
+            rung: self.ladder.level(),
+            action: self.ladder.next_action(&self.manager).name().to_string(),
+        });
+        let attempt = self.ladder.attempt(&self.manager);
+
+        if let Err(e) = &attempt.result {
+            warn!(
+                rung = attempt.rung,
+                action = %attempt.action,
+                step = e.step().map(tracing::field::display),
+                error = %e,
+                "Remediation failed"
+            );
+            self.emit(NetworkEvent::RemediationResult {
+                attempt: attempt.clone(),
+                verification: None,
+            });
+            report.remediation = Some(attempt);
+            return report;
+        }
+
+        info!(
+            rung = attempt.rung,
+            action = %attempt.action,
+            "Remediation succeeded, verifying internet"
+        );
+        let verification = self.verify_recovery(token);
+        let state = self.debouncer.borrow().state();
+        if state != report.state {
+            report.transition = Some(Transition {
+                from: report.state,
+                to: state,
+            });
+            report.state = state;
+        }
+
+        match verification.recovered {
+            true => {
+                info!(
+                    elapsed_ms = verification.elapsed.as_millis() as u64,
+                    probes = verification.probes,
+                    "Internet restored"
+                );
+                report = match report.status {
+                    NetworkStatus::Degraded { .. } => self.verify_quality(report),
+                    _ => PollReport {
+                        status: NetworkStatus::Connected,
+                        ..report
+                    },
+                };
+            }
+            false => {
+                warn!(
+                    elapsed_ms = verification.elapsed.as_millis() as u64,
+                    probes = verification.probes,
+                    "Internet not restored"
+                );
+            }
+        }
+        self.emit(NetworkEvent::RemediationResult {
+            attempt: attempt.clone(),
+            verification: Some(verification),
+        });
+        report.verification = Some(verification);
+        report.remediation = Some(attempt);
+        report
+    }
+
+    /// Measures the connection quality again after remediating a degraded connection, which only
+    /// becomes [`NetworkStatus::Connected`] once the quality is within the thresholds.
+    fn verify_quality(&self, mut report: PollReport) -> PollReport {
+        let Some((quality, issues)) = self.measure_quality() else {
+            report.status = NetworkStatus::Connected;
+            return report;
+        };
+        debug!(%quality, "Connection quality measured after remediation");
+        self.emit_probe(ProbeKind::Quality, issues.is_empty());
+        report.quality = Some(quality);
+
+        match issues.is_empty() {
+            true => {
+                info!("Connection quality restored");
+                self.degraded.set(false);
+                self.ladder.reset();
+                report.status = NetworkStatus::Connected;
+            }
+            false => {
+                report.status = NetworkStatus::Degraded { issues };
+                warn!(status = %report.status, "Connection still degraded");
+            }
+        }
+        report
+    }
+
+    /// Sends `event` to every subscriber, in the order they were registered.
+    fn emit(&self, event: NetworkEvent) {
+        for subscriber in &self.subscribers {
+            subscriber.on_event(&event);
+        }
+    }
+
+    fn emit_probe(&self, probe: ProbeKind, passed: bool) {
+        self.emit(NetworkEvent::ProbeResult { probe, passed });
+    }
+
+    /// Re-probes the internet until recovery is declared, the verification timeout passes or
+    /// `token` is cancelled.
+    ///
+    /// Each probe is fed to the [`Debouncer`], so the internet has only returned once it is
+    /// [`LinkState::Up`] after a successful probe.
+    fn verify_recovery(&self, token: &CancellationToken) -> VerificationResult {
+        let started = Instant::now();
+        let deadline = started + self.verification.timeout;
+        let mut probes = 0;
+
+        loop {
+            probes += 1;
+            let internet = self.checker.is_connected_to_internet();
+            self.record_internet(internet);
+            let recovered = internet && self.debouncer.borrow().state() == LinkState::Up;
+
+            let now = Instant::now();
+            let result = VerificationResult {
+                recovered,
+                elapsed: now - started,
+                probes,
+            };
+            if recovered
+                || now >= deadline
+                || token.wait_timeout(self.verification.interval.min(deadline - now))
+            {
+                return result;
+            }
+        }
+    }
+
+    /// Polls the network status repeatedly until `token` is cancelled.
+    ///
+    /// # Arguments
+    /// - `interval`: Time to wait between the end of one poll and the start of the next.
+    /// - `token`: A [`CancellationToken`] used to stop the loop, including while it is waiting.
+    ///
+    /// Returns a [`RunSummary`] describing the polls performed.
+    pub fn run(&self, interval: Duration, token: &CancellationToken) -> RunSummary {
+        self.run_until(interval, token, |_| false)
+    }
+
+    /// Polls the network status repeatedly until `token` is cancelled or `stop` returns `true`.
+    ///
+    /// # Arguments
+    /// - `interval`: Time to wait between the end of one poll and the start of the next.
+    /// - `token`: A [`CancellationToken`] used to stop the loop, including while it is waiting.
+    /// - `stop`: Called with the [`NetworkStatus`] of every poll; returning `true` ends the loop.
+    ///
+    /// Returns a [`RunSummary`] describing the polls performed.
+    pub fn run_until<F>(
+        &self,
+        interval: Duration,
+        token: &CancellationToken,
+        mut stop: F,
+    ) -> RunSummary
+    where
+        F: FnMut(&NetworkStatus) -> bool,
+    {
+        let started = Instant::now();
+        let mut summary = RunSummary::default();
+
+        while !token.is_cancelled() {
+            let status = self.poll_cancellable(token).status;
+            summary.record(&status);
+
+            if stop(&status) || token.wait_timeout(interval) {
+                break;
+            }
+        }
+
+        summary.elapsed = started.elapsed();
+        summary
     }
 }
 
 cfg_if::cfg_if! {
     if #[cfg(target_os = "windows")] {
+        use crate::internet_connectivity::CaptivePortalDetector;
         use crate::internet_connectivity::WindowsInternetConnectivity;
         use crate::network_manager::WindowsNetworkManager;
         use crate::network_manager::WlanApiImpl;
 
         /// Default implementation of [`NetworkApp`] for Windows OS.
-        impl Default for NetworkApp<WindowsInternetConnectivity, WindowsNetworkManager<WlanApiImpl>> {
+        impl Default for NetworkApp<CaptivePortalDetector<WindowsInternetConnectivity>, WindowsNetworkManager<WlanApiImpl>> {
             fn default() -> Self {
-                Self::new(WindowsInternetConnectivity {}, WindowsNetworkManager::<WlanApiImpl>::new()
+                Self::new(CaptivePortalDetector::new(WindowsInternetConnectivity::default()), WindowsNetworkManager::<WlanApiImpl>::new()
  )
             }
         }
+    } else if #[cfg(target_os = "linux")] {
+        use crate::internet_connectivity::CaptivePortalDetector;
+        use crate::diagnosis::LinuxDiagnostics;
+        use crate::internet_connectivity::LinuxInternetConnectivity;
+        use crate::network_manager::DbusNetworkManager;
+
+        /// Default implementation of [`NetworkApp`] for Linux, using NetworkManager over D-Bus.
+        impl Default for NetworkApp<CaptivePortalDetector<LinuxInternetConnectivity>, DbusNetworkManager> {
+            fn default() -> Self {
+                Self::new(CaptivePortalDetector::new(LinuxInternetConnectivity::new()), DbusNetworkManager::new())
+                    .with_diagnostics(LinuxDiagnostics::new())
+            }
+        }
     } else {
-        compile_error!("Unsupported OS: this crate only supports Windows.");
+        compile_error!("Unsupported OS: this crate only supports Windows and Linux.");
     }
 }
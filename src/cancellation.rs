@@ -0,0 +1,56 @@
+//! Module for cooperative cancellation of long-running loops.
+//!
+//! This module defines the [`CancellationToken`] struct, which is used by [`crate::app::NetworkApp::run`]
+//! to stop the supervision loop cleanly, even while it is sleeping between polls.
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// A cloneable token used to request that a long-running loop stops.
+///
+/// All clones share the same state, so cancelling one clone cancels them all.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    inner: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl CancellationToken {
+    /// Creates a new, not yet cancelled, [`CancellationToken`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation, waking any thread currently waiting on this token.
+    pub fn cancel(&self) {
+        let (lock, condvar) = &*self.inner;
+        *lock.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        condvar.notify_all();
+    }
+
+    /// Returns `true` if cancellation has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        *self.inner.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Blocks for up to `timeout`, returning early if the token is cancelled.
+    ///
+    /// Returns `true` if the token was cancelled.
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let (lock, condvar) = &*self.inner;
+        let deadline = Instant::now() + timeout;
+        let mut cancelled = lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        while !*cancelled {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            cancelled = condvar
+                .wait_timeout(cancelled, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+
+        *cancelled
+    }
+}
@@ -0,0 +1,336 @@
+//! Command-line interface of the `internet_reloader` binary.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use internet_reloader::adapter::InterfaceSelector;
+use internet_reloader::app::NetworkStatus;
+use internet_reloader::config::{Config, ConfigError, parse_duration};
+use internet_reloader::diagnosis::{DiagnosisReport, Layer};
+use internet_reloader::logging::LogFormat;
+use internet_reloader::network_manager::Backend;
+use internet_reloader::report::{Period, ReportFormat};
+use tracing::Level;
+
+/// Exit codes returned by every subcommand, shown at the end of `--help`.
+const EXIT_CODES: &str = "\
+Exit codes:
+  0  Connected, or the subcommand succeeded
+  1  The subcommand failed, such as a reconnect being rejected or an invalid configuration
+  2  Invalid arguments
+  3  Connected to a network, but not the internet
+  4  Not connected to a network
+  5  Connected to a network behind a captive portal
+  6  Connected to the internet, but the connection is degraded";
+
+/// Process exit codes, as documented in [`EXIT_CODES`].
+pub mod exit {
+    /// Connected, or the subcommand succeeded.
+    pub const SUCCESS: u8 = 0;
+
+    /// The subcommand failed.
+    pub const FAILURE: u8 = 1;
+
+    /// Connected to a network, but not the internet.
+    pub const NETWORK_ONLY: u8 = 3;
+
+    /// Not connected to a network.
+    pub const DISCONNECTED: u8 = 4;
+
+    /// Connected to a network behind a captive portal.
+    pub const CAPTIVE_PORTAL: u8 = 5;
+
+    /// Connected to the internet, but the connection is degraded.
+    pub const DEGRADED: u8 = 6;
+}
+
+/// Monitors internet connectivity, and reconnects the network when the internet is unavailable.
+#[derive(Debug, Parser)]
+#[command(version, about, after_help = EXIT_CODES)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+
+    #[command(flatten)]
+    pub options: Options,
+}
+
+/// Options accepted by every subcommand.
+#[derive(Debug, Args)]
+pub struct Options {
+    /// TOML configuration file, reloaded by `watch` on change or SIGHUP
+    #[arg(short, long, global = true, env = "INTERNET_RELOADER_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Time between polls, such as `30s`, `500ms` or `2m` [default: 30s]
+    #[arg(short, long, global = true, value_parser = parse_duration)]
+    pub interval: Option<Duration>,
+
+    /// Backend used to reconnect the network [default: auto]
+    #[arg(short, long, global = true, value_enum)]
+    pub backend: Option<BackendArg>,
+
+    /// Interface to check and reconnect, by name or as `mac:ADDRESS`, `description:TEXT` or
+    /// `guid:GUID` [default: the first wireless interface]
+    #[arg(long, global = true)]
+    pub interface: Option<InterfaceSelector>,
+
+    /// Manage every interface matching `--interface`, rather than only the first
+    #[arg(long, global = true, requires = "interface")]
+    pub all_interfaces: bool,
+
+    /// Log more detail; repeat for more (`-v` debug, `-vv` trace)
+    #[arg(short, long, global = true, action = ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Log less detail; repeat for less (`-q` warnings, `-qq` errors)
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub quiet: u8,
+
+    /// Format of log output: text, json, journald or syslog
+    #[arg(long, global = true, env = "INTERNET_RELOADER_LOG_FORMAT", default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
+}
+
+impl Options {
+    /// Loads the configuration file, if any, then applies the options which override it.
+    pub fn load_config(&self) -> Result<Config, ConfigError> {
+        let mut config = match &self.config {
+            Some(path) => Config::load(path)?,
+            None => Config::default(),
+        };
+
+        if let Some(interval) = self.interval {
+            config.monitor.interval = interval;
+        }
+        if let Some(backend) = self.backend {
+            config.network.backend = backend.into();
+        }
+        if let Some(interface) = &self.interface {
+            config.network.interface = Some(interface.clone());
+        }
+        if self.all_interfaces {
+            config.network.all_interfaces = true;
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Returns the least severe log level to write, according to `--verbose` and `--quiet`.
+    pub fn level(&self) -> Level {
+        match (self.verbose, self.quiet) {
+            (0, 0) => Level::INFO,
+            (1, _) => Level::DEBUG,
+            (_, 0) => Level::TRACE,
+            (_, 1) => Level::WARN,
+            _ => Level::ERROR,
+        }
+    }
+}
+
+/// The subcommands of the binary.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Check the network once and print its status, without remediating
+    Status,
+
+    /// Check each layer from the link up to the internet, and print which layer fails first
+    Diagnose,
+
+    /// Supervise the network continuously, remediating outages
+    Watch {
+        /// Stop after this many polls, exiting with the status of the last one
+        #[arg(long)]
+        count: Option<u64>,
+    },
+
+    /// Reconnect the network now, then wait for the internet to return
+    Reconnect {
+        /// Longest time to wait for the internet to return, re-checking every interval
+        /// [default: the configured verification timeout]
+        #[arg(long, value_parser = parse_duration)]
+        timeout: Option<Duration>,
+    },
+
+    /// Run a single probe as configured, scoped to `--interface`, and print its raw results
+    Probe(ProbeArgs),
+
+    /// List the network adapters present on the system
+    Interfaces,
+
+    /// Report uptime from the history file, with availability, MTBF/MTTR and outage causes
+    Report(ReportArgs),
+}
+
+/// The values of `--backend`, mapped onto [`Backend`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BackendArg {
+    /// The default backend for the current OS
+    Auto,
+
+    /// NetworkManager over D-Bus
+    #[cfg(target_os = "linux")]
+    NetworkManager,
+
+    /// The wpa_supplicant control socket of the managed interface
+    #[cfg(target_os = "linux")]
+    WpaSupplicant,
+
+    /// The Windows WLAN API
+    #[cfg(target_os = "windows")]
+    Wlan,
+}
+
+impl From<BackendArg> for Backend {
+    fn from(backend: BackendArg) -> Self {
+        match backend {
+            BackendArg::Auto => Backend::Auto,
+            #[cfg(target_os = "linux")]
+            BackendArg::NetworkManager => Backend::NetworkManager,
+            #[cfg(target_os = "linux")]
+            BackendArg::WpaSupplicant => Backend::WpaSupplicant,
+            #[cfg(target_os = "windows")]
+            BackendArg::Wlan => Backend::Wlan,
+        }
+    }
+}
+
+/// Arguments of the `report` subcommand.
+#[derive(Debug, Args)]
+pub struct ReportArgs {
+    /// Length of each period availability is reported for, in UTC
+    #[arg(long, value_enum, default_value_t = PeriodArg::Daily)]
+    pub period: PeriodArg,
+
+    /// Format of the report: text, csv or html
+    #[arg(long, value_enum, default_value_t = FormatArg::Text)]
+    pub format: FormatArg,
+
+    /// How far back the report starts, such as `7d` or `12h`
+    #[arg(long, default_value = "30d", value_parser = parse_duration)]
+    pub since: Duration,
+
+    /// History file to read [default: the `[history] path` of the configuration]
+    #[arg(long)]
+    pub history: Option<PathBuf>,
+}
+
+/// The values of `report --period`, mapped onto [`Period`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PeriodArg {
+    /// Calendar days
+    Daily,
+
+    /// Weeks starting on Monday
+    Weekly,
+
+    /// Calendar months
+    Monthly,
+}
+
+impl From<PeriodArg> for Period {
+    fn from(period: PeriodArg) -> Self {
+        match period {
+            PeriodArg::Daily => Period::Daily,
+            PeriodArg::Weekly => Period::Weekly,
+            PeriodArg::Monthly => Period::Monthly,
+        }
+    }
+}
+
+/// The values of `report --format`, mapped onto [`ReportFormat`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FormatArg {
+    /// Aligned plain text
+    Text,
+
+    /// Tables of comma-separated values, separated by blank lines
+    Csv,
+
+    /// A self-contained HTML page
+    Html,
+}
+
+impl From<FormatArg> for ReportFormat {
+    fn from(format: FormatArg) -> Self {
+        match format {
+            FormatArg::Text => ReportFormat::Text,
+            FormatArg::Csv => ReportFormat::Csv,
+            FormatArg::Html => ReportFormat::Html,
+        }
+    }
+}
+
+/// Arguments of the `probe` subcommand.
+#[derive(Debug, Args)]
+pub struct ProbeArgs {
+    /// The probe to run
+    #[arg(value_enum)]
+    pub probe: ProbeName,
+
+    /// URL requested by the http probe without `[probes.http]`; may be repeated
+    #[arg(
+        long = "url",
+        default_value = "http://connectivitycheck.gstatic.com/generate_204"
+    )]
+    pub urls: Vec<String>,
+
+    /// Status code a successful http probe response must have without `[probes.http]`
+    #[arg(long, default_value_t = 204)]
+    pub expect_status: u16,
+
+    /// DNS server queried by the dns probe without `[probes.dns]`, instead of the system
+    /// resolvers; may be repeated
+    #[arg(long = "server")]
+    pub servers: Vec<SocketAddr>,
+
+    /// Name resolved by the dns probe without `[probes.dns]`
+    #[arg(long, default_value = "www.google.com")]
+    pub query: String,
+
+    /// Time allowed for each request of a probe without its `[probes]` section
+    #[arg(long, default_value = "5s", value_parser = parse_duration)]
+    pub timeout: Duration,
+}
+
+/// The probes available to the `probe` subcommand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProbeName {
+    /// The OS connectivity check
+    System,
+
+    /// Requests HTTP(S) URLs
+    Http,
+
+    /// Resolves a name with each DNS server
+    Dns,
+
+    /// Requests known-content URLs to detect captive portals
+    Captive,
+
+    /// Runs every member of the configured `[probes.quorum]`
+    Quorum,
+}
+
+/// Returns the exit code reported for `status`.
+pub fn status_exit_code(status: &NetworkStatus) -> u8 {
+    match status {
+        NetworkStatus::Connected => exit::SUCCESS,
+        NetworkStatus::NetworkOnly => exit::NETWORK_ONLY,
+        NetworkStatus::Disconnected => exit::DISCONNECTED,
+        NetworkStatus::CaptivePortal { .. } => exit::CAPTIVE_PORTAL,
+        NetworkStatus::Degraded { .. } => exit::DEGRADED,
+    }
+}
+
+/// Returns the exit code reported for a diagnosis, based on the first layer which failed.
+pub fn diagnosis_exit_code(report: &DiagnosisReport) -> u8 {
+    match report.failed_layer() {
+        None => exit::SUCCESS,
+        Some(Layer::Link | Layer::Address) => exit::DISCONNECTED,
+        Some(Layer::Gateway | Layer::Dns | Layer::Internet) => exit::NETWORK_ONLY,
+    }
+}
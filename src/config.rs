@@ -0,0 +1,1613 @@
+//! Module for loading the application configuration from a TOML file.
+//!
+//! This module defines the [`Config`] struct, which describes the probes, intervals, thresholds,
+//! managed interface, remediation steps, notification sinks and history file, and the
+//! [`ConfigWatcher`] which detects when the file should be reloaded.
+//!
+//! Every section and field is optional, so an empty file is a valid configuration:
+//!
+//! ```toml
+//! [monitor]
+//! interval = "30s"
+//!
+//! [monitor.verification]
+//! timeout = "30s"
+//! interval = "2s"
+//!
+//! [network]
+//! backend = "auto"
+//! interface = "wlan0"          # or "mac:…", "description:…" or "guid:…"
+//! all_interfaces = false       # manage every matching interface, not only the first
+//!
+//! [probes]
+//! internet = "tcp"             # system, http, dns, tcp, icmp or quorum; not http with an interface
+//!
+//! [probes.tcp]
+//! endpoints = ["www.google.com:443"]
+//!
+//! [probes.quality]
+//! target = "www.google.com:443"
+//! max_latency = "500ms"
+//! max_loss_percent = 10
+//!
+//! [thresholds]
+//! consecutive_failures = 3
+//! recovery_successes = 2
+//!
+//! [[remediation]]
+//! action = "renew-dhcp"
+//!
+//! [[notifications.webhook]]
+//! url = "https://example.com/hooks/internet"
+//! format = "slack"             # json, slack, discord or teams
+//! queue = "/var/lib/internet-reloader/webhook.jsonl"
+//!
+//! [history]
+//! path = "/var/lib/internet-reloader/history.jsonl"
+//! ```
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+use std::{fs, io};
+
+use serde::{Deserialize, Deserializer};
+use tracing::warn;
+
+use crate::adapter::InterfaceSelector;
+use crate::app::{NetworkApp, RecoveryVerification};
+use crate::cancellation::CancellationToken;
+#[cfg(target_os = "linux")]
+use crate::diagnosis::LinuxDiagnostics;
+use crate::events::ChannelSubscriber;
+use crate::history::{HistoryLog, HistoryRecorder};
+use crate::hysteresis::Hysteresis;
+use crate::internet_connectivity::{
+    CaptivePortalDetector, ConnectionQuality, DnsProbe, DnsRecordType, HttpProbe,
+    InternetConnectivity, KnownContent, QualityProbe, QualityThresholds, QuorumMember,
+    QuorumPolicy, QuorumProbe, SystemInternetConnectivity, TcpConnectProbe,
+};
+#[cfg(target_os = "linux")]
+use crate::internet_connectivity::{GatewayProbe, IcmpProbe};
+use crate::network_manager::{Backend, NetworkManager};
+use crate::notifications::{NotificationQueue, Notifier, RetryPolicy, WebhookFormat, WebhookSink};
+use crate::remediation::{CommandAction, RemediationLadder};
+
+/// A [`NetworkApp`] built from a [`Config`], with the checker and backend chosen at runtime.
+pub type ConfiguredApp = NetworkApp<Box<dyn InternetConnectivity>, Box<dyn NetworkManager>>;
+
+/// Errors returned when a configuration cannot be loaded.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be read.
+    Io {
+        /// The file which could not be read.
+        path: PathBuf,
+
+        /// The error reading it failed with.
+        source: io::Error,
+    },
+
+    /// The file is not valid TOML, or does not match the expected structure.
+    Parse {
+        /// Description of the error, including its line and column.
+        message: String,
+    },
+
+    /// A value is well-formed, but not allowed.
+    Invalid {
+        /// Path of the offending key, such as `probes.http.urls[1]`.
+        key: String,
+
+        /// Why the value is not allowed.
+        message: String,
+    },
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`ConfigError`] to enable easy printing.
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(f, "failed to read {}: {source}", path.display())
+            }
+            ConfigError::Parse { message } => write!(f, "{}", message.trim_end()),
+            ConfigError::Invalid { key, message } => write!(f, "{key}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// The application configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// How often the network is polled, and how recovery is verified.
+    pub monitor: MonitorConfig,
+
+    /// The backend and interface to manage.
+    pub network: NetworkConfig,
+
+    /// The probes used to check connectivity.
+    pub probes: ProbesConfig,
+
+    /// When outages and recoveries are declared.
+    pub thresholds: ThresholdsConfig,
+
+    /// Remediation steps attempted after reconnecting, in order.
+    pub remediation: Vec<RemediationStep>,
+
+    /// Where notifications are sent.
+    pub notifications: NotificationsConfig,
+
+    /// Where status changes and remediations are recorded.
+    pub history: HistoryConfig,
+}
+
+/// The `[monitor]` section.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MonitorConfig {
+    /// Time between polls.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub interval: Duration,
+
+    /// How the internet is re-probed after a remediation.
+    pub verification: VerificationConfig,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(30),
+            verification: VerificationConfig::default(),
+        }
+    }
+}
+
+/// The `[monitor.verification]` section.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VerificationConfig {
+    /// Longest time to wait for the internet to return.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub timeout: Duration,
+
+    /// Time between probes.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub interval: Duration,
+}
+
+impl Default for VerificationConfig {
+    fn default() -> Self {
+        let defaults = RecoveryVerification::default();
+        Self {
+            timeout: defaults.timeout,
+            interval: defaults.interval,
+        }
+    }
+}
+
+/// The `[network]` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// The backend used to reconnect.
+    pub backend: Backend,
+
+    /// Selects the interfaces to check and reconnect.
+    pub interface: Option<InterfaceSelector>,
+
+    /// Whether every interface matching `interface` is managed, rather than only the first.
+    pub all_interfaces: bool,
+}
+
+/// The probe which decides whether the internet is available.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InternetProbe {
+    /// The OS connectivity check.
+    #[default]
+    System,
+
+    /// The `[probes.http]` probe.
+    Http,
+
+    /// The `[probes.dns]` probe.
+    Dns,
+
+    /// The `[probes.tcp]` probe.
+    Tcp,
+
+    /// The `[probes.icmp]` probe, only available on Linux.
+    Icmp,
+
+    /// The `[probes.quorum]` probe, combining several of the others.
+    Quorum,
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`InternetProbe`] to enable easy printing.
+impl std::fmt::Display for InternetProbe {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            InternetProbe::System => write!(f, "system"),
+            InternetProbe::Http => write!(f, "http"),
+            InternetProbe::Dns => write!(f, "dns"),
+            InternetProbe::Tcp => write!(f, "tcp"),
+            InternetProbe::Icmp => write!(f, "icmp"),
+            InternetProbe::Quorum => write!(f, "quorum"),
+        }
+    }
+}
+
+/// The `[probes]` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProbesConfig {
+    /// The probe which decides whether the internet is available.
+    pub internet: InternetProbe,
+
+    /// Settings of the HTTP probe.
+    pub http: Option<HttpProbeConfig>,
+
+    /// Settings of the DNS probe.
+    pub dns: Option<DnsProbeConfig>,
+
+    /// Settings of the TCP connect probe.
+    pub tcp: Option<TcpProbeConfig>,
+
+    /// Settings of the ICMP probe.
+    pub icmp: Option<IcmpProbeConfig>,
+
+    /// Settings of the quorum probe.
+    pub quorum: Option<QuorumConfig>,
+
+    /// Settings of the gateway reachability check, only available on Linux.
+    pub gateway: Option<GatewayConfig>,
+
+    /// Settings of the connection quality probe.
+    pub quality: Option<QualityConfig>,
+
+    /// Settings of captive portal detection.
+    pub captive: CaptiveConfig,
+}
+
+/// The `[probes.http]` section.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HttpProbeConfig {
+    /// The URLs to request, in order.
+    pub urls: Vec<String>,
+
+    /// Status code a successful response must have.
+    #[serde(default = "default_http_status")]
+    pub expected_status: u16,
+
+    /// Content a successful response body must contain.
+    #[serde(default)]
+    pub expected_body: Option<String>,
+
+    /// Time allowed for each request.
+    #[serde(
+        default = "default_http_timeout",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub timeout: Duration,
+}
+
+/// The `[probes.dns]` section.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DnsProbeConfig {
+    /// Servers to query, or the system resolvers if empty.
+    pub servers: Vec<SocketAddr>,
+
+    /// The name to resolve.
+    pub query: String,
+
+    /// The record type to query, `a` or `aaaa`.
+    pub record_type: RecordType,
+
+    /// Time allowed for each query.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub timeout: Duration,
+}
+
+impl Default for DnsProbeConfig {
+    fn default() -> Self {
+        Self {
+            servers: Vec::new(),
+            query: "www.google.com".to_string(),
+            record_type: RecordType::A,
+            timeout: Duration::from_secs(3),
+        }
+    }
+}
+
+/// The `[probes.tcp]` section.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TcpProbeConfig {
+    /// The `host:port` endpoints to connect to, in order.
+    pub endpoints: Vec<String>,
+
+    /// Time allowed for each connection attempt.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub timeout: Duration,
+
+    /// Time to wait for an attempt before racing the next address of an endpoint.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub attempt_delay: Duration,
+}
+
+impl Default for TcpProbeConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: vec![
+                "www.google.com:443".to_string(),
+                "one.one.one.one:853".to_string(),
+            ],
+            timeout: Duration::from_secs(3),
+            attempt_delay: Duration::from_millis(250),
+        }
+    }
+}
+
+/// The `[probes.icmp]` section.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IcmpProbeConfig {
+    /// The IPv4 or IPv6 addresses to ping.
+    pub targets: Vec<IpAddr>,
+
+    /// Number of echo requests sent to each target.
+    pub count: u16,
+
+    /// Time allowed for each echo reply.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub timeout: Duration,
+}
+
+impl Default for IcmpProbeConfig {
+    fn default() -> Self {
+        Self {
+            targets: vec![
+                IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+                IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8)),
+            ],
+            count: 3,
+            timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+/// The `[probes.gateway]` section.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GatewayConfig {
+    /// Number of echo requests sent to each default gateway.
+    pub count: u16,
+
+    /// Time allowed for each echo reply.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub timeout: Duration,
+}
+
+impl Default for GatewayConfig {
+    fn default() -> Self {
+        Self {
+            count: 2,
+            timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+/// DNS record types accepted in `[probes.dns]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordType {
+    /// IPv4 address record.
+    #[default]
+    A,
+
+    /// IPv6 address record.
+    Aaaa,
+}
+
+/// The `[probes.quorum]` section.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuorumConfig {
+    /// How the results of the members are combined.
+    #[serde(default)]
+    pub policy: QuorumPolicyKind,
+
+    /// Members which must succeed under `k-of-n`, or the score required under `weighted`.
+    #[serde(default)]
+    pub required: Option<u32>,
+
+    /// The probes combined.
+    pub members: Vec<QuorumMemberConfig>,
+}
+
+/// Policies accepted in `[probes.quorum]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuorumPolicyKind {
+    /// Connected if any member succeeds.
+    #[default]
+    Any,
+
+    /// Connected if every member succeeds.
+    All,
+
+    /// Connected if at least `required` members succeed.
+    KOfN,
+
+    /// Connected if the weights of the members which succeed add up to at least `required`.
+    Weighted,
+}
+
+/// A `[[probes.quorum.members]]` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuorumMemberConfig {
+    /// The probe to run, which must be configured in its own section.
+    pub probe: InternetProbe,
+
+    /// Weight of the member under the `weighted` policy.
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+/// How the `[probes.quality]` probe samples round-trip times.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QualityProtocol {
+    /// Timing TCP handshakes, where a lost SYN is retransmitted and shows up as latency.
+    #[default]
+    Tcp,
+
+    /// Timing ICMP echo requests, only available on Linux.
+    Icmp,
+}
+
+/// The `[probes.quality]` section.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QualityConfig {
+    /// How the samples are taken.
+    pub protocol: QualityProtocol,
+
+    /// The `host:port` to time TCP connections to, or the IP address to ping with ICMP.
+    pub target: String,
+
+    /// Number of samples in each burst.
+    pub count: u32,
+
+    /// Time between the start of consecutive samples.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub spacing: Duration,
+
+    /// Time after which a sample is considered lost.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub timeout: Duration,
+
+    /// Longest acceptable median round-trip time.
+    #[serde(deserialize_with = "deserialize_optional_duration")]
+    pub max_latency: Option<Duration>,
+
+    /// Highest acceptable jitter.
+    #[serde(deserialize_with = "deserialize_optional_duration")]
+    pub max_jitter: Option<Duration>,
+
+    /// Highest acceptable percentage of lost samples.
+    pub max_loss_percent: Option<u32>,
+
+    /// Whether a degraded connection is remediated the same way as one without internet.
+    pub remediate: bool,
+}
+
+impl Default for QualityConfig {
+    fn default() -> Self {
+        Self {
+            protocol: QualityProtocol::Tcp,
+            target: "www.google.com:443".to_string(),
+            count: 10,
+            spacing: Duration::from_millis(100),
+            timeout: Duration::from_secs(2),
+            max_latency: None,
+            max_jitter: None,
+            max_loss_percent: None,
+            remediate: false,
+        }
+    }
+}
+
+/// The `[probes.captive]` section.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CaptiveConfig {
+    /// Whether captive portals are detected.
+    pub enabled: bool,
+
+    /// Known-content checks, or [`KnownContent::defaults`] if empty.
+    pub checks: Vec<KnownContentConfig>,
+
+    /// Time allowed for each request.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub timeout: Duration,
+}
+
+impl Default for CaptiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            checks: Vec::new(),
+            timeout: default_http_timeout(),
+        }
+    }
+}
+
+/// A `[[probes.captive.checks]]` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KnownContentConfig {
+    /// The plain HTTP URL to request.
+    pub url: String,
+
+    /// The status code the URL responds with when not intercepted.
+    #[serde(default = "default_http_status")]
+    pub expected_status: u16,
+
+    /// Content the body contains when not intercepted.
+    #[serde(default)]
+    pub expected_body: Option<String>,
+}
+
+/// The `[thresholds]` section.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThresholdsConfig {
+    /// Consecutive failures before an outage is declared, or `None` to declare it on the first
+    /// failure, unless a failure window is configured.
+    pub consecutive_failures: Option<usize>,
+
+    /// Failures within a window of results before an outage is declared.
+    pub failure_window: Option<FailureWindowConfig>,
+
+    /// Consecutive successes before recovery is declared.
+    pub recovery_successes: usize,
+}
+
+impl Default for ThresholdsConfig {
+    fn default() -> Self {
+        Self {
+            consecutive_failures: None,
+            failure_window: None,
+            recovery_successes: 1,
+        }
+    }
+}
+
+/// The `[thresholds.failure_window]` section.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FailureWindowConfig {
+    /// Failures required within the window.
+    pub failures: usize,
+
+    /// Number of most recent results in the window.
+    pub window: usize,
+}
+
+/// A `[[remediation]]` entry, selected by its `action` key.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "action", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum RemediationStep {
+    /// Sets the interface down then up.
+    BounceInterface {
+        /// The interface, or the `[network]` interface if not set.
+        interface: Option<String>,
+    },
+
+    /// Releases then renews the DHCP lease of the interface.
+    RenewDhcp {
+        /// The interface, or the `[network]` interface if not set.
+        interface: Option<String>,
+    },
+
+    /// Connects to a fallback profile.
+    ConnectTo {
+        /// Name of the fallback profile.
+        profile: String,
+    },
+
+    /// Runs an external command.
+    Command {
+        /// Short name of the step, used when reporting attempts.
+        name: String,
+
+        /// The program to run.
+        program: String,
+
+        /// The arguments to pass to the program.
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+/// The `[notifications]` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationsConfig {
+    /// Webhooks notified of status changes.
+    pub webhook: Vec<WebhookConfig>,
+}
+
+/// A `[[notifications.webhook]]` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    /// The URL to send notifications to.
+    pub url: String,
+
+    /// The body posted, unless `template` is set.
+    #[serde(default)]
+    pub format: WebhookFormatKind,
+
+    /// A JSON body with `{placeholders}` replaced by the fields of each notification.
+    #[serde(default)]
+    pub template: Option<String>,
+
+    /// Time allowed for each request.
+    #[serde(
+        default = "default_webhook_timeout",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub timeout: Duration,
+
+    /// Number of times delivery is attempted before a notification is left queued.
+    #[serde(default = "default_webhook_attempts")]
+    pub attempts: u32,
+
+    /// Delay before the first retry, doubled for each retry after it.
+    #[serde(
+        default = "default_webhook_backoff",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub backoff: Duration,
+
+    /// File holding notifications which could not be delivered, so they survive a restart.
+    #[serde(default)]
+    pub queue: Option<PathBuf>,
+}
+
+/// The `[history]` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HistoryConfig {
+    /// File every status change and remediation is appended to, if any.
+    pub path: Option<PathBuf>,
+}
+
+/// Webhook body formats accepted in `[[notifications.webhook]]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormatKind {
+    /// The notification as JSON.
+    #[default]
+    Json,
+
+    /// A Slack incoming webhook message.
+    Slack,
+
+    /// A Discord webhook message.
+    Discord,
+
+    /// A Microsoft Teams incoming webhook message card.
+    Teams,
+}
+
+impl Config {
+    /// Reads and validates the configuration file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        Self::parse(&contents).map_err(|e| match e {
+            ConfigError::Parse { message } => ConfigError::Parse {
+                message: format!("{}: {message}", path.display()),
+            },
+            e => e,
+        })
+    }
+
+    /// Parses and validates a configuration from TOML.
+    pub fn parse(contents: &str) -> Result<Self, ConfigError> {
+        let config: Config = toml::from_str(contents).map_err(|e| ConfigError::Parse {
+            message: e.to_string(),
+        })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks every value is allowed, returning an error naming the first key which is not.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let monitor = &self.monitor;
+        positive("monitor.interval", monitor.interval)?;
+        positive(
+            "monitor.verification.interval",
+            monitor.verification.interval,
+        )?;
+
+        let probes = &self.probes;
+        self.probe_configured("probes.internet", probes.internet)?;
+        self.probe_scoped("probes.internet", probes.internet)?;
+
+        if let Some(quorum) = &probes.quorum {
+            if quorum.members.is_empty() {
+                return invalid("probes.quorum.members", "must list at least one probe");
+            }
+            for (i, member) in quorum.members.iter().enumerate() {
+                let key = format!("probes.quorum.members[{i}]");
+                if member.probe == InternetProbe::Quorum {
+                    return invalid(&format!("{key}.probe"), "cannot be \"quorum\"");
+                }
+                self.probe_configured(&format!("{key}.probe"), member.probe)?;
+                self.probe_scoped(&format!("{key}.probe"), member.probe)?;
+                at_least_one(&format!("{key}.weight"), member.weight as usize)?;
+            }
+
+            let maximum = match quorum.policy {
+                QuorumPolicyKind::Any | QuorumPolicyKind::All => None,
+                QuorumPolicyKind::KOfN => Some(quorum.members.len() as u32),
+                QuorumPolicyKind::Weighted => Some(
+                    quorum
+                        .members
+                        .iter()
+                        .fold(0u32, |sum, member| sum.saturating_add(member.weight)),
+                ),
+            };
+            match (maximum, quorum.required) {
+                (None, Some(_)) => {
+                    return invalid(
+                        "probes.quorum.required",
+                        "only applies to the k-of-n and weighted policies",
+                    );
+                }
+                (Some(_), None) => {
+                    return invalid(
+                        "probes.quorum.required",
+                        "must be set for the k-of-n and weighted policies",
+                    );
+                }
+                (Some(maximum), Some(required)) if required == 0 || required > maximum => {
+                    return invalid(
+                        "probes.quorum.required",
+                        &format!("must be between 1 and {maximum}, but is {required}"),
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(http) = &probes.http {
+            if http.urls.is_empty() {
+                return invalid("probes.http.urls", "must list at least one URL");
+            }
+            for (i, url) in http.urls.iter().enumerate() {
+                http_url(
+                    &format!("probes.http.urls[{i}]"),
+                    url,
+                    &["http://", "https://"],
+                )?;
+            }
+            status_code("probes.http.expected_status", http.expected_status)?;
+            positive("probes.http.timeout", http.timeout)?;
+        }
+
+        if let Some(dns) = &probes.dns {
+            if dns.query.trim().is_empty() {
+                return invalid("probes.dns.query", "must not be empty");
+            }
+            positive("probes.dns.timeout", dns.timeout)?;
+        }
+
+        if let Some(tcp) = &probes.tcp {
+            if tcp.endpoints.is_empty() {
+                return invalid("probes.tcp.endpoints", "must list at least one endpoint");
+            }
+            for (i, endpoint) in tcp.endpoints.iter().enumerate() {
+                host_port(&format!("probes.tcp.endpoints[{i}]"), endpoint)?;
+            }
+            positive("probes.tcp.timeout", tcp.timeout)?;
+        }
+
+        if let Some(icmp) = &probes.icmp {
+            if icmp.targets.is_empty() {
+                return invalid("probes.icmp.targets", "must list at least one address");
+            }
+            at_least_one("probes.icmp.count", icmp.count as usize)?;
+            positive("probes.icmp.timeout", icmp.timeout)?;
+        }
+
+        if let Some(gateway) = &probes.gateway {
+            if cfg!(not(target_os = "linux")) {
+                return invalid("probes.gateway", "is only supported on Linux");
+            }
+            at_least_one("probes.gateway.count", gateway.count as usize)?;
+            positive("probes.gateway.timeout", gateway.timeout)?;
+        }
+
+        if let Some(quality) = &probes.quality {
+            if self.network.interface.is_some()
+                && cfg!(not(any(target_os = "linux", target_os = "windows")))
+            {
+                return invalid(
+                    "probes.quality",
+                    "can only be scoped to network.interface on Linux and Windows",
+                );
+            }
+            match quality.protocol {
+                QualityProtocol::Tcp => host_port("probes.quality.target", &quality.target)?,
+                QualityProtocol::Icmp if cfg!(not(target_os = "linux")) => {
+                    return invalid(
+                        "probes.quality.protocol",
+                        "is \"icmp\", which is only supported on Linux",
+                    );
+                }
+                QualityProtocol::Icmp if quality.target.parse::<IpAddr>().is_err() => {
+                    return invalid(
+                        "probes.quality.target",
+                        &format!("{:?} must be an IP address to ping", quality.target),
+                    );
+                }
+                QualityProtocol::Icmp => {}
+            }
+            at_least_one("probes.quality.count", quality.count as usize)?;
+            positive("probes.quality.timeout", quality.timeout)?;
+            if let Some(percent) = quality.max_loss_percent.filter(|percent| *percent > 100) {
+                return invalid(
+                    "probes.quality.max_loss_percent",
+                    &format!("must be between 0 and 100, but is {percent}"),
+                );
+            }
+        }
+
+        for (i, check) in probes.captive.checks.iter().enumerate() {
+            let key = format!("probes.captive.checks[{i}]");
+            http_url(&format!("{key}.url"), &check.url, &["http://"])?;
+            status_code(&format!("{key}.expected_status"), check.expected_status)?;
+        }
+        positive("probes.captive.timeout", probes.captive.timeout)?;
+
+        let thresholds = &self.thresholds;
+        if let Some(count) = thresholds.consecutive_failures {
+            at_least_one("thresholds.consecutive_failures", count)?;
+        }
+        at_least_one(
+            "thresholds.recovery_successes",
+            thresholds.recovery_successes,
+        )?;
+        if let Some(window) = &thresholds.failure_window {
+            at_least_one("thresholds.failure_window.failures", window.failures)?;
+            if window.window < window.failures {
+                return invalid(
+                    "thresholds.failure_window.window",
+                    &format!(
+                        "must be at least failures ({}), but is {}",
+                        window.failures, window.window
+                    ),
+                );
+            }
+        }
+
+        if self.network.all_interfaces && self.network.interface.is_none() {
+            return invalid("network.all_interfaces", "requires `interface` to be set");
+        }
+
+        for (i, step) in self.remediation.iter().enumerate() {
+            let key = format!("remediation[{i}]");
+            match step {
+                RemediationStep::BounceInterface { interface: None }
+                | RemediationStep::RenewDhcp { interface: None }
+                    if self.network.interface.is_none() =>
+                {
+                    return invalid(
+                        &key,
+                        "requires an interface; set `interface` on the step or in [network]",
+                    );
+                }
+                RemediationStep::ConnectTo { profile } if profile.trim().is_empty() => {
+                    return invalid(&format!("{key}.profile"), "must not be empty");
+                }
+                RemediationStep::Command { program, .. } if program.trim().is_empty() => {
+                    return invalid(&format!("{key}.program"), "must not be empty");
+                }
+                _ => {}
+            }
+        }
+
+        for (i, webhook) in self.notifications.webhook.iter().enumerate() {
+            let key = format!("notifications.webhook[{i}]");
+            http_url(
+                &format!("{key}.url"),
+                &webhook.url,
+                &["http://", "https://"],
+            )?;
+            if webhook
+                .template
+                .as_ref()
+                .is_some_and(|t| t.trim().is_empty())
+            {
+                return invalid(&format!("{key}.template"), "must not be empty");
+            }
+            positive(&format!("{key}.timeout"), webhook.timeout)?;
+            at_least_one(&format!("{key}.attempts"), webhook.attempts as usize)?;
+            positive(&format!("{key}.backoff"), webhook.backoff)?;
+
+            let shared = self.notifications.webhook[..i]
+                .iter()
+                .any(|other| other.queue.is_some() && other.queue == webhook.queue);
+            if shared {
+                return invalid(
+                    &format!("{key}.queue"),
+                    "is already used by another webhook",
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the section of the `kind` probe is present, naming `key` if it is not.
+    fn probe_configured(&self, key: &str, kind: InternetProbe) -> Result<(), ConfigError> {
+        let probes = &self.probes;
+        let configured = match kind {
+            InternetProbe::System => true,
+            InternetProbe::Http => probes.http.is_some(),
+            InternetProbe::Dns => probes.dns.is_some(),
+            InternetProbe::Tcp => probes.tcp.is_some(),
+            InternetProbe::Icmp if cfg!(not(target_os = "linux")) => {
+                return invalid(key, "is \"icmp\", which is only supported on Linux");
+            }
+            InternetProbe::Icmp => probes.icmp.is_some(),
+            InternetProbe::Quorum => probes.quorum.is_some(),
+        };
+
+        match configured {
+            true => Ok(()),
+            false => invalid(
+                key,
+                &format!("is \"{kind}\", but [probes.{kind}] is missing"),
+            ),
+        }
+    }
+
+    /// Checks the `kind` probe can send its traffic through the `[network]` interface, if one is
+    /// set, naming `key` if it cannot.
+    fn probe_scoped(&self, key: &str, kind: InternetProbe) -> Result<(), ConfigError> {
+        if self.network.interface.is_none() {
+            return Ok(());
+        }
+
+        match kind {
+            InternetProbe::System | InternetProbe::Quorum => Ok(()),
+            InternetProbe::Http => invalid(
+                key,
+                "is \"http\", which cannot be scoped to network.interface",
+            ),
+            _ if cfg!(not(any(target_os = "linux", target_os = "windows"))) => invalid(
+                key,
+                &format!(
+                    "is \"{kind}\", which can only be scoped to network.interface on Linux and \
+                     Windows"
+                ),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the [`Hysteresis`] described by `[thresholds]`.
+    pub fn hysteresis(&self) -> Hysteresis {
+        let thresholds = &self.thresholds;
+        let mut hysteresis =
+            Hysteresis::new().with_recovery_successes(thresholds.recovery_successes);
+        if let Some(count) = thresholds.consecutive_failures {
+            hysteresis = hysteresis.with_consecutive_failures(count);
+        }
+
+        match &thresholds.failure_window {
+            Some(window) => hysteresis.with_failure_window(window.failures, window.window),
+            None => hysteresis,
+        }
+    }
+
+    /// Returns the [`RecoveryVerification`] described by `[monitor.verification]`.
+    pub fn recovery_verification(&self) -> RecoveryVerification {
+        RecoveryVerification {
+            timeout: self.monitor.verification.timeout,
+            interval: self.monitor.verification.interval,
+        }
+    }
+
+    /// Returns the [`InternetConnectivity`] checker described by `[probes]`.
+    pub fn checker(&self) -> Box<dyn InternetConnectivity> {
+        let probes = &self.probes;
+        let internet: Box<dyn InternetConnectivity> = match (probes.internet, &probes.quorum) {
+            (InternetProbe::Quorum, Some(quorum)) => Box::new(self.quorum_probe(quorum)),
+            (kind, _) => self.probe(kind),
+        };
+        #[cfg(target_os = "linux")]
+        let internet: Box<dyn InternetConnectivity> = match &probes.gateway {
+            Some(gateway) => Box::new(self.gateway_probe(internet, gateway)),
+            None => internet,
+        };
+
+        match probes.captive.enabled {
+            true => Box::new(self.captive_portal_detector(internet)),
+            false => internet,
+        }
+    }
+
+    /// Returns the [`CaptivePortalDetector`] described by `[probes.captive]`, wrapping `inner`.
+    pub fn captive_portal_detector<C: InternetConnectivity>(
+        &self,
+        inner: C,
+    ) -> CaptivePortalDetector<C> {
+        let captive = &self.probes.captive;
+        let detector = CaptivePortalDetector::new(inner).with_timeout(captive.timeout);
+        match captive.checks.is_empty() {
+            true => detector,
+            false => detector.with_checks(captive.checks.iter().map(|check| {
+                KnownContent::new(
+                    check.url.clone(),
+                    check.expected_status,
+                    check.expected_body.as_deref(),
+                )
+            })),
+        }
+    }
+
+    /// Returns the `kind` probe described by its `[probes]` section, or the OS connectivity check
+    /// if that section is missing.
+    fn probe(&self, kind: InternetProbe) -> QuorumMember {
+        match (kind, &self.probes) {
+            (
+                InternetProbe::Http,
+                ProbesConfig {
+                    http: Some(http), ..
+                },
+            ) => Box::new(self.http_probe(http)),
+            (InternetProbe::Dns, ProbesConfig { dns: Some(dns), .. }) => {
+                Box::new(self.dns_probe(dns).with_network(self.system_checker()))
+            }
+            (InternetProbe::Tcp, ProbesConfig { tcp: Some(tcp), .. }) => {
+                let probe = TcpConnectProbe::new(tcp.endpoints.iter().cloned())
+                    .with_network(self.system_checker())
+                    .with_timeout(tcp.timeout)
+                    .with_attempt_delay(tcp.attempt_delay);
+                match self.probe_device() {
+                    Some(device) => Box::new(probe.with_device(device)),
+                    None => Box::new(probe),
+                }
+            }
+            #[cfg(target_os = "linux")]
+            (
+                InternetProbe::Icmp,
+                ProbesConfig {
+                    icmp: Some(icmp), ..
+                },
+            ) => {
+                let probe = IcmpProbe::new(icmp.targets.iter().copied())
+                    .with_network(self.system_checker())
+                    .with_count(icmp.count)
+                    .with_timeout(icmp.timeout);
+                match self.probe_device() {
+                    Some(device) => Box::new(probe.with_device(device)),
+                    None => Box::new(probe),
+                }
+            }
+            _ => Box::new(self.system_checker()),
+        }
+    }
+
+    /// Returns the [`HttpProbe`] described by `[probes.http]`.
+    pub fn http_probe(&self, http: &HttpProbeConfig) -> HttpProbe {
+        let probe = HttpProbe::new(http.urls.iter().cloned())
+            .with_network(self.system_checker())
+            .with_expected_status(http.expected_status)
+            .with_timeout(http.timeout);
+        match &http.expected_body {
+            Some(body) => probe.with_expected_body(body.clone()),
+            None => probe,
+        }
+    }
+
+    /// Returns the [`DnsProbe`] described by `[probes.dns]`, sending its queries through the
+    /// [`Config::probe_device`], if any.
+    pub fn dns_probe(&self, dns: &DnsProbeConfig) -> DnsProbe {
+        let probe = match dns.servers.is_empty() {
+            true => DnsProbe::system(),
+            false => DnsProbe::with_servers(dns.servers.iter().copied()),
+        };
+        let probe = probe
+            .with_query(dns.query.clone())
+            .with_record_type(match dns.record_type {
+                RecordType::A => DnsRecordType::A,
+                RecordType::Aaaa => DnsRecordType::Aaaa,
+            })
+            .with_timeout(dns.timeout);
+        match self.probe_device() {
+            Some(device) => probe.with_device(device),
+            None => probe,
+        }
+    }
+
+    /// Returns the [`LinuxDiagnostics`] scoped to the `[network]` interfaces, using the
+    /// `[probes.gateway]` and `[probes.dns]` settings if present.
+    #[cfg(target_os = "linux")]
+    pub fn diagnostics(&self) -> LinuxDiagnostics {
+        let diagnostics = match self.interface_selector() {
+            Some(selector) => LinuxDiagnostics::new().with_interface(selector),
+            None => LinuxDiagnostics::new(),
+        };
+        let diagnostics = match &self.probes.gateway {
+            Some(gateway) => diagnostics.with_gateway_timeout(gateway.timeout),
+            None => diagnostics,
+        };
+        match &self.probes.dns {
+            Some(dns) => diagnostics.with_dns_probe(self.dns_probe(dns)),
+            None => diagnostics,
+        }
+    }
+
+    /// Returns the [`QuorumProbe`] described by `[probes.quorum]`.
+    pub fn quorum_probe(&self, quorum: &QuorumConfig) -> QuorumProbe {
+        let required = quorum.required.unwrap_or(1);
+        let policy = match quorum.policy {
+            QuorumPolicyKind::Any => QuorumPolicy::Any,
+            QuorumPolicyKind::All => QuorumPolicy::All,
+            QuorumPolicyKind::KOfN => QuorumPolicy::AtLeast(required as usize),
+            QuorumPolicyKind::Weighted => QuorumPolicy::Weighted(required),
+        };
+
+        quorum.members.iter().fold(
+            QuorumProbe::new()
+                .with_network(self.system_checker())
+                .with_policy(policy),
+            |probe, member| {
+                probe.with_weighted_member(
+                    member.probe.to_string(),
+                    member.weight,
+                    self.probe(member.probe),
+                )
+            },
+        )
+    }
+
+    /// Returns a [`GatewayProbe`] adding the `[probes.gateway]` check to the network check of `inner`.
+    #[cfg(target_os = "linux")]
+    pub fn gateway_probe<C: InternetConnectivity>(
+        &self,
+        inner: C,
+        gateway: &GatewayConfig,
+    ) -> GatewayProbe<C> {
+        let probe = GatewayProbe::new(inner)
+            .with_count(gateway.count)
+            .with_timeout(gateway.timeout);
+        match self.interface_selector() {
+            Some(selector) => probe.with_interface(selector),
+            None => probe,
+        }
+    }
+
+    /// Returns the [`ConnectionQuality`] probe and [`QualityThresholds`] described by
+    /// `[probes.quality]`.
+    pub fn quality_probe(
+        &self,
+        quality: &QualityConfig,
+    ) -> (Box<dyn ConnectionQuality>, QualityThresholds) {
+        let device = self.probe_device();
+        let probe: Box<dyn ConnectionQuality> = match quality.protocol {
+            #[cfg(target_os = "linux")]
+            QualityProtocol::Icmp => {
+                let probe = IcmpProbe::new(quality.target.parse::<IpAddr>().ok())
+                    .with_count(u16::try_from(quality.count).unwrap_or(u16::MAX))
+                    .with_timeout(quality.timeout);
+                match device {
+                    Some(device) => Box::new(probe.with_device(device)),
+                    None => Box::new(probe),
+                }
+            }
+            _ => {
+                let probe = QualityProbe::new(quality.target.clone())
+                    .with_count(quality.count)
+                    .with_spacing(quality.spacing)
+                    .with_timeout(quality.timeout);
+                match device {
+                    Some(device) => Box::new(probe.with_device(device)),
+                    None => Box::new(probe),
+                }
+            }
+        };
+        let thresholds = QualityThresholds {
+            max_latency: quality.max_latency,
+            max_jitter: quality.max_jitter,
+            max_loss_percent: quality.max_loss_percent,
+        };
+        (probe, thresholds)
+    }
+
+    /// Returns the [`Notifier`] described by a `[[notifications.webhook]]` entry, naming the
+    /// `[network]` interface in its notifications.
+    ///
+    /// If the queue file cannot be read, notifications are queued in memory instead.
+    pub fn notifier(&self, webhook: &WebhookConfig) -> Notifier<WebhookSink> {
+        let format = match webhook.format {
+            WebhookFormatKind::Json => WebhookFormat::Json,
+            WebhookFormatKind::Slack => WebhookFormat::Slack,
+            WebhookFormatKind::Discord => WebhookFormat::Discord,
+            WebhookFormatKind::Teams => WebhookFormat::Teams,
+        };
+        let sink = WebhookSink::new(webhook.url.clone())
+            .with_format(format)
+            .with_timeout(webhook.timeout);
+        let sink = match &webhook.template {
+            Some(template) => sink.with_template(template.clone()),
+            None => sink,
+        };
+
+        let notifier = Notifier::new(sink).with_retry_policy(RetryPolicy {
+            attempts: webhook.attempts,
+            backoff: webhook.backoff,
+            ..RetryPolicy::default()
+        });
+        let notifier = match webhook.queue.as_ref().map(NotificationQueue::open) {
+            Some(Ok(queue)) => notifier.with_queue(queue),
+            Some(Err(e)) => {
+                warn!(
+                    url = %webhook.url,
+                    error = %e,
+                    "Failed to open notification queue, queuing in memory"
+                );
+                notifier
+            }
+            None => notifier,
+        };
+        match &self.network.interface {
+            Some(selector) => notifier.with_interface(selector.to_string()),
+            None => notifier,
+        }
+    }
+
+    /// Returns the [`InterfaceSelector`] described by `[network]`, if an interface is set.
+    pub fn interface_selector(&self) -> Option<InterfaceSelector> {
+        self.network
+            .interface
+            .clone()
+            .map(|selector| selector.with_all(self.network.all_interfaces))
+    }
+
+    /// Returns the OS connectivity checker, scoped to the `[network]` interfaces if set.
+    pub fn system_checker(&self) -> SystemInternetConnectivity {
+        match self.interface_selector() {
+            Some(selector) => SystemInternetConnectivity::default().with_interface(selector),
+            None => SystemInternetConnectivity::default(),
+        }
+    }
+
+    /// Returns the interface the DNS, TCP, ICMP and quality probes send their traffic through: the
+    /// first of the `[network]` interfaces found when this is called, or `None` if no interface
+    /// is set or none is found.
+    ///
+    /// Always `None` outside Linux and Windows, where probes cannot be scoped to an interface.
+    pub fn probe_device(&self) -> Option<String> {
+        #[cfg(any(target_os = "linux", target_os = "windows"))]
+        return self
+            .system_checker()
+            .selected_interfaces()?
+            .into_iter()
+            .next();
+        #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+        None
+    }
+
+    /// Returns the [`RemediationLadder`] described by the `[[remediation]]` entries.
+    ///
+    /// Steps without their own interface get one rung per `[network]` interface; a step is
+    /// skipped if none of those interfaces can be found.
+    pub fn remediation_ladder(&self) -> RemediationLadder {
+        let interfaces = |own: &Option<String>| match (own, self.interface_selector()) {
+            (Some(own), _) => vec![own.clone()],
+            (None, Some(selector)) => selector.names().unwrap_or_else(|e| {
+                warn!(interface = %selector, error = %e, "Failed to list interfaces");
+                Vec::new()
+            }),
+            (None, None) => Vec::new(),
+        };
+
+        let rungs = self.remediation.iter().flat_map(|step| match step {
+            RemediationStep::BounceInterface { interface: own } => interfaces(own)
+                .iter()
+                .map(|interface| CommandAction::bounce_interface(interface))
+                .collect(),
+            RemediationStep::RenewDhcp { interface: own } => interfaces(own)
+                .iter()
+                .map(|interface| CommandAction::renew_dhcp(interface))
+                .collect(),
+            RemediationStep::ConnectTo { profile } => vec![CommandAction::connect_to(profile)],
+            RemediationStep::Command {
+                name,
+                program,
+                args,
+            } => vec![
+                CommandAction::new(name.clone())
+                    .with_command(program.clone(), args.iter().cloned()),
+            ],
+        });
+
+        rungs.fold(RemediationLadder::new(), RemediationLadder::with_rung)
+    }
+
+    /// Returns the [`NetworkManager`] for the `[network]` backend and interface.
+    pub fn manager(&self) -> Box<dyn NetworkManager> {
+        self.network
+            .backend
+            .manager(self.interface_selector().as_ref())
+    }
+
+    /// Builds a [`NetworkApp`] from every section of the configuration.
+    pub fn app(&self) -> ConfiguredApp {
+        let app = self
+            .notifications
+            .webhook
+            .iter()
+            .fold(self.core_app(), |app, webhook| {
+                app.with_subscriber(ChannelSubscriber::spawn(self.notifier(webhook)))
+            });
+        match &self.history.path {
+            Some(path) => app.with_subscriber(HistoryRecorder::new(HistoryLog::new(path))),
+            None => app,
+        }
+    }
+
+    /// Rebuilds `previous` with the settings of this configuration, as described by
+    /// [`NetworkApp::resume`].
+    ///
+    /// The subscribers of `previous` are kept rather than rebuilt, so a notification being retried
+    /// is not delivered twice; changes to `[notifications]` and `[history]` only take effect once
+    /// the app is built again with [`Config::app`].
+    pub fn reload_app(&self, previous: ConfiguredApp) -> ConfiguredApp {
+        self.core_app().resume(previous)
+    }
+
+    /// Returns `true` if the subscribers built by [`Config::app`] differ from those built for
+    /// `other`, so they would not be updated by [`Config::reload_app`].
+    pub fn subscribers_differ(&self, other: &Config) -> bool {
+        self.notifications != other.notifications
+            || self.history != other.history
+            || (!self.notifications.webhook.is_empty()
+                && self.network.interface != other.network.interface)
+    }
+
+    /// Builds a [`NetworkApp`] from every section except `[notifications]` and `[history]`.
+    fn core_app(&self) -> ConfiguredApp {
+        let app = NetworkApp::new(self.checker(), self.manager())
+            .with_hysteresis(self.hysteresis())
+            .with_recovery_verification(self.recovery_verification())
+            .with_remediation_ladder(self.remediation_ladder());
+        #[cfg(target_os = "linux")]
+        let app = app.with_diagnostics(self.diagnostics());
+
+        match &self.probes.quality {
+            Some(quality) => {
+                let (probe, thresholds) = self.quality_probe(quality);
+                app.with_quality_probe(probe, thresholds)
+                    .with_degraded_remediation(quality.remediate)
+            }
+            None => app,
+        }
+    }
+}
+
+/// Detects when a configuration file should be reloaded.
+///
+/// A reload is requested when the file's modification time or size changes, when
+/// [`ConfigWatcher::request_reload`] is called, or, once [`ConfigWatcher::watch_sighup`] has been
+/// called, when the process receives `SIGHUP`.
+pub struct ConfigWatcher {
+    path: PathBuf,
+    fingerprint: Option<(SystemTime, u64)>,
+    requested: Arc<AtomicBool>,
+}
+
+impl ConfigWatcher {
+    /// Creates a new [`ConfigWatcher`] for the file at `path`, treating its current contents as loaded.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self {
+            fingerprint: fingerprint(&path),
+            path,
+            requested: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Returns the path of the watched file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Requests a reload, as if the file had changed.
+    pub fn request_reload(&self) {
+        self.requested.store(true, Ordering::SeqCst);
+    }
+
+    /// Requests a reload whenever the process receives `SIGHUP`.
+    #[cfg(target_os = "linux")]
+    pub fn watch_sighup(&self) -> io::Result<()> {
+        signal_hook::flag::register(signal_hook::consts::SIGHUP, self.requested.clone())?;
+        Ok(())
+    }
+
+    /// Returns `true`, at most once per change, if a reload has been requested since the last call.
+    pub fn reload_requested(&mut self) -> bool {
+        let requested = self.requested.swap(false, Ordering::SeqCst);
+
+        let current = fingerprint(&self.path);
+        let changed = current != self.fingerprint;
+        self.fingerprint = current;
+
+        requested || changed
+    }
+
+    /// Waits until a reload is requested or `token` is cancelled, checking every `poll`.
+    ///
+    /// Returns `true` if a reload was requested, or `false` if `token` was cancelled.
+    pub fn wait(&mut self, poll: Duration, token: &CancellationToken) -> bool {
+        loop {
+            if self.reload_requested() {
+                return true;
+            }
+            if token.wait_timeout(poll) {
+                return false;
+            }
+        }
+    }
+}
+
+fn fingerprint(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Parses a duration such as `500ms`, `30s`, `2m`, `1h` or `7d`, where a bare number is in seconds.
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration {value:?}"))?;
+    let seconds = match unit.trim() {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        "d" => number * 86400.0,
+        unit => {
+            return Err(format!(
+                "unknown duration unit {unit:?}, expected ms, s, m, h or d"
+            ));
+        }
+    };
+
+    Duration::try_from_secs_f64(seconds).map_err(|e| format!("invalid duration {value:?}: {e}"))
+}
+
+fn deserialize_duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_duration(&value).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_optional_duration<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    deserialize_duration(deserializer).map(Some)
+}
+
+fn default_http_status() -> u16 {
+    200
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+fn default_http_timeout() -> Duration {
+    Duration::from_secs(5)
+}
+
+fn default_webhook_timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
+fn default_webhook_attempts() -> u32 {
+    3
+}
+
+fn default_webhook_backoff() -> Duration {
+    Duration::from_secs(1)
+}
+
+fn invalid(key: &str, message: &str) -> Result<(), ConfigError> {
+    Err(ConfigError::Invalid {
+        key: key.to_string(),
+        message: message.to_string(),
+    })
+}
+
+fn positive(key: &str, value: Duration) -> Result<(), ConfigError> {
+    match value.is_zero() {
+        true => invalid(key, "must be greater than zero"),
+        false => Ok(()),
+    }
+}
+
+fn at_least_one(key: &str, value: usize) -> Result<(), ConfigError> {
+    match value {
+        0 => invalid(key, "must be at least 1"),
+        _ => Ok(()),
+    }
+}
+
+fn status_code(key: &str, value: u16) -> Result<(), ConfigError> {
+    match value {
+        100..=599 => Ok(()),
+        _ => invalid(key, &format!("{value} is not an HTTP status code")),
+    }
+}
+
+fn host_port(key: &str, value: &str) -> Result<(), ConfigError> {
+    let parts = value
+        .rsplit_once(':')
+        .map(|(host, port)| (host.trim_matches(['[', ']']), port.parse::<u16>()));
+    match parts {
+        Some((host, Ok(port))) if !host.is_empty() && port != 0 => Ok(()),
+        _ => invalid(key, &format!("{value:?} must be a host:port")),
+    }
+}
+
+fn http_url(key: &str, url: &str, schemes: &[&str]) -> Result<(), ConfigError> {
+    let has_host = schemes
+        .iter()
+        .filter_map(|scheme| url.strip_prefix(scheme))
+        .any(|rest| !rest.is_empty());
+    if has_host {
+        return Ok(());
+    }
+    invalid(
+        key,
+        &format!(
+            "{url:?} must be a URL starting with {}",
+            schemes.join(" or ")
+        ),
+    )
+}
@@ -0,0 +1,38 @@
+use crate::diagnosis::LayerCheck;
+
+/// Trait for checking the layers of the network stack below the internet.
+///
+/// Each check is only performed once the layers beneath it have passed, so implementations can
+/// assume, for instance, that a link is up when checking for an address.
+pub trait NetworkDiagnostics {
+    /// Checks a network interface has a link, such as a Wi-Fi association or an Ethernet carrier.
+    fn check_link(&self) -> LayerCheck;
+
+    /// Checks an interface with a link has been assigned an IP address, such as by DHCP.
+    fn check_address(&self) -> LayerCheck;
+
+    /// Checks a default gateway is configured and reachable.
+    fn check_gateway(&self) -> LayerCheck;
+
+    /// Checks names can be resolved.
+    fn check_dns(&self) -> LayerCheck;
+}
+
+/// Boxed diagnostics can be used wherever a [`NetworkDiagnostics`] is expected, so they can be chosen at runtime.
+impl<D: NetworkDiagnostics + ?Sized> NetworkDiagnostics for Box<D> {
+    fn check_link(&self) -> LayerCheck {
+        (**self).check_link()
+    }
+
+    fn check_address(&self) -> LayerCheck {
+        (**self).check_address()
+    }
+
+    fn check_gateway(&self) -> LayerCheck {
+        (**self).check_gateway()
+    }
+
+    fn check_dns(&self) -> LayerCheck {
+        (**self).check_dns()
+    }
+}
@@ -0,0 +1,301 @@
+use std::ffi::CStr;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::adapter::InterfaceSelector;
+use crate::diagnosis::{LayerCheck, NetworkDiagnostics};
+use crate::internet_connectivity::{
+    DnsOutcome, DnsProbe, Gateway, GatewayProbe, LinuxInternetConnectivity,
+};
+
+/// Default time allowed for each echo reply from a gateway.
+const DEFAULT_GATEWAY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Implementation of [`NetworkDiagnostics`] for Linux.
+///
+/// The link layer is read from `/sys/class/net`, and the address layer lists the addresses of each
+/// interface with `getifaddrs`, ignoring link-local ones, which are assigned without a DHCP server.
+/// The gateway layer pings the default gateways of the interfaces with a link, using a
+/// [`GatewayProbe`]. Every file is read relative to a configurable root directory, and the
+/// addresses can be set with [`LinuxDiagnostics::with_addresses`], so the checks can be exercised
+/// against fixtures.
+///
+/// The DNS layer queries the resolvers listed in `etc/resolv.conf` under the same root, unless
+/// another [`DnsProbe`] is set.
+pub struct LinuxDiagnostics {
+    root: PathBuf,
+    interface: Option<InterfaceSelector>,
+    dns: DnsProbe,
+    gateway_timeout: Duration,
+    addresses: Option<Vec<InterfaceAddress>>,
+}
+
+/// An IP address assigned to an interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceAddress {
+    /// Name of the interface, such as `wlan0`.
+    pub interface: String,
+    /// The address.
+    pub address: IpAddr,
+    /// Length of the network prefix, such as `24` for `192.168.2.5/24`.
+    pub prefix_len: u8,
+}
+
+impl InterfaceAddress {
+    /// Returns whether the address is link-local, which the host assigns itself when no DHCP
+    /// server answers.
+    pub fn is_link_local(&self) -> bool {
+        match self.address {
+            IpAddr::V4(address) => address.is_link_local(),
+            IpAddr::V6(address) => address.is_unicast_link_local(),
+        }
+    }
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`InterfaceAddress`] to enable easy printing.
+impl std::fmt::Display for InterfaceAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix_len)
+    }
+}
+
+impl Default for LinuxDiagnostics {
+    /// Creates a new instance of [`LinuxDiagnostics`] reading from `/`.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinuxDiagnostics {
+    /// Creates a new instance of [`LinuxDiagnostics`] reading from `/`.
+    pub fn new() -> Self {
+        Self::with_root("/")
+    }
+
+    /// Creates a new instance of [`LinuxDiagnostics`] reading from `root`.
+    ///
+    /// # Arguments
+    /// - `root`: Directory containing the `sys/class/net`, `proc/net` and `etc/resolv.conf` entries.
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        Self {
+            dns: DnsProbe::with_resolv_conf(root.join("etc/resolv.conf")),
+            root,
+            interface: None,
+            gateway_timeout: DEFAULT_GATEWAY_TIMEOUT,
+            addresses: None,
+        }
+    }
+
+    /// Scopes the checks to the interfaces chosen by `selector`.
+    pub fn with_interface(mut self, selector: InterfaceSelector) -> Self {
+        self.interface = Some(selector);
+        self
+    }
+
+    /// Sets the probe used to check the DNS layer.
+    pub fn with_dns_probe(mut self, dns: DnsProbe) -> Self {
+        self.dns = dns;
+        self
+    }
+
+    /// Sets the time allowed for each echo reply from a gateway.
+    pub fn with_gateway_timeout(mut self, timeout: Duration) -> Self {
+        self.gateway_timeout = timeout;
+        self
+    }
+
+    /// Sets the addresses assigned to the interfaces, in place of those listed by `getifaddrs`.
+    pub fn with_addresses(mut self, addresses: impl IntoIterator<Item = InterfaceAddress>) -> Self {
+        self.addresses = Some(addresses.into_iter().collect());
+        self
+    }
+
+    /// Returns the names of the selected interfaces whose link is up.
+    fn interfaces_up(&self) -> Vec<String> {
+        let connectivity = LinuxInternetConnectivity::with_root(&self.root);
+        let connectivity = match &self.interface {
+            Some(selector) => connectivity.with_interface(selector.clone()),
+            None => connectivity,
+        };
+
+        let selected = connectivity.selected_interfaces();
+        connectivity
+            .interfaces_up()
+            .into_iter()
+            .filter(|iface| selected.as_ref().is_none_or(|names| names.contains(iface)))
+            .collect()
+    }
+
+    /// Returns the addresses of `iface`, other than link-local ones.
+    fn addresses(&self, iface: &str) -> Vec<InterfaceAddress> {
+        let addresses = match &self.addresses {
+            Some(addresses) => addresses.clone(),
+            None => interface_addresses().unwrap_or_default(),
+        };
+        addresses
+            .into_iter()
+            .filter(|address| address.interface == iface && !address.is_link_local())
+            .collect()
+    }
+
+    /// Returns the [`GatewayProbe`] testing the gateways under the same root.
+    fn gateway_probe(&self) -> GatewayProbe<LinuxInternetConnectivity> {
+        let probe = GatewayProbe::new(LinuxInternetConnectivity::with_root(&self.root))
+            .with_root(&self.root)
+            .with_timeout(self.gateway_timeout);
+        match &self.interface {
+            Some(selector) => probe.with_interface(selector.clone()),
+            None => probe,
+        }
+    }
+}
+
+impl NetworkDiagnostics for LinuxDiagnostics {
+    fn check_link(&self) -> LayerCheck {
+        let up = self.interfaces_up();
+        match (up.is_empty(), &self.interface) {
+            (false, _) => LayerCheck::passed(format!("link up on {}", up.join(", "))),
+            (true, Some(selector)) => {
+                LayerCheck::failed(format!("no link on the interfaces selected by {selector}"))
+            }
+            (true, None) => LayerCheck::failed("no interface has a link"),
+        }
+    }
+
+    fn check_address(&self) -> LayerCheck {
+        let up = self.interfaces_up();
+        let assigned: Vec<String> = up
+            .iter()
+            .filter_map(|iface| {
+                let addresses: Vec<String> = self
+                    .addresses(iface)
+                    .iter()
+                    .map(InterfaceAddress::to_string)
+                    .collect();
+                (!addresses.is_empty()).then(|| format!("{iface} is on {}", addresses.join(", ")))
+            })
+            .collect();
+
+        match assigned.is_empty() {
+            false => LayerCheck::passed(assigned.join("; ")),
+            true => LayerCheck::failed(format!("no address assigned on {}", up.join(", "))),
+        }
+    }
+
+    fn check_gateway(&self) -> LayerCheck {
+        let up = self.interfaces_up();
+        let probe = self.gateway_probe();
+        let gateways: Vec<Gateway> = probe
+            .gateways()
+            .into_iter()
+            .filter(|gateway| up.contains(&gateway.interface))
+            .collect();
+        if gateways.is_empty() {
+            return LayerCheck::failed("no default route");
+        }
+
+        let mut failures = Vec::new();
+        for gateway in &gateways {
+            let result = probe.check(gateway);
+            match (result.rtt(), &result.neighbour, &result.ping.error) {
+                (Some(rtt), _, _) => {
+                    return LayerCheck::passed(format!("gateway {gateway} replied in {rtt:?}"));
+                }
+                (None, Some(mac), _) if result.is_reachable() => {
+                    return LayerCheck::passed(format!("gateway {gateway} resolved to {mac}"));
+                }
+                (None, _, Some(e)) if result.ping.socket.is_none() => failures.push(format!(
+                    "gateway {gateway} could not be pinged ({e}) and has no resolved hardware address"
+                )),
+                _ => failures.push(format!("gateway {gateway} did not reply")),
+            }
+        }
+        LayerCheck::failed(failures.join("; "))
+    }
+
+    fn check_dns(&self) -> LayerCheck {
+        let results = self.dns.probe();
+        if results.is_empty() {
+            return LayerCheck::failed("no DNS servers configured");
+        }
+
+        match results.iter().find(|result| result.is_success()) {
+            Some(result) => LayerCheck::passed(format!(
+                "{} resolved in {:?}",
+                result.server, result.latency
+            )),
+            None => LayerCheck::failed(
+                results
+                    .iter()
+                    .map(|result| match &result.outcome {
+                        DnsOutcome::ResponseCode(code) => {
+                            format!("{} answered with response code {code}", result.server)
+                        }
+                        DnsOutcome::Failed(e) => format!("{}: {e}", result.server),
+                        DnsOutcome::Resolved(_) => format!("{} resolved", result.server),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            ),
+        }
+    }
+}
+
+/// Returns the IPv4 and IPv6 addresses of every interface, listed with `getifaddrs`.
+fn interface_addresses() -> io::Result<Vec<InterfaceAddress>> {
+    let mut list: *mut libc::ifaddrs = std::ptr::null_mut();
+    if unsafe { libc::getifaddrs(&mut list) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut addresses = Vec::new();
+    let mut entry = list;
+    while let Some(ifaddr) = unsafe { entry.as_ref() } {
+        entry = ifaddr.ifa_next;
+        if ifaddr.ifa_addr.is_null() || ifaddr.ifa_netmask.is_null() {
+            continue;
+        }
+        let interface = unsafe { CStr::from_ptr(ifaddr.ifa_name) }
+            .to_string_lossy()
+            .into_owned();
+        let (address, netmask) = unsafe { (&*ifaddr.ifa_addr, &*ifaddr.ifa_netmask) };
+        let (address, prefix_len) = match i32::from(address.sa_family) {
+            libc::AF_INET => {
+                let (address, netmask) = unsafe {
+                    (
+                        &*(address as *const libc::sockaddr).cast::<libc::sockaddr_in>(),
+                        &*(netmask as *const libc::sockaddr).cast::<libc::sockaddr_in>(),
+                    )
+                };
+                (
+                    IpAddr::V4(Ipv4Addr::from(u32::from_be(address.sin_addr.s_addr))),
+                    u32::from_be(netmask.sin_addr.s_addr).count_ones(),
+                )
+            }
+            libc::AF_INET6 => {
+                let (address, netmask) = unsafe {
+                    (
+                        &*(address as *const libc::sockaddr).cast::<libc::sockaddr_in6>(),
+                        &*(netmask as *const libc::sockaddr).cast::<libc::sockaddr_in6>(),
+                    )
+                };
+                (
+                    IpAddr::V6(Ipv6Addr::from(address.sin6_addr.s6_addr)),
+                    u128::from_be_bytes(netmask.sin6_addr.s6_addr).count_ones(),
+                )
+            }
+            _ => continue,
+        };
+        addresses.push(InterfaceAddress {
+            interface,
+            address,
+            prefix_len: prefix_len as u8,
+        });
+    }
+
+    unsafe { libc::freeifaddrs(list) };
+    Ok(addresses)
+}
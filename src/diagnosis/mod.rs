@@ -0,0 +1,196 @@
+//! Module for diagnosing which layer of the network stack is failing.
+//!
+//! This module defines the [`NetworkDiagnostics`] trait, which checks the link, address, gateway
+//! and DNS layers, and the [`DiagnosisReport`] returned by [`crate::app::NetworkApp::diagnose`].
+
+mod interface;
+
+pub use interface::NetworkDiagnostics;
+
+cfg_if::cfg_if! {
+    if #[cfg(target_os = "linux")] {
+        mod linux;
+        pub use linux::{InterfaceAddress, LinuxDiagnostics};
+    }
+}
+
+use serde::{Deserialize, Serialize};
+
+/// A layer of the network stack, from the link up to the internet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Layer {
+    /// An interface has a link, such as a Wi-Fi association or an Ethernet carrier.
+    Link,
+
+    /// An interface with a link has an IP address.
+    Address,
+
+    /// A default gateway is configured and reachable.
+    Gateway,
+
+    /// Names can be resolved.
+    Dns,
+
+    /// The internet is reachable.
+    Internet,
+}
+
+impl Layer {
+    /// Every layer, in the order they are checked.
+    pub const ALL: [Layer; 5] = [
+        Layer::Link,
+        Layer::Address,
+        Layer::Gateway,
+        Layer::Dns,
+        Layer::Internet,
+    ];
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`Layer`] to enable easy printing.
+impl std::fmt::Display for Layer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Layer::Link => write!(f, "link"),
+            Layer::Address => write!(f, "address"),
+            Layer::Gateway => write!(f, "gateway"),
+            Layer::Dns => write!(f, "dns"),
+            Layer::Internet => write!(f, "internet"),
+        }
+    }
+}
+
+/// Outcome of checking a single layer with [`NetworkDiagnostics`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerCheck {
+    /// Whether the layer is working.
+    pub passed: bool,
+
+    /// What the check found, such as `wlan0 has no carrier`.
+    pub evidence: String,
+}
+
+impl LayerCheck {
+    /// Creates a [`LayerCheck`] for a working layer.
+    pub fn passed(evidence: impl Into<String>) -> Self {
+        Self {
+            passed: true,
+            evidence: evidence.into(),
+        }
+    }
+
+    /// Creates a [`LayerCheck`] for a failing layer.
+    pub fn failed(evidence: impl Into<String>) -> Self {
+        Self {
+            passed: false,
+            evidence: evidence.into(),
+        }
+    }
+}
+
+/// Whether a layer passed, failed or was not checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerStatus {
+    /// The layer is working.
+    Passed,
+
+    /// The layer is failing.
+    Failed,
+
+    /// The layer was not checked, as a layer beneath it failed or no check is available.
+    Skipped,
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`LayerStatus`] to enable easy printing.
+impl std::fmt::Display for LayerStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LayerStatus::Passed => write!(f, "passed"),
+            LayerStatus::Failed => write!(f, "failed"),
+            LayerStatus::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+/// Result of a single layer in a [`DiagnosisReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerResult {
+    /// The layer checked.
+    pub layer: Layer,
+
+    /// Whether the layer passed, failed or was not checked.
+    pub status: LayerStatus,
+
+    /// What the check found, or why it was skipped.
+    pub evidence: String,
+}
+
+impl LayerResult {
+    /// Creates a [`LayerResult`] from the outcome of checking `layer`.
+    pub fn checked(layer: Layer, check: LayerCheck) -> Self {
+        Self {
+            layer,
+            status: match check.passed {
+                true => LayerStatus::Passed,
+                false => LayerStatus::Failed,
+            },
+            evidence: check.evidence,
+        }
+    }
+
+    /// Creates a [`LayerResult`] for a layer which was not checked.
+    pub fn skipped(layer: Layer, reason: impl Into<String>) -> Self {
+        Self {
+            layer,
+            status: LayerStatus::Skipped,
+            evidence: reason.into(),
+        }
+    }
+}
+
+/// Result of checking every layer, from the link up to the internet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiagnosisReport {
+    /// The result of each layer, in the order they were checked.
+    pub layers: Vec<LayerResult>,
+}
+
+impl DiagnosisReport {
+    /// Returns the result of the first layer which failed, if any.
+    pub fn first_failure(&self) -> Option<&LayerResult> {
+        self.layers
+            .iter()
+            .find(|result| result.status == LayerStatus::Failed)
+    }
+
+    /// Returns the first layer which failed, if any.
+    pub fn failed_layer(&self) -> Option<Layer> {
+        self.first_failure().map(|result| result.layer)
+    }
+
+    /// Returns the result of `layer`, if it is part of the report.
+    pub fn layer(&self, layer: Layer) -> Option<&LayerResult> {
+        self.layers.iter().find(|result| result.layer == layer)
+    }
+
+    /// Returns `true` if no layer failed.
+    pub fn is_healthy(&self) -> bool {
+        self.first_failure().is_none()
+    }
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`DiagnosisReport`] to enable easy printing.
+///
+/// Each layer is printed on its own line, as its name, status and evidence separated by tabs.
+impl std::fmt::Display for DiagnosisReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for result in &self.layers {
+            writeln!(
+                f,
+                "{}\t{}\t{}",
+                result.layer, result.status, result.evidence
+            )?;
+        }
+        Ok(())
+    }
+}
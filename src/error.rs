@@ -0,0 +1,159 @@
+//! Module for the errors returned when reconnecting or remediating fails.
+//!
+//! This module defines the crate [`Error`], which records the [`Step`] that failed along with the
+//! error code reported by the operating system or backend, so callers can decide how to respond.
+
+use std::io;
+
+/// A specialized [`std::result::Result`] type for reconnect and remediation operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The step of a reconnect or remediation which failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Opening a handle or connection to the backend.
+    Open,
+
+    /// Finding the wireless interface or connection to reconnect.
+    FindInterface,
+
+    /// Querying the current connection.
+    QueryConnection,
+
+    /// Disconnecting from the current network.
+    Disconnect,
+
+    /// Connecting to the network.
+    Connect,
+
+    /// Running an external command.
+    RunCommand,
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`Step`] to enable easy printing.
+impl std::fmt::Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Step::Open => write!(f, "open"),
+            Step::FindInterface => write!(f, "find interface"),
+            Step::QueryConnection => write!(f, "query connection"),
+            Step::Disconnect => write!(f, "disconnect"),
+            Step::Connect => write!(f, "connect"),
+            Step::RunCommand => write!(f, "run command"),
+        }
+    }
+}
+
+/// Errors returned when reconnecting or remediating fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// An operating system call failed with an error code, such as a WLAN API result or `errno`.
+    Os {
+        /// The step which failed.
+        step: Step,
+
+        /// The error code returned by the operating system.
+        code: i32,
+    },
+
+    /// An I/O operation failed without an operating system error code.
+    Io {
+        /// The step which failed.
+        step: Step,
+
+        /// The kind of I/O error.
+        kind: io::ErrorKind,
+
+        /// Description of the error.
+        message: String,
+    },
+
+    /// The backend rejected a request, such as with a D-Bus error or a wpa_supplicant `FAIL` reply.
+    Backend {
+        /// The step which failed.
+        step: Step,
+
+        /// The error code reported by the backend, such as a D-Bus error name, if any.
+        code: Option<String>,
+
+        /// Description of the error.
+        message: String,
+    },
+
+    /// An external command ran, but exited unsuccessfully.
+    Command {
+        /// The program which was run.
+        program: String,
+
+        /// The exit code of the program, if it exited normally.
+        code: Option<i32>,
+    },
+
+    /// No wireless interface was found.
+    NoInterface,
+
+    /// No wireless connection is active, so there is nothing to reconnect.
+    NotConnected,
+}
+
+impl Error {
+    /// Creates an [`Error`] from an [`io::Error`], keeping its operating system error code if it has one.
+    ///
+    /// # Arguments
+    /// - `step`: The step which failed.
+    /// - `error`: The I/O error it failed with.
+    pub fn io(step: Step, error: &io::Error) -> Self {
+        match error.raw_os_error() {
+            Some(code) => Error::Os { step, code },
+            None => Error::Io {
+                step,
+                kind: error.kind(),
+                message: error.to_string(),
+            },
+        }
+    }
+
+    /// Returns the step which failed, if the error is tied to one.
+    pub fn step(&self) -> Option<Step> {
+        match self {
+            Error::Os { step, .. } | Error::Io { step, .. } | Error::Backend { step, .. } => {
+                Some(*step)
+            }
+            Error::Command { .. } => Some(Step::RunCommand),
+            Error::NoInterface => Some(Step::FindInterface),
+            Error::NotConnected => Some(Step::QueryConnection),
+        }
+    }
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`Error`] to enable easy printing.
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::Os { step, code } => write!(f, "{step} failed with OS error {code}"),
+            Error::Io { step, message, .. } => write!(f, "{step} failed: {message}"),
+            Error::Backend {
+                step,
+                code: Some(code),
+                message,
+            } => write!(f, "{step} failed with {code}: {message}"),
+            Error::Backend {
+                step,
+                code: None,
+                message,
+            } => write!(f, "{step} failed: {message}"),
+            Error::Command {
+                program,
+                code: Some(code),
+            } => write!(f, "{program} exited with code {code}"),
+            Error::Command {
+                program,
+                code: None,
+            } => write!(f, "{program} was terminated by a signal"),
+            Error::NoInterface => write!(f, "no wireless interface found"),
+            Error::NotConnected => write!(f, "no active wireless connection"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
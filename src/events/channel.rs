@@ -0,0 +1,45 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::events::{EventSubscriber, NetworkEvent};
+
+/// Implementation of [`EventSubscriber`] which sends a copy of every event over a channel.
+///
+/// This lets events be consumed on another thread, such as by a UI or a notifier which must not
+/// delay polling. Events sent after the receiver is dropped are discarded.
+pub struct ChannelSubscriber {
+    sender: Sender<NetworkEvent>,
+}
+
+impl ChannelSubscriber {
+    /// Creates a new instance of [`ChannelSubscriber`] sending events to `sender`.
+    pub fn new(sender: Sender<NetworkEvent>) -> Self {
+        Self { sender }
+    }
+
+    /// Creates a new channel, returning a [`ChannelSubscriber`] sending to it and the receiving end.
+    pub fn channel() -> (Self, Receiver<NetworkEvent>) {
+        let (sender, receiver) = mpsc::channel();
+        (Self::new(sender), receiver)
+    }
+
+    /// Creates a [`ChannelSubscriber`] which forwards events to `subscriber` on a new thread.
+    ///
+    /// The thread exits once the returned subscriber is dropped and every event sent before then
+    /// has been handled.
+    pub fn spawn(subscriber: impl EventSubscriber + Send + 'static) -> Self {
+        let (forwarder, events) = Self::channel();
+        thread::spawn(move || {
+            for event in events {
+                subscriber.on_event(&event);
+            }
+        });
+        forwarder
+    }
+}
+
+impl EventSubscriber for ChannelSubscriber {
+    fn on_event(&self, event: &NetworkEvent) {
+        let _ = self.sender.send(event.clone());
+    }
+}
@@ -0,0 +1,18 @@
+use crate::events::NetworkEvent;
+
+/// Trait for receiving the [`NetworkEvent`]s emitted by [`crate::app::NetworkApp`].
+///
+/// Subscribers are called synchronously, in the order they were registered, from the thread
+/// polling the network, so they should return quickly. Slow consumers can be moved to another
+/// thread with a [`crate::events::ChannelSubscriber`].
+pub trait EventSubscriber {
+    /// Called for every event, in the order the events occur.
+    fn on_event(&self, event: &NetworkEvent);
+}
+
+/// Boxed subscribers can be used wherever an [`EventSubscriber`] is expected, so they can be chosen at runtime.
+impl<S: EventSubscriber + ?Sized> EventSubscriber for Box<S> {
+    fn on_event(&self, event: &NetworkEvent) {
+        (**self).on_event(event)
+    }
+}
@@ -0,0 +1,138 @@
+//! Module for observing what [`crate::app::NetworkApp`] does.
+//!
+//! This module defines the [`NetworkEvent`]s emitted while polling, and the [`EventSubscriber`]
+//! trait which receives them. Any number of subscribers can be registered with
+//! [`crate::app::NetworkApp::with_subscriber`]; a [`ChannelSubscriber`] forwards events to another
+//! thread.
+
+mod channel;
+mod interface;
+
+pub use channel::ChannelSubscriber;
+pub use interface::EventSubscriber;
+
+use crate::app::{NetworkStatus, VerificationResult};
+use crate::diagnosis::DiagnosisReport;
+use crate::remediation::RemediationAttempt;
+
+/// A check performed during a poll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeKind {
+    /// Whether the system is connected to a network.
+    Network,
+
+    /// Whether traffic is free of a captive portal.
+    CaptivePortal,
+
+    /// Whether the internet is reachable.
+    Internet,
+
+    /// Whether the connection quality is within the thresholds.
+    Quality,
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`ProbeKind`] to enable easy printing.
+impl std::fmt::Display for ProbeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ProbeKind::Network => write!(f, "network"),
+            ProbeKind::CaptivePortal => write!(f, "captive portal"),
+            ProbeKind::Internet => write!(f, "internet"),
+            ProbeKind::Quality => write!(f, "quality"),
+        }
+    }
+}
+
+/// Something [`crate::app::NetworkApp`] did while polling.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkEvent {
+    /// A poll started.
+    PollStarted,
+
+    /// A check performed during a poll completed.
+    ProbeResult {
+        /// The check performed.
+        probe: ProbeKind,
+
+        /// Whether the check passed; a captive portal check passes when no portal is detected.
+        passed: bool,
+    },
+
+    /// The status differs from the status last reported, either before a remediation is attempted
+    /// or once a poll completes.
+    StatusChanged {
+        /// The status last reported, or `None` for the first poll.
+        from: Option<NetworkStatus>,
+
+        /// The new status.
+        to: NetworkStatus,
+    },
+
+    /// The status became an outage, and each layer of the network stack was checked to find
+    /// which one failed.
+    ///
+    /// Only emitted if [`crate::app::NetworkApp::with_diagnostics`] was used.
+    Diagnosed {
+        /// The result of each layer.
+        report: DiagnosisReport,
+    },
+
+    /// A rung of the remediation ladder is about to be attempted.
+    RemediationAttempted {
+        /// Index of the rung, where `0` is reconnecting.
+        rung: usize,
+
+        /// Name of the action.
+        action: String,
+    },
+
+    /// A remediation finished.
+    RemediationResult {
+        /// The rung attempted and the result it reported.
+        attempt: RemediationAttempt,
+
+        /// The verification performed if the remediation reported success.
+        verification: Option<VerificationResult>,
+    },
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`NetworkEvent`] to enable easy printing.
+impl std::fmt::Display for NetworkEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            NetworkEvent::PollStarted => write!(f, "poll started"),
+            NetworkEvent::ProbeResult { probe, passed } => match passed {
+                true => write!(f, "{probe} check passed"),
+                false => write!(f, "{probe} check failed"),
+            },
+            NetworkEvent::StatusChanged { from: None, to } => write!(f, "status is {to}"),
+            NetworkEvent::StatusChanged {
+                from: Some(from),
+                to,
+            } => write!(f, "status changed from {from} to {to}"),
+            NetworkEvent::Diagnosed { report } => match report.first_failure() {
+                Some(failure) => write!(f, "{} failed: {}", failure.layer, failure.evidence),
+                None => write!(f, "every layer passed"),
+            },
+            NetworkEvent::RemediationAttempted { rung, action } => {
+                write!(f, "attempting {action} (rung {rung})")
+            }
+            NetworkEvent::RemediationResult {
+                attempt,
+                verification,
+            } => match (&attempt.result, verification) {
+                (Err(e), _) => write!(f, "{} failed: {e}", attempt.action),
+                (Ok(()), Some(verification)) if verification.recovered => write!(
+                    f,
+                    "{} restored the internet in {:?}",
+                    attempt.action, verification.elapsed
+                ),
+                (Ok(()), _) => write!(
+                    f,
+                    "{} succeeded, but the internet did not return",
+                    attempt.action
+                ),
+            },
+        }
+    }
+}
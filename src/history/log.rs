@@ -0,0 +1,99 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tracing::warn;
+
+use crate::history::{HistoryRecord, Outage, outages};
+
+/// An append-only file of [`HistoryRecord`]s, one JSON record per line.
+///
+/// Records are only ever appended, so the file can be kept as evidence and read by other tools.
+/// Lines which cannot be read, such as one cut short by a crash, are skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryLog {
+    path: PathBuf,
+}
+
+impl HistoryLog {
+    /// Creates a new instance of [`HistoryLog`] for the file at `path`, which is created when the
+    /// first record is appended.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends `record` to the end of the file, creating the file and its directory if needed.
+    pub fn append(&self, record: &HistoryRecord) -> io::Result<()> {
+        if let Some(parent) = self
+            .path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(&line)
+    }
+
+    /// Reads every record, oldest first, or none if the file does not exist.
+    pub fn records(&self) -> io::Result<Vec<HistoryRecord>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        Ok(contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .filter_map(|(i, line)| match serde_json::from_str(line) {
+                Ok(record) => Some(record),
+                Err(e) => {
+                    warn!(
+                        path = %self.path.display(),
+                        line = i + 1,
+                        error = %e,
+                        "Skipping invalid history record"
+                    );
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Returns the outages which overlap the time between `from` and `to`, in the order they started.
+    ///
+    /// Outages are returned whole, including any part before `from` or after `to`.
+    pub fn outages(&self, from: SystemTime, to: SystemTime) -> io::Result<Vec<Outage>> {
+        Ok(outages(&self.records()?)
+            .into_iter()
+            .filter(|outage| outage.overlaps(from, to))
+            .collect())
+    }
+
+    /// Returns the total time the internet was unavailable between `from` and `to`.
+    ///
+    /// Only the part of each outage between `from` and `to` is counted, and an ongoing outage is
+    /// counted until now.
+    pub fn downtime(&self, from: SystemTime, to: SystemTime) -> io::Result<Duration> {
+        let now = SystemTime::now();
+        Ok(self
+            .outages(from, to)?
+            .iter()
+            .map(|outage| outage.overlap(from, to, now))
+            .sum())
+    }
+}
@@ -0,0 +1,198 @@
+//! Module for keeping a history of outages.
+//!
+//! This module defines the [`HistoryRecord`]s which the [`HistoryRecorder`] appends to a
+//! [`HistoryLog`] for every status change and remediation, and the [`Outage`]s derived from them,
+//! so how often and for how long the connection failed can be shown afterwards.
+
+mod log;
+mod recorder;
+
+pub use log::HistoryLog;
+pub use recorder::HistoryRecorder;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::diagnosis::Layer;
+
+/// A single entry of a [`HistoryLog`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    /// Time of the occurrence, in seconds since the Unix epoch.
+    pub timestamp: u64,
+
+    /// What happened.
+    #[serde(flatten)]
+    pub event: HistoryEvent,
+}
+
+impl HistoryRecord {
+    /// Returns the time of the occurrence.
+    pub fn time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.timestamp)
+    }
+}
+
+/// What a [`HistoryRecord`] records.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HistoryEvent {
+    /// The status changed.
+    StatusChanged {
+        /// The previous status, or `None` when monitoring started.
+        from: Option<String>,
+
+        /// The new status.
+        to: String,
+
+        /// Whether the new status means the internet is unavailable.
+        outage: bool,
+
+        /// The results of the checks which led to the new status, such as `internet check failed`.
+        evidence: Vec<String>,
+    },
+
+    /// The layers of the network stack were diagnosed when an outage started.
+    Diagnosis {
+        /// The first layer which failed, if any.
+        failed_layer: Option<Layer>,
+
+        /// What was found for each layer checked, such as `dns failed: no server replied`.
+        evidence: Vec<String>,
+    },
+
+    /// A rung of the remediation ladder was attempted.
+    Remediation {
+        /// Index of the rung, where `0` is reconnecting.
+        rung: usize,
+
+        /// Name of the action.
+        action: String,
+
+        /// The error the action failed with, if it failed.
+        error: Option<String>,
+
+        /// Whether the internet returned after the action succeeded, if it was verified.
+        recovered: Option<bool>,
+
+        /// Seconds spent waiting for the internet to return, if it was verified.
+        verification_secs: Option<u64>,
+    },
+}
+
+/// A period during which the internet was unavailable, derived from a [`HistoryLog`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outage {
+    /// When the status first became an outage.
+    pub started: SystemTime,
+
+    /// When the status next became healthy, or `None` if the outage is ongoing.
+    ///
+    /// If monitoring stopped during the outage, it ends at the last record before monitoring
+    /// restarted, as nothing is known of the time in between.
+    pub ended: Option<SystemTime>,
+
+    /// The status which started the outage, such as `Disconnected` or `NetworkOnly`.
+    pub status: String,
+
+    /// The results of the checks which started the outage, followed by those of its diagnosis.
+    pub evidence: Vec<String>,
+
+    /// The first layer which failed when the outage was diagnosed, if it was diagnosed.
+    pub failed_layer: Option<Layer>,
+
+    /// The remediations attempted during the outage, in order.
+    pub remediations: Vec<HistoryRecord>,
+}
+
+impl Outage {
+    /// Returns how long the outage lasted, or has lasted until `now` if it is ongoing.
+    pub fn duration(&self, now: SystemTime) -> Duration {
+        let ended = self.ended.unwrap_or(now);
+        ended.duration_since(self.started).unwrap_or_default()
+    }
+
+    /// Returns how much of the outage fell between `from` and `to`, treating an ongoing outage as
+    /// lasting until `now`.
+    pub fn overlap(&self, from: SystemTime, to: SystemTime, now: SystemTime) -> Duration {
+        let started = self.started.max(from);
+        let ended = self.ended.unwrap_or(now).min(to);
+        ended.duration_since(started).unwrap_or_default()
+    }
+
+    /// Returns `true` if the outage started before `to` and had not ended by `from`.
+    pub fn overlaps(&self, from: SystemTime, to: SystemTime) -> bool {
+        self.started < to && self.ended.is_none_or(|ended| ended > from)
+    }
+}
+
+/// Derives the [`Outage`]s recorded by `records`, in the order they started.
+///
+/// An outage starts with the first status change to an outage status and ends with the next
+/// change to a healthy status; the first diagnosis and every remediation in between are attached
+/// to it. The last outage has no end if no healthy status follows it.
+///
+/// A status change from no previous status marks monitoring restarting, so an outage still open
+/// at that point ends at the last record before it, rather than spanning the time monitoring was
+/// stopped.
+pub fn outages(records: &[HistoryRecord]) -> Vec<Outage> {
+    let mut outages = Vec::new();
+    let mut current: Option<Outage> = None;
+    let mut last = None;
+
+    for record in records {
+        if let HistoryEvent::StatusChanged { from: None, .. } = &record.event
+            && let Some(mut outage) = current.take()
+        {
+            outage.ended = Some(last.unwrap_or(outage.started));
+            outages.push(outage);
+        }
+        last = Some(record.time());
+
+        match &record.event {
+            HistoryEvent::StatusChanged {
+                to,
+                outage: true,
+                evidence,
+                ..
+            } if current.is_none() => {
+                current = Some(Outage {
+                    started: record.time(),
+                    ended: None,
+                    status: to.clone(),
+                    evidence: evidence.clone(),
+                    failed_layer: None,
+                    remediations: Vec::new(),
+                });
+            }
+            HistoryEvent::StatusChanged { outage: false, .. } => {
+                if let Some(mut outage) = current.take() {
+                    outage.ended = Some(record.time());
+                    outages.push(outage);
+                }
+            }
+            HistoryEvent::Diagnosis {
+                failed_layer,
+                evidence,
+            } => {
+                if let Some(outage) = current
+                    .as_mut()
+                    .filter(|outage| outage.failed_layer.is_none())
+                {
+                    outage.failed_layer = *failed_layer;
+                    outage.evidence.extend(evidence.iter().cloned());
+                }
+            }
+            HistoryEvent::Remediation { .. } => {
+                if let Some(outage) = current.as_mut() {
+                    outage.remediations.push(record.clone());
+                }
+            }
+            HistoryEvent::StatusChanged { .. } => {}
+        }
+    }
+
+    outages.extend(current);
+    outages
+}
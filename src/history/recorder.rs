@@ -0,0 +1,84 @@
+use std::cell::RefCell;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tracing::warn;
+
+use crate::diagnosis::LayerStatus;
+use crate::events::{EventSubscriber, NetworkEvent};
+use crate::history::{HistoryEvent, HistoryLog, HistoryRecord};
+
+/// Implementation of [`EventSubscriber`] which appends every status change and remediation to a
+/// [`HistoryLog`].
+///
+/// Each status change is recorded with the results of the checks performed during the same poll,
+/// as evidence of why the status changed. Failing to append is logged rather than interrupting
+/// monitoring.
+pub struct HistoryRecorder {
+    log: HistoryLog,
+    evidence: RefCell<Vec<String>>,
+}
+
+impl HistoryRecorder {
+    /// Creates a new instance of [`HistoryRecorder`] appending to `log`.
+    pub fn new(log: HistoryLog) -> Self {
+        Self {
+            log,
+            evidence: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns the log records are appended to.
+    pub fn log(&self) -> &HistoryLog {
+        &self.log
+    }
+
+    fn append(&self, event: HistoryEvent) {
+        let record = HistoryRecord {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            event,
+        };
+        if let Err(e) = self.log.append(&record) {
+            warn!(path = %self.log.path().display(), error = %e, "Failed to append to history");
+        }
+    }
+}
+
+impl EventSubscriber for HistoryRecorder {
+    fn on_event(&self, event: &NetworkEvent) {
+        match event {
+            NetworkEvent::PollStarted => self.evidence.borrow_mut().clear(),
+            NetworkEvent::ProbeResult { .. } => self.evidence.borrow_mut().push(event.to_string()),
+            NetworkEvent::StatusChanged { from, to } => self.append(HistoryEvent::StatusChanged {
+                from: from.as_ref().map(ToString::to_string),
+                to: to.to_string(),
+                outage: to.is_outage(),
+                evidence: self.evidence.borrow().clone(),
+            }),
+            NetworkEvent::Diagnosed { report } => self.append(HistoryEvent::Diagnosis {
+                failed_layer: report.failed_layer(),
+                evidence: report
+                    .layers
+                    .iter()
+                    .filter(|result| result.status != LayerStatus::Skipped)
+                    .map(|result| {
+                        format!("{} {}: {}", result.layer, result.status, result.evidence)
+                    })
+                    .collect(),
+            }),
+            NetworkEvent::RemediationAttempted { .. } => {}
+            NetworkEvent::RemediationResult {
+                attempt,
+                verification,
+            } => self.append(HistoryEvent::Remediation {
+                rung: attempt.rung,
+                action: attempt.action.clone(),
+                error: attempt.result.as_ref().err().map(ToString::to_string),
+                recovered: verification.map(|verification| verification.recovered),
+                verification_secs: verification.map(|verification| verification.elapsed.as_secs()),
+            }),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Module for debouncing raw connectivity results.
+//!
+//! This module defines the [`Hysteresis`] settings and the [`Debouncer`] which applies them, so a
+//! momentary failed probe does not immediately declare an outage, and a single successful probe
+//! does not immediately declare recovery.
+
+use std::collections::VecDeque;
+
+/// The debounced state of internet connectivity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkState {
+    /// The internet is considered available.
+    Up,
+
+    /// An outage has been declared.
+    Down,
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`LinkState`] to enable easy printing.
+impl std::fmt::Display for LinkState {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LinkState::Up => write!(f, "Up"),
+            LinkState::Down => write!(f, "Down"),
+        }
+    }
+}
+
+/// A change of the debounced [`LinkState`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transition {
+    /// The state before the change.
+    pub from: LinkState,
+
+    /// The state after the change.
+    pub to: LinkState,
+}
+
+/// Settings controlling when outages and recoveries are declared.
+///
+/// The default settings declare an outage on the first failure and recovery on the first
+/// success, which is equivalent to no debouncing. When only a failure window is set, outages are
+/// declared by the window alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hysteresis {
+    consecutive_failures: Option<usize>,
+    failure_window: Option<(usize, usize)>,
+    recovery_successes: usize,
+}
+
+impl Default for Hysteresis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hysteresis {
+    /// Creates new [`Hysteresis`] settings which perform no debouncing.
+    pub fn new() -> Self {
+        Self {
+            consecutive_failures: None,
+            failure_window: None,
+            recovery_successes: 1,
+        }
+    }
+
+    /// Declares an outage after `count` consecutive failures.
+    pub fn with_consecutive_failures(mut self, count: usize) -> Self {
+        self.consecutive_failures = Some(count.max(1));
+        self
+    }
+
+    /// Declares an outage when `failures` of the last `window` results failed, as well as after
+    /// the consecutive failures, if set.
+    pub fn with_failure_window(mut self, failures: usize, window: usize) -> Self {
+        let window = window.max(1);
+        self.failure_window = Some((failures.clamp(1, window), window));
+        self
+    }
+
+    /// Declares recovery after `count` consecutive successes.
+    pub fn with_recovery_successes(mut self, count: usize) -> Self {
+        self.recovery_successes = count.max(1);
+        self
+    }
+}
+
+/// Applies [`Hysteresis`] settings to a stream of raw connectivity results.
+#[derive(Debug, Clone)]
+pub struct Debouncer {
+    settings: Hysteresis,
+    state: LinkState,
+    consecutive_failures: usize,
+    consecutive_successes: usize,
+    history: VecDeque<bool>,
+}
+
+impl Debouncer {
+    /// Creates a new [`Debouncer`] in the [`LinkState::Up`] state.
+    pub fn new(settings: Hysteresis) -> Self {
+        Self {
+            settings,
+            state: LinkState::Up,
+            consecutive_failures: 0,
+            consecutive_successes: 0,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Replaces the settings, keeping the current state and the results recorded so far.
+    ///
+    /// Used when the settings are reloaded during an outage, so the outage is not declared again.
+    pub fn with_settings(mut self, settings: Hysteresis) -> Self {
+        self.settings = settings;
+        match settings.failure_window {
+            Some((_, window)) => {
+                while self.history.len() > window {
+                    self.history.pop_front();
+                }
+            }
+            None => self.history.clear(),
+        }
+        self
+    }
+
+    /// Returns the settings applied.
+    pub fn settings(&self) -> Hysteresis {
+        self.settings
+    }
+
+    /// Returns the current debounced state.
+    pub fn state(&self) -> LinkState {
+        self.state
+    }
+
+    /// Records a raw result, returning the [`Transition`] it caused, if any.
+    ///
+    /// # Arguments
+    /// - `success`: Whether the raw connectivity check succeeded.
+    pub fn record(&mut self, success: bool) -> Option<Transition> {
+        if success {
+            self.consecutive_successes += 1;
+            self.consecutive_failures = 0;
+        } else {
+            self.consecutive_failures += 1;
+            self.consecutive_successes = 0;
+        }
+
+        if let Some((_, window)) = self.settings.failure_window {
+            self.history.push_back(success);
+            while self.history.len() > window {
+                self.history.pop_front();
+            }
+        }
+
+        let next = match self.state {
+            LinkState::Up if self.outage_reached() => LinkState::Down,
+            LinkState::Down if self.consecutive_successes >= self.settings.recovery_successes => {
+                LinkState::Up
+            }
+            state => state,
+        };
+
+        if next == self.state {
+            return None;
+        }
+
+        let transition = Transition {
+            from: self.state,
+            to: next,
+        };
+        self.state = next;
+        self.history.clear();
+        Some(transition)
+    }
+
+    fn outage_reached(&self) -> bool {
+        let window_reached = self.settings.failure_window.is_some_and(|(failures, _)| {
+            self.history.iter().filter(|success| !**success).count() >= failures
+        });
+
+        let consecutive = match self.settings.failure_window {
+            Some(_) => self.settings.consecutive_failures,
+            None => Some(self.settings.consecutive_failures.unwrap_or(1)),
+        };
+        let consecutive_reached =
+            consecutive.is_some_and(|count| self.consecutive_failures >= count);
+
+        consecutive_reached || window_reached
+    }
+}
@@ -0,0 +1,139 @@
+use std::time::Duration;
+
+use ureq::Agent;
+
+use crate::internet_connectivity::http::{agent, request};
+use crate::internet_connectivity::{CaptivePortal, HttpProbeResult, InternetConnectivity};
+
+/// Default time allowed for each known-content request.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A URL serving known content, used to detect captive portals.
+///
+/// Captive portals intercept plain HTTP requests, so a response that does not match the known
+/// content indicates a portal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownContent {
+    /// The plain HTTP URL to request.
+    pub url: String,
+
+    /// The status code the URL responds with when not intercepted.
+    pub expected_status: u16,
+
+    /// Content the body contains when not intercepted, if any.
+    pub expected_body: Option<String>,
+}
+
+impl KnownContent {
+    /// Creates a new [`KnownContent`] check.
+    ///
+    /// # Arguments
+    /// - `url`: The plain HTTP URL to request.
+    /// - `expected_status`: The status code the URL responds with when not intercepted.
+    /// - `expected_body`: Content the body contains when not intercepted, if any.
+    pub fn new(url: impl Into<String>, expected_status: u16, expected_body: Option<&str>) -> Self {
+        Self {
+            url: url.into(),
+            expected_status,
+            expected_body: expected_body.map(str::to_string),
+        }
+    }
+
+    /// The known-content checks used by default: Google's `generate_204` and Apple's hotspot detection page.
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new(
+                "http://connectivitycheck.gstatic.com/generate_204",
+                204,
+                None,
+            ),
+            Self::new(
+                "http://captive.apple.com/hotspot-detect.html",
+                200,
+                Some("Success"),
+            ),
+        ]
+    }
+}
+
+/// Adds captive portal detection to any [`InternetConnectivity`] implementation.
+///
+/// Connectivity checks are delegated to the wrapped checker, while
+/// [`InternetConnectivity::captive_portal`] requests a list of [`KnownContent`] URLs. The first
+/// URL to respond decides the result: matching content means there is no portal, while a
+/// redirect or unexpected content means there is one.
+pub struct CaptivePortalDetector<C: InternetConnectivity> {
+    inner: C,
+    checks: Vec<KnownContent>,
+    timeout: Duration,
+}
+
+impl<C: InternetConnectivity> CaptivePortalDetector<C> {
+    /// Creates a new instance of [`CaptivePortalDetector`] using [`KnownContent::defaults`].
+    ///
+    /// # Arguments
+    /// - `inner`: The checker to delegate connectivity checks to.
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            checks: KnownContent::defaults(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Replaces the known-content checks.
+    pub fn with_checks(mut self, checks: impl IntoIterator<Item = KnownContent>) -> Self {
+        self.checks = checks.into_iter().collect();
+        self
+    }
+
+    /// Sets the time allowed for each known-content request.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Requests every known-content URL and returns the result for each, in order.
+    pub fn probe(&self) -> Vec<HttpProbeResult> {
+        let agent = agent(self.timeout);
+        self.checks
+            .iter()
+            .map(|check| Self::request(&agent, check))
+            .collect()
+    }
+
+    fn request(agent: &Agent, check: &KnownContent) -> HttpProbeResult {
+        request(
+            agent,
+            &check.url,
+            check.expected_status,
+            check.expected_body.as_deref(),
+        )
+    }
+}
+
+impl<C: InternetConnectivity> InternetConnectivity for CaptivePortalDetector<C> {
+    fn is_connected_to_network(&self) -> bool {
+        self.inner.is_connected_to_network()
+    }
+
+    fn is_connected_to_internet(&self) -> bool {
+        self.inner.is_connected_to_internet()
+    }
+
+    fn captive_portal(&self) -> Option<CaptivePortal> {
+        let agent = agent(self.timeout);
+
+        for check in &self.checks {
+            let result = Self::request(&agent, check);
+            if result.is_success() {
+                return None;
+            }
+            if let Some(portal) = result.captive_portal() {
+                return Some(portal);
+            }
+        }
+
+        None
+    }
+}
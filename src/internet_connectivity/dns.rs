@@ -0,0 +1,446 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tracing::warn;
+
+use crate::internet_connectivity::socket;
+use crate::internet_connectivity::{InternetConnectivity, SystemInternetConnectivity};
+
+/// Location of the system resolver configuration.
+const RESOLV_CONF: &str = "/etc/resolv.conf";
+
+/// Port DNS servers listen on.
+const DNS_PORT: u16 = 53;
+
+/// Name queried when none is configured.
+const DEFAULT_QUERY: &str = "www.google.com";
+
+/// Default time allowed for each query.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Largest DNS message accepted over UDP.
+const MAX_UDP_MESSAGE_SIZE: usize = 4096;
+
+/// Size of the fixed DNS message header.
+const HEADER_SIZE: usize = 12;
+
+/// Flag requesting recursive resolution.
+const FLAG_RECURSION_DESIRED: u16 = 0x0100;
+
+/// Flag set on responses.
+const FLAG_RESPONSE: u16 = 0x8000;
+
+/// Flag set on responses which were truncated to fit in a UDP datagram.
+const FLAG_TRUNCATED: u16 = 0x0200;
+
+/// The `IN` (internet) class.
+const CLASS_IN: u16 = 1;
+
+/// Counter mixed into query identifiers so concurrent queries do not collide.
+static QUERY_COUNTER: AtomicU16 = AtomicU16::new(0);
+
+/// DNS record types the probe can query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DnsRecordType {
+    /// IPv4 address record.
+    A,
+
+    /// IPv6 address record.
+    Aaaa,
+}
+
+impl DnsRecordType {
+    fn code(self) -> u16 {
+        match self {
+            DnsRecordType::A => 1,
+            DnsRecordType::Aaaa => 28,
+        }
+    }
+}
+
+/// Transport a DNS response was received over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DnsTransport {
+    /// The response was received over UDP.
+    Udp,
+
+    /// The response was received over TCP, after UDP failed or was truncated.
+    Tcp,
+}
+
+/// Outcome of querying a single DNS server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DnsOutcome {
+    /// The server resolved the query to at least one address.
+    Resolved(Vec<IpAddr>),
+
+    /// The server responded with an error code, such as `3` (`NXDOMAIN`), or with no addresses (`0`).
+    ResponseCode(u8),
+
+    /// No valid response was received.
+    Failed(String),
+}
+
+/// Result of querying a single DNS server with [`DnsProbe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsServerResult {
+    /// The server that was queried.
+    pub server: SocketAddr,
+
+    /// The transport the final response was received over, if any response was received.
+    pub transport: Option<DnsTransport>,
+
+    /// Time taken to receive a response, or to give up.
+    pub latency: Duration,
+
+    /// Whether the server resolved the query.
+    pub outcome: DnsOutcome,
+}
+
+impl DnsServerResult {
+    /// Returns `true` if the server resolved the query to at least one address.
+    pub fn is_success(&self) -> bool {
+        matches!(self.outcome, DnsOutcome::Resolved(_))
+    }
+}
+
+/// The servers a [`DnsProbe`] queries.
+#[derive(Debug, Clone)]
+enum DnsServers {
+    /// The `nameserver` entries of a resolv.conf file, read on every probe.
+    ResolvConf(PathBuf),
+
+    /// An explicit list of servers.
+    Explicit(Vec<SocketAddr>),
+}
+
+/// Implementation of [`InternetConnectivity`] which sends raw DNS queries to a set of resolvers.
+///
+/// Queries are sent over UDP, falling back to TCP when the UDP response is truncated or no valid
+/// UDP response is received. The system is considered connected to the internet when any server
+/// resolves the query.
+///
+/// Network connectivity is delegated to `N`, which defaults to the [`SystemInternetConnectivity`]
+/// for the current OS.
+pub struct DnsProbe<N: InternetConnectivity = SystemInternetConnectivity> {
+    network: N,
+    servers: DnsServers,
+    query: String,
+    record_type: DnsRecordType,
+    timeout: Duration,
+    device: Option<String>,
+}
+
+impl DnsProbe {
+    /// Creates a new instance of [`DnsProbe`] querying the system resolvers from `/etc/resolv.conf`.
+    ///
+    /// The file is not available on Windows, where [`DnsProbe::with_servers`] should be used instead.
+    pub fn system() -> Self {
+        Self::with_resolv_conf(RESOLV_CONF)
+    }
+
+    /// Creates a new instance of [`DnsProbe`] querying the resolvers listed in a resolv.conf file.
+    ///
+    /// # Arguments
+    /// - `path`: Path of the file, which is re-read on every probe to follow resolver changes.
+    pub fn with_resolv_conf(path: impl Into<PathBuf>) -> Self {
+        Self::from_servers(DnsServers::ResolvConf(path.into()))
+    }
+
+    /// Creates a new instance of [`DnsProbe`] querying an explicit list of servers.
+    ///
+    /// # Arguments
+    /// - `servers`: The servers to query, in order.
+    pub fn with_servers(servers: impl IntoIterator<Item = SocketAddr>) -> Self {
+        Self::from_servers(DnsServers::Explicit(servers.into_iter().collect()))
+    }
+
+    fn from_servers(servers: DnsServers) -> Self {
+        Self {
+            network: SystemInternetConnectivity::default(),
+            servers,
+            query: DEFAULT_QUERY.to_string(),
+            record_type: DnsRecordType::A,
+            timeout: DEFAULT_TIMEOUT,
+            device: None,
+        }
+    }
+}
+
+impl<N: InternetConnectivity> DnsProbe<N> {
+    /// Replaces the checker used for [`InternetConnectivity::is_connected_to_network`].
+    pub fn with_network<M: InternetConnectivity>(self, network: M) -> DnsProbe<M> {
+        DnsProbe {
+            network,
+            servers: self.servers,
+            query: self.query,
+            record_type: self.record_type,
+            timeout: self.timeout,
+            device: self.device,
+        }
+    }
+
+    /// Sets the name to resolve.
+    pub fn with_query(mut self, query: impl Into<String>) -> Self {
+        self.query = query.into();
+        self
+    }
+
+    /// Sets the record type to query for.
+    pub fn with_record_type(mut self, record_type: DnsRecordType) -> Self {
+        self.record_type = record_type;
+        self
+    }
+
+    /// Sets the time allowed for each query, per transport.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sends the queries through the interface `device` only, such as `wlan0`. Only supported on
+    /// Linux and Windows.
+    pub fn with_device(mut self, device: impl Into<String>) -> Self {
+        self.device = Some(device.into());
+        self
+    }
+
+    /// Returns the servers that will be queried.
+    pub fn servers(&self) -> Vec<SocketAddr> {
+        match &self.servers {
+            DnsServers::Explicit(servers) => servers.clone(),
+            DnsServers::ResolvConf(path) => fs::read_to_string(path)
+                .map(|contents| parse_resolv_conf(&contents))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Queries every server and returns the result for each, in order.
+    pub fn probe(&self) -> Vec<DnsServerResult> {
+        self.servers()
+            .into_iter()
+            .map(|server| self.probe_server(server))
+            .collect()
+    }
+
+    fn probe_server(&self, server: SocketAddr) -> DnsServerResult {
+        let started = Instant::now();
+        let id = next_query_id();
+
+        let (transport, outcome) = match encode_query(id, &self.query, self.record_type) {
+            Ok(query) => match self.query_udp(server, &query, id) {
+                Ok(response) if !response.truncated => {
+                    (Some(DnsTransport::Udp), response.outcome())
+                }
+                _ => match self.query_tcp(server, &query, id) {
+                    Ok(response) => (Some(DnsTransport::Tcp), response.outcome()),
+                    Err(e) => (None, DnsOutcome::Failed(e.to_string())),
+                },
+            },
+            Err(e) => (None, DnsOutcome::Failed(e.to_string())),
+        };
+
+        DnsServerResult {
+            server,
+            transport,
+            latency: started.elapsed(),
+            outcome,
+        }
+    }
+
+    fn query_udp(&self, server: SocketAddr, query: &[u8], id: u16) -> io::Result<DnsResponse> {
+        let bind: SocketAddr = match server {
+            SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+            SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+        };
+        let socket = socket::bind_udp(bind, self.device.as_deref())?;
+        socket.set_read_timeout(Some(self.timeout))?;
+        socket.connect(server)?;
+        socket.send(query)?;
+
+        let mut buffer = [0u8; MAX_UDP_MESSAGE_SIZE];
+        loop {
+            let len = socket.recv(&mut buffer)?;
+            // Ignore stray datagrams, such as late responses to earlier queries.
+            if let Ok(response) = parse_response(&buffer[..len], id, self.record_type) {
+                return Ok(response);
+            }
+        }
+    }
+
+    fn query_tcp(&self, server: SocketAddr, query: &[u8], id: u16) -> io::Result<DnsResponse> {
+        let mut stream = socket::connect_timeout(&server, self.device.as_deref(), self.timeout)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        let mut message = (query.len() as u16).to_be_bytes().to_vec();
+        message.extend_from_slice(query);
+        stream.write_all(&message)?;
+
+        let mut length = [0u8; 2];
+        stream.read_exact(&mut length)?;
+        let mut response = vec![0u8; u16::from_be_bytes(length) as usize];
+        stream.read_exact(&mut response)?;
+
+        parse_response(&response, id, self.record_type)
+    }
+}
+
+impl<N: InternetConnectivity> InternetConnectivity for DnsProbe<N> {
+    fn is_connected_to_network(&self) -> bool {
+        self.network.is_connected_to_network()
+    }
+
+    fn is_connected_to_internet(&self) -> bool {
+        self.servers()
+            .into_iter()
+            .any(|server| self.probe_server(server).is_success())
+    }
+}
+
+/// A parsed DNS response.
+struct DnsResponse {
+    response_code: u8,
+    truncated: bool,
+    addresses: Vec<IpAddr>,
+}
+
+impl DnsResponse {
+    fn outcome(self) -> DnsOutcome {
+        if self.response_code == 0 && !self.addresses.is_empty() {
+            DnsOutcome::Resolved(self.addresses)
+        } else {
+            DnsOutcome::ResponseCode(self.response_code)
+        }
+    }
+}
+
+/// Returns the `nameserver` entries of a resolv.conf file.
+///
+/// IPv6 link-local servers carry a zone index, such as `fe80::1%eth0`, which is resolved into the
+/// scope ID of the address; servers whose zone cannot be resolved are skipped, as they cannot be
+/// reached.
+fn parse_resolv_conf(contents: &str) -> Vec<SocketAddr> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            if fields.next()? != "nameserver" {
+                return None;
+            }
+            let mut address = fields.next()?.split('%');
+            let ip = address.next()?.parse::<IpAddr>().ok()?;
+            match (ip, address.next()) {
+                (IpAddr::V6(ip), Some(zone)) => match socket::scope_id(zone) {
+                    Some(scope_id) => Some(SocketAddrV6::new(ip, DNS_PORT, 0, scope_id).into()),
+                    None => {
+                        warn!(server = %ip, zone, "Skipping DNS server on an unknown interface");
+                        None
+                    }
+                },
+                (ip, _) => Some(SocketAddr::new(ip, DNS_PORT)),
+            }
+        })
+        .collect()
+}
+
+fn next_query_id() -> u16 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.subsec_nanos())
+        .unwrap_or_default();
+    (nanos as u16) ^ QUERY_COUNTER.fetch_add(1, Ordering::Relaxed)
+}
+
+fn encode_query(id: u16, name: &str, record_type: DnsRecordType) -> io::Result<Vec<u8>> {
+    let mut message = Vec::with_capacity(HEADER_SIZE + name.len() + 6);
+    message.extend_from_slice(&id.to_be_bytes());
+    message.extend_from_slice(&FLAG_RECURSION_DESIRED.to_be_bytes());
+    message.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid DNS name: {name}"),
+            ));
+        }
+        message.push(label.len() as u8);
+        message.extend_from_slice(label.as_bytes());
+    }
+    message.push(0);
+
+    message.extend_from_slice(&record_type.code().to_be_bytes());
+    message.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Ok(message)
+}
+
+fn parse_response(message: &[u8], id: u16, record_type: DnsRecordType) -> io::Result<DnsResponse> {
+    let invalid = |reason: &str| io::Error::new(io::ErrorKind::InvalidData, reason.to_string());
+    let read_u16 = |offset: usize| -> io::Result<u16> {
+        message
+            .get(offset..offset + 2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+            .ok_or_else(|| invalid("truncated DNS message"))
+    };
+
+    if read_u16(0)? != id {
+        return Err(invalid("DNS response identifier does not match query"));
+    }
+    let flags = read_u16(2)?;
+    if flags & FLAG_RESPONSE == 0 {
+        return Err(invalid("DNS message is not a response"));
+    }
+    let questions = read_u16(4)?;
+    let answers = read_u16(6)?;
+
+    let mut offset = HEADER_SIZE;
+    for _ in 0..questions {
+        offset = skip_name(message, offset).ok_or_else(|| invalid("malformed DNS question"))?;
+        offset += 4;
+    }
+
+    let mut addresses = Vec::new();
+    for _ in 0..answers {
+        offset = skip_name(message, offset).ok_or_else(|| invalid("malformed DNS answer"))?;
+        let kind = read_u16(offset)?;
+        let class = read_u16(offset + 2)?;
+        let length = read_u16(offset + 8)? as usize;
+        let data = message
+            .get(offset + 10..offset + 10 + length)
+            .ok_or_else(|| invalid("truncated DNS answer"))?;
+        offset += 10 + length;
+
+        if class != CLASS_IN || kind != record_type.code() {
+            continue;
+        }
+        match data.len() {
+            4 => addresses.push(IpAddr::from(<[u8; 4]>::try_from(data).unwrap())),
+            16 => addresses.push(IpAddr::from(<[u8; 16]>::try_from(data).unwrap())),
+            _ => return Err(invalid("malformed DNS address record")),
+        }
+    }
+
+    Ok(DnsResponse {
+        response_code: (flags & 0x000F) as u8,
+        truncated: flags & FLAG_TRUNCATED != 0,
+        addresses,
+    })
+}
+
+/// Returns the offset just past the (possibly compressed) name starting at `offset`.
+fn skip_name(message: &[u8], mut offset: usize) -> Option<usize> {
+    loop {
+        let len = *message.get(offset)?;
+        match len {
+            0 => return Some(offset + 1),
+            // A compression pointer ends the name.
+            len if len & 0xC0 == 0xC0 => return Some(offset + 2),
+            len => offset += 1 + len as usize,
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::adapter::InterfaceSelector;
+use crate::internet_connectivity::{
+    CaptivePortal, IcmpProbe, IcmpTargetResult, InternetAccess, InternetConnectivity,
+    LinuxInternetConnectivity,
+};
+
+/// Default number of echo requests sent to each gateway.
+const DEFAULT_COUNT: u16 = 2;
+
+/// Default time allowed for each echo reply from a gateway.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// `RTF_UP`, the flag set on usable routes.
+pub(crate) const RTF_UP: u32 = 0x0001;
+
+/// `RTF_GATEWAY`, the flag set on routes through a gateway.
+pub(crate) const RTF_GATEWAY: u32 = 0x0002;
+
+/// `ATF_COM`, the flag set on neighbour entries whose hardware address has been resolved.
+const ATF_COM: u32 = 0x0002;
+
+/// A default gateway, as found in the kernel routing table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gateway {
+    /// Name of the interface the default route leaves through, such as `wlan0`.
+    pub interface: String,
+
+    /// Address of the gateway.
+    pub address: IpAddr,
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`Gateway`] to enable easy printing.
+impl std::fmt::Display for Gateway {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} on {}", self.address, self.interface)
+    }
+}
+
+/// Result of testing the reachability of a single [`Gateway`] with [`GatewayProbe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GatewayResult {
+    /// The gateway tested.
+    pub gateway: Gateway,
+
+    /// The echo requests sent to the gateway, and their replies.
+    pub ping: IcmpTargetResult,
+
+    /// The hardware address of the gateway in the neighbour table after pinging, if resolved.
+    pub neighbour: Option<String>,
+}
+
+impl GatewayResult {
+    /// Returns `true` if the gateway replied to an echo request, or if its hardware address is
+    /// resolved, as gateways which drop echo requests are still reachable on the link.
+    pub fn is_reachable(&self) -> bool {
+        self.ping.is_success() || self.neighbour.is_some()
+    }
+
+    /// Returns the round-trip time of the first echo reply, if any.
+    pub fn rtt(&self) -> Option<Duration> {
+        self.ping.replies.iter().flatten().next().copied()
+    }
+}
+
+/// Adds a gateway reachability check to the network check of any [`InternetConnectivity`]
+/// implementation.
+///
+/// The default gateways are read from the kernel routing tables, `/proc/net/route` and
+/// `/proc/net/ipv6_route`, relative to a configurable root directory. Each gateway is pinged
+/// through the interface its route leaves by, using an [`IcmpProbe`]. An IPv4 gateway which does not
+/// answer, because it drops echo requests or no ICMP socket can be opened, still counts as
+/// reachable if its hardware address is resolved in `/proc/net/arp`.
+///
+/// The network is only considered connected when the wrapped checker reports it is and a gateway
+/// is reachable. This tells a lost Wi-Fi link, which leaves the gateway unreachable, apart from a
+/// hotspot which dropped its own uplink, which still answers as a gateway. Internet and captive
+/// portal checks are delegated to the wrapped checker.
+pub struct GatewayProbe<C: InternetConnectivity> {
+    inner: C,
+    root: PathBuf,
+    interface: Option<InterfaceSelector>,
+    count: u16,
+    timeout: Duration,
+}
+
+impl<C: InternetConnectivity> GatewayProbe<C> {
+    /// Creates a new instance of [`GatewayProbe`] reading from `/`, sending 2 echo requests to each
+    /// gateway and allowing 1s for each reply.
+    ///
+    /// # Arguments
+    /// - `inner`: The checker to delegate connectivity checks to.
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            root: PathBuf::from("/"),
+            interface: None,
+            count: DEFAULT_COUNT,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Reads the routing and neighbour tables from `root` rather than `/`.
+    ///
+    /// # Arguments
+    /// - `root`: Directory containing the `proc/net` and `sys/class/net` entries.
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = root.into();
+        self
+    }
+
+    /// Only considers the gateways of the interfaces chosen by `selector`.
+    pub fn with_interface(mut self, selector: InterfaceSelector) -> Self {
+        self.interface = Some(selector);
+        self
+    }
+
+    /// Sets the number of echo requests sent to each gateway.
+    pub fn with_count(mut self, count: u16) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Sets the time allowed for each echo reply.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the default gateways of the selected interfaces, IPv4 first.
+    pub fn gateways(&self) -> Vec<Gateway> {
+        let selected = match &self.interface {
+            Some(selector) => LinuxInternetConnectivity::with_root(&self.root)
+                .with_interface(selector.clone())
+                .selected_interfaces(),
+            None => None,
+        };
+
+        let ipv4 = ipv4_routes(&self.root).into_iter().filter_map(|route| {
+            (route.is_default() && route.flags & RTF_GATEWAY != 0).then_some(Gateway {
+                interface: route.interface,
+                address: IpAddr::V4(route.gateway),
+            })
+        });
+        let ipv6 = ipv6_default_gateways(&self.root);
+
+        ipv4.chain(ipv6)
+            .filter(|gateway| {
+                selected
+                    .as_ref()
+                    .is_none_or(|names| names.contains(&gateway.interface))
+            })
+            .collect()
+    }
+
+    /// Returns the resolved hardware address of `gateway` in the neighbour table, if any.
+    ///
+    /// Only IPv4 neighbours are listed in `/proc/net/arp`, so IPv6 gateways always return `None`.
+    pub fn neighbour(&self, gateway: &Gateway) -> Option<String> {
+        let IpAddr::V4(address) = gateway.address else {
+            return None;
+        };
+
+        let arp = fs::read_to_string(self.root.join("proc/net/arp")).unwrap_or_default();
+        arp.lines().skip(1).find_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (ip, flags, mac, iface) = (
+                fields.first()?,
+                fields.get(2)?,
+                fields.get(3)?,
+                fields.get(5)?,
+            );
+            let flags = u32::from_str_radix(flags.trim_start_matches("0x"), 16).ok()?;
+
+            let matches = ip.parse::<Ipv4Addr>().ok()? == address && *iface == gateway.interface;
+            (matches && flags & ATF_COM != 0).then(|| mac.to_string())
+        })
+    }
+
+    /// Pings `gateway` through its interface, then looks up its neighbour entry.
+    pub fn check(&self, gateway: &Gateway) -> GatewayResult {
+        let ping = IcmpProbe::new([gateway.address])
+            .with_device(gateway.interface.clone())
+            .with_count(self.count)
+            .with_timeout(self.timeout)
+            .ping(gateway.address);
+
+        GatewayResult {
+            neighbour: self.neighbour(gateway),
+            gateway: gateway.clone(),
+            ping,
+        }
+    }
+
+    /// Tests every default gateway and returns the result for each, in order.
+    pub fn probe(&self) -> Vec<GatewayResult> {
+        self.gateways()
+            .iter()
+            .map(|gateway| self.check(gateway))
+            .collect()
+    }
+}
+
+impl<C: InternetConnectivity> InternetConnectivity for GatewayProbe<C> {
+    fn is_connected_to_network(&self) -> bool {
+        self.inner.is_connected_to_network()
+            && self
+                .gateways()
+                .iter()
+                .any(|gateway| self.check(gateway).is_reachable())
+    }
+
+    fn is_connected_to_internet(&self) -> bool {
+        self.inner.is_connected_to_internet()
+    }
+
+    fn captive_portal(&self) -> Option<CaptivePortal> {
+        self.inner.captive_portal()
+    }
+
+    fn internet_access(&self) -> InternetAccess {
+        self.inner.internet_access()
+    }
+}
+
+/// A usable entry of `/proc/net/route`.
+pub(crate) struct Ipv4Route {
+    pub(crate) interface: String,
+    pub(crate) destination: Ipv4Addr,
+    pub(crate) gateway: Ipv4Addr,
+    pub(crate) flags: u32,
+    pub(crate) mask: Ipv4Addr,
+}
+
+impl Ipv4Route {
+    fn parse(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        Some(Self {
+            interface: fields.first()?.to_string(),
+            destination: parse_ipv4(fields.get(1)?)?,
+            gateway: parse_ipv4(fields.get(2)?)?,
+            flags: u32::from_str_radix(fields.get(3)?, 16).ok()?,
+            mask: parse_ipv4(fields.get(7)?)?,
+        })
+    }
+
+    /// Returns `true` if this is a default route.
+    pub(crate) fn is_default(&self) -> bool {
+        self.destination.is_unspecified() && self.mask.is_unspecified()
+    }
+}
+
+/// Returns the usable routes in `proc/net/route` under `root`.
+pub(crate) fn ipv4_routes(root: &Path) -> Vec<Ipv4Route> {
+    fs::read_to_string(root.join("proc/net/route"))
+        .unwrap_or_default()
+        .lines()
+        .skip(1)
+        .filter_map(Ipv4Route::parse)
+        .filter(|route| route.flags & RTF_UP != 0)
+        .collect()
+}
+
+/// Returns the gateways of the usable default routes in `proc/net/ipv6_route` under `root`.
+fn ipv6_default_gateways(root: &Path) -> Vec<Gateway> {
+    fs::read_to_string(root.join("proc/net/ipv6_route"))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (destination, prefix, next_hop, flags, iface) = (
+                fields.first()?,
+                fields.get(1)?,
+                fields.get(4)?,
+                fields.get(8)?,
+                fields.get(9)?,
+            );
+            let flags = u32::from_str_radix(flags, 16).ok()?;
+
+            let is_default = parse_ipv6(destination)?.is_unspecified() && *prefix == "00";
+            let usable = flags & RTF_UP != 0 && flags & RTF_GATEWAY != 0;
+            (is_default && usable).then_some(Gateway {
+                interface: iface.to_string(),
+                address: IpAddr::V6(parse_ipv6(next_hop)?),
+            })
+        })
+        .collect()
+}
+
+/// Parses an IPv4 address as printed in `/proc/net/route`, in little-endian hex.
+fn parse_ipv4(hex: &str) -> Option<Ipv4Addr> {
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    Some(Ipv4Addr::from(value.to_le_bytes()))
+}
+
+/// Parses an IPv6 address as printed in `/proc/net/if_inet6` and `/proc/net/ipv6_route`, as 32 hex digits.
+pub(crate) fn parse_ipv6(hex: &str) -> Option<Ipv6Addr> {
+    u128::from_str_radix(hex, 16).ok().map(Ipv6Addr::from)
+}
@@ -0,0 +1,247 @@
+use std::time::{Duration, Instant};
+
+use ureq::Agent;
+
+use crate::internet_connectivity::{
+    CaptivePortal, InternetAccess, InternetConnectivity, SystemInternetConnectivity,
+};
+
+/// Default time allowed for each probe request.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest response body read when checking for expected content.
+const MAX_BODY_SIZE: u64 = 64 * 1024;
+
+/// Outcome of a single probe request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HttpOutcome {
+    /// The response matched the expected status and body.
+    Success,
+
+    /// The server responded with a status other than the expected one.
+    UnexpectedStatus,
+
+    /// The server responded with the expected status, but the body did not contain the expected content.
+    UnexpectedBody,
+
+    /// The request failed before a response was received.
+    Failed(String),
+}
+
+/// Result of probing a single URL with [`HttpProbe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpProbeResult {
+    /// The URL that was requested.
+    pub url: String,
+
+    /// The response status code, if a response was received.
+    pub status: Option<u16>,
+
+    /// The `Location` header of the response, if any.
+    pub location: Option<String>,
+
+    /// Time taken to receive and check the response.
+    pub elapsed: Duration,
+
+    /// Whether the response matched what was expected.
+    pub outcome: HttpOutcome,
+}
+
+impl HttpProbeResult {
+    /// Returns `true` if the response matched what was expected.
+    pub fn is_success(&self) -> bool {
+        self.outcome == HttpOutcome::Success
+    }
+
+    /// Returns the [`CaptivePortal`] this response indicates, if any.
+    ///
+    /// A portal is indicated by a redirect, a `511 Network Authentication Required`, an unexpected
+    /// successful status (such as a login page served instead of a `204`), or an unexpected body.
+    pub fn captive_portal(&self) -> Option<CaptivePortal> {
+        let portal = match (&self.outcome, self.status) {
+            (HttpOutcome::UnexpectedBody, _) => true,
+            (HttpOutcome::UnexpectedStatus, Some(status)) => {
+                (200..400).contains(&status) || status == 511
+            }
+            _ => false,
+        };
+
+        portal.then(|| CaptivePortal {
+            login_url: self.location.clone(),
+        })
+    }
+}
+
+/// Portable implementation of [`InternetConnectivity`] which requests a list of HTTP(S) URLs.
+///
+/// The system is considered connected to the internet when any URL responds with the expected
+/// status code and, if configured, a body containing the expected content. Redirects are not
+/// followed, so a redirecting URL only succeeds if a redirect status is expected.
+///
+/// Network connectivity is delegated to `N`, which defaults to the [`SystemInternetConnectivity`]
+/// for the current OS.
+pub struct HttpProbe<N: InternetConnectivity = SystemInternetConnectivity> {
+    network: N,
+    urls: Vec<String>,
+    expected_status: u16,
+    expected_body: Option<String>,
+    timeout: Duration,
+}
+
+impl HttpProbe {
+    /// Creates a new instance of [`HttpProbe`] expecting a `200 OK` response from any of `urls`.
+    ///
+    /// # Arguments
+    /// - `urls`: The URLs to request, in order.
+    pub fn new<I, S>(urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            network: SystemInternetConnectivity::default(),
+            urls: urls.into_iter().map(Into::into).collect(),
+            expected_status: 200,
+            expected_body: None,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+impl<N: InternetConnectivity> HttpProbe<N> {
+    /// Replaces the checker used for [`InternetConnectivity::is_connected_to_network`].
+    pub fn with_network<M: InternetConnectivity>(self, network: M) -> HttpProbe<M> {
+        HttpProbe {
+            network,
+            urls: self.urls,
+            expected_status: self.expected_status,
+            expected_body: self.expected_body,
+            timeout: self.timeout,
+        }
+    }
+
+    /// Sets the status code a successful response must have, such as `204` for `generate_204` endpoints.
+    pub fn with_expected_status(mut self, status: u16) -> Self {
+        self.expected_status = status;
+        self
+    }
+
+    /// Sets content a successful response body must contain.
+    pub fn with_expected_body(mut self, body: impl Into<String>) -> Self {
+        self.expected_body = Some(body.into());
+        self
+    }
+
+    /// Sets the time allowed for each request, including connecting and reading the body.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Requests every URL and returns the result for each, in order.
+    pub fn probe(&self) -> Vec<HttpProbeResult> {
+        let agent = agent(self.timeout);
+        self.urls
+            .iter()
+            .map(|url| self.probe_url(&agent, url))
+            .collect()
+    }
+
+    fn probe_url(&self, agent: &Agent, url: &str) -> HttpProbeResult {
+        request(
+            agent,
+            url,
+            self.expected_status,
+            self.expected_body.as_deref(),
+        )
+    }
+}
+
+impl<N: InternetConnectivity> InternetConnectivity for HttpProbe<N> {
+    fn is_connected_to_network(&self) -> bool {
+        self.network.is_connected_to_network()
+    }
+
+    fn is_connected_to_internet(&self) -> bool {
+        let agent = agent(self.timeout);
+        self.urls
+            .iter()
+            .any(|url| self.probe_url(&agent, url).is_success())
+    }
+
+    fn captive_portal(&self) -> Option<CaptivePortal> {
+        match self.internet_access() {
+            InternetAccess::CaptivePortal(portal) => Some(portal),
+            _ => None,
+        }
+    }
+
+    fn internet_access(&self) -> InternetAccess {
+        let results = self.probe();
+        if results.iter().any(HttpProbeResult::is_success) {
+            return InternetAccess::Reachable;
+        }
+        match results.iter().find_map(HttpProbeResult::captive_portal) {
+            Some(portal) => InternetAccess::CaptivePortal(portal),
+            None => InternetAccess::Unreachable,
+        }
+    }
+}
+
+/// Creates an agent which does not follow redirects or treat error statuses as errors.
+pub(crate) fn agent(timeout: Duration) -> Agent {
+    Agent::config_builder()
+        .timeout_global(Some(timeout))
+        .http_status_as_error(false)
+        .max_redirects(0)
+        .build()
+        .new_agent()
+}
+
+/// Requests `url` and checks the response against the expected status and body.
+pub(crate) fn request(
+    agent: &Agent,
+    url: &str,
+    expected_status: u16,
+    expected_body: Option<&str>,
+) -> HttpProbeResult {
+    let started = Instant::now();
+
+    let (status, location, outcome) = match agent.get(url).call() {
+        Ok(mut response) => {
+            let status = response.status().as_u16();
+            let location = response
+                .headers()
+                .get("location")
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            let outcome = if status != expected_status {
+                HttpOutcome::UnexpectedStatus
+            } else {
+                match expected_body {
+                    None => HttpOutcome::Success,
+                    Some(expected) => match response
+                        .body_mut()
+                        .with_config()
+                        .limit(MAX_BODY_SIZE)
+                        .read_to_string()
+                    {
+                        Ok(body) if body.contains(expected) => HttpOutcome::Success,
+                        Ok(_) => HttpOutcome::UnexpectedBody,
+                        Err(e) => HttpOutcome::Failed(e.to_string()),
+                    },
+                }
+            };
+            (Some(status), location, outcome)
+        }
+        Err(e) => (None, None, HttpOutcome::Failed(e.to_string())),
+    };
+
+    HttpProbeResult {
+        url: url.to_string(),
+        status,
+        location,
+        elapsed: started.elapsed(),
+        outcome,
+    }
+}
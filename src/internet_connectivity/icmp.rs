@@ -0,0 +1,467 @@
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::internet_connectivity::linux::{bind_to_device, sockaddr};
+use crate::internet_connectivity::{
+    ConnectionQuality, InternetConnectivity, QualityReport, SystemInternetConnectivity,
+};
+
+/// Targets pinged when none are configured.
+const DEFAULT_TARGETS: [IpAddr; 2] = [
+    IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+    IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8)),
+];
+
+/// Default number of echo requests sent to each target.
+const DEFAULT_COUNT: u16 = 3;
+
+/// Default time allowed for each echo reply.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Payload carried by every echo request, and expected back in the reply.
+const PAYLOAD: &[u8] = b"internet_reloader";
+
+/// Size of the ICMP echo header: type, code, checksum, identifier and sequence number.
+const ECHO_HEADER_SIZE: usize = 8;
+
+/// Largest packet accepted, including the IPv4 header raw sockets receive.
+const MAX_PACKET_SIZE: usize = 1500;
+
+/// ICMPv4 echo request and reply types.
+const ICMPV4_ECHO_REQUEST: u8 = 8;
+const ICMPV4_ECHO_REPLY: u8 = 0;
+
+/// ICMPv6 echo request and reply types.
+const ICMPV6_ECHO_REQUEST: u8 = 128;
+const ICMPV6_ECHO_REPLY: u8 = 129;
+
+/// Counter mixed into raw socket identifiers so concurrent probes do not collide.
+static IDENTIFIER_COUNTER: AtomicU16 = AtomicU16::new(0);
+
+/// Kind of socket echo requests are sent on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IcmpSocketKind {
+    /// An unprivileged ICMP datagram socket, allowed by `net.ipv4.ping_group_range`.
+    ///
+    /// The kernel assigns the identifier and only delivers the replies addressed to the socket.
+    Datagram,
+
+    /// A raw ICMP socket, which requires `CAP_NET_RAW`.
+    Raw,
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`IcmpSocketKind`] to enable easy printing.
+impl std::fmt::Display for IcmpSocketKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            IcmpSocketKind::Datagram => write!(f, "datagram"),
+            IcmpSocketKind::Raw => write!(f, "raw"),
+        }
+    }
+}
+
+/// Result of pinging a single target with [`IcmpProbe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcmpTargetResult {
+    /// The target that was pinged.
+    pub target: IpAddr,
+
+    /// The kind of socket used, if one could be opened.
+    pub socket: Option<IcmpSocketKind>,
+
+    /// Round-trip time of each echo request, in order, or `None` for requests without a reply.
+    pub replies: Vec<Option<Duration>>,
+
+    /// The last error encountered, such as the socket failing to open or a request failing to send.
+    pub error: Option<String>,
+}
+
+impl IcmpTargetResult {
+    /// Returns `true` if any echo request received a reply.
+    pub fn is_success(&self) -> bool {
+        self.replies.iter().any(Option::is_some)
+    }
+}
+
+/// Implementation of [`InternetConnectivity`] which sends ICMP echo requests to a set of targets.
+///
+/// Requests are sent on an unprivileged ICMP datagram socket, falling back to a raw socket when
+/// datagram sockets are not permitted. Replies are matched by identifier, sequence number and
+/// payload, and each request is allowed its own timeout. The system is considered connected to
+/// the internet when any target replies to any request.
+///
+/// Network connectivity is delegated to `N`, which defaults to the [`SystemInternetConnectivity`]
+/// for the current OS.
+pub struct IcmpProbe<N: InternetConnectivity = SystemInternetConnectivity> {
+    network: N,
+    targets: Vec<IpAddr>,
+    count: u16,
+    timeout: Duration,
+    socket: Option<IcmpSocketKind>,
+    device: Option<String>,
+}
+
+impl Default for IcmpProbe {
+    /// Creates a new instance of [`IcmpProbe`] pinging `1.1.1.1` and `8.8.8.8`.
+    fn default() -> Self {
+        Self::new(DEFAULT_TARGETS)
+    }
+}
+
+impl IcmpProbe {
+    /// Creates a new instance of [`IcmpProbe`] sending 3 echo requests to each target, allowing 1s for each reply.
+    ///
+    /// # Arguments
+    /// - `targets`: The IPv4 or IPv6 addresses to ping, in order.
+    pub fn new(targets: impl IntoIterator<Item = IpAddr>) -> Self {
+        Self {
+            network: SystemInternetConnectivity::default(),
+            targets: targets.into_iter().collect(),
+            count: DEFAULT_COUNT,
+            timeout: DEFAULT_TIMEOUT,
+            socket: None,
+            device: None,
+        }
+    }
+}
+
+impl<N: InternetConnectivity> IcmpProbe<N> {
+    /// Replaces the checker used for [`InternetConnectivity::is_connected_to_network`].
+    pub fn with_network<M: InternetConnectivity>(self, network: M) -> IcmpProbe<M> {
+        IcmpProbe {
+            network,
+            targets: self.targets,
+            count: self.count,
+            timeout: self.timeout,
+            socket: self.socket,
+            device: self.device,
+        }
+    }
+
+    /// Sets the number of echo requests sent to each target.
+    pub fn with_count(mut self, count: u16) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Sets the time allowed for each echo reply.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Restricts the probe to one kind of socket, rather than falling back from datagram to raw.
+    pub fn with_socket_kind(mut self, socket: IcmpSocketKind) -> Self {
+        self.socket = Some(socket);
+        self
+    }
+
+    /// Sends the echo requests through the interface `device` only, such as `wlan0`.
+    ///
+    /// This also allows link-local IPv6 targets, such as `fe80::1`, to be pinged on that interface.
+    pub fn with_device(mut self, device: impl Into<String>) -> Self {
+        self.device = Some(device.into());
+        self
+    }
+
+    /// Returns the targets that will be pinged.
+    pub fn targets(&self) -> &[IpAddr] {
+        &self.targets
+    }
+
+    /// Pings every target and returns the result for each, in order.
+    pub fn probe(&self) -> Vec<IcmpTargetResult> {
+        self.targets
+            .iter()
+            .map(|target| self.ping(*target))
+            .collect()
+    }
+
+    /// Sends the echo requests to `target` one at a time, waiting for each reply in turn.
+    pub fn ping(&self, target: IpAddr) -> IcmpTargetResult {
+        let mut result = IcmpTargetResult {
+            target,
+            socket: None,
+            replies: Vec::new(),
+            error: None,
+        };
+
+        let socket = match IcmpSocket::open(target, self.socket, self.device.as_deref()) {
+            Ok(socket) => socket,
+            Err(e) => {
+                result.error = Some(e.to_string());
+                return result;
+            }
+        };
+        result.socket = Some(socket.kind);
+
+        for sequence in 0..self.count {
+            match socket.echo(sequence, self.timeout) {
+                Ok(rtt) => result.replies.push(rtt),
+                Err(e) => {
+                    result.replies.push(None);
+                    result.error = Some(e.to_string());
+                }
+            }
+        }
+        result
+    }
+}
+
+impl<N: InternetConnectivity> InternetConnectivity for IcmpProbe<N> {
+    fn is_connected_to_network(&self) -> bool {
+        self.network.is_connected_to_network()
+    }
+
+    fn is_connected_to_internet(&self) -> bool {
+        self.targets
+            .iter()
+            .any(|target| self.ping(*target).is_success())
+    }
+}
+
+/// Measures the connection quality by pinging the first target, so a lost echo request or reply
+/// counts as lost rather than being retransmitted. Every request is lost if the socket cannot be
+/// opened.
+impl<N: InternetConnectivity> ConnectionQuality for IcmpProbe<N> {
+    fn measure(&self) -> QualityReport {
+        let Some(target) = self.targets.first() else {
+            return QualityReport::default();
+        };
+        let result = self.ping(*target);
+
+        QualityReport::from_samples(
+            (0..usize::from(self.count)).map(|i| result.replies.get(i).copied().flatten()),
+        )
+    }
+}
+
+/// An ICMP socket connected to a single target.
+struct IcmpSocket {
+    fd: OwnedFd,
+    kind: IcmpSocketKind,
+    v6: bool,
+    identifier: u16,
+}
+
+impl IcmpSocket {
+    /// Opens a socket of the `kind` requested, or a datagram socket falling back to a raw one,
+    /// bound to `device` if set.
+    fn open(
+        target: IpAddr,
+        kind: Option<IcmpSocketKind>,
+        device: Option<&str>,
+    ) -> io::Result<Self> {
+        match kind {
+            Some(kind) => Self::open_kind(target, kind, device),
+            None => Self::open_kind(target, IcmpSocketKind::Datagram, device)
+                .or_else(|_| Self::open_kind(target, IcmpSocketKind::Raw, device)),
+        }
+    }
+
+    fn open_kind(target: IpAddr, kind: IcmpSocketKind, device: Option<&str>) -> io::Result<Self> {
+        let (domain, protocol, unspecified) = match target {
+            IpAddr::V4(_) => (
+                libc::AF_INET,
+                libc::IPPROTO_ICMP,
+                IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            ),
+            IpAddr::V6(_) => (
+                libc::AF_INET6,
+                libc::IPPROTO_ICMPV6,
+                IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+            ),
+        };
+        let socket_type = match kind {
+            IcmpSocketKind::Datagram => libc::SOCK_DGRAM,
+            IcmpSocketKind::Raw => libc::SOCK_RAW,
+        };
+
+        let fd = unsafe { libc::socket(domain, socket_type | libc::SOCK_CLOEXEC, protocol) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        if let Some(device) = device {
+            bind_to_device(&fd, device)?;
+        }
+
+        let identifier = match kind {
+            // The kernel replaces the identifier with the port the socket is bound to.
+            IcmpSocketKind::Datagram => {
+                let (storage, len) = sockaddr(&SocketAddr::new(unspecified, 0));
+                if unsafe { libc::bind(fd.as_raw_fd(), (&raw const storage).cast(), len) } < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                local_port(&fd)?
+            }
+            IcmpSocketKind::Raw => {
+                (std::process::id() as u16) ^ IDENTIFIER_COUNTER.fetch_add(1, Ordering::Relaxed)
+            }
+        };
+
+        // Connecting filters out packets from other hosts and lets replies be read with `recv`.
+        let (storage, len) = sockaddr(&SocketAddr::new(target, 0));
+        if unsafe { libc::connect(fd.as_raw_fd(), (&raw const storage).cast(), len) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            fd,
+            kind,
+            v6: target.is_ipv6(),
+            identifier,
+        })
+    }
+
+    /// Sends one echo request and waits up to `timeout` for its reply, returning the round-trip
+    /// time, or `None` if no reply arrived in time.
+    fn echo(&self, sequence: u16, timeout: Duration) -> io::Result<Option<Duration>> {
+        let request = self.encode_request(sequence);
+        let started = Instant::now();
+        if unsafe {
+            libc::send(
+                self.fd.as_raw_fd(),
+                request.as_ptr().cast(),
+                request.len(),
+                0,
+            )
+        } < 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+
+        let deadline = started + timeout;
+        let mut buffer = [0u8; MAX_PACKET_SIZE];
+        loop {
+            let now = Instant::now();
+            if now >= deadline || !self.wait_readable(deadline - now)? {
+                return Ok(None);
+            }
+
+            let len = unsafe {
+                libc::recv(
+                    self.fd.as_raw_fd(),
+                    buffer.as_mut_ptr().cast(),
+                    buffer.len(),
+                    libc::MSG_DONTWAIT,
+                )
+            };
+            if len < 0 {
+                let error = io::Error::last_os_error();
+                match error.kind() {
+                    io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted => continue,
+                    _ => return Err(error),
+                }
+            }
+
+            // Ignore other ICMP traffic, such as replies to earlier requests or other sockets.
+            if self.is_reply(&buffer[..len as usize], sequence) {
+                return Ok(Some(started.elapsed()));
+            }
+        }
+    }
+
+    /// Waits up to `timeout` for a packet to arrive, returning `false` if none did.
+    fn wait_readable(&self, timeout: Duration) -> io::Result<bool> {
+        let mut pollfd = libc::pollfd {
+            fd: self.fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // Round up, so a sub-millisecond remainder does not turn into a busy loop.
+        let millis = timeout
+            .as_micros()
+            .div_ceil(1000)
+            .min(libc::c_int::MAX as u128) as libc::c_int;
+
+        match unsafe { libc::poll(&mut pollfd, 1, millis) } {
+            0 => Ok(false),
+            n if n < 0 => {
+                let error = io::Error::last_os_error();
+                match error.kind() {
+                    io::ErrorKind::Interrupted => Ok(true),
+                    _ => Err(error),
+                }
+            }
+            _ => Ok(true),
+        }
+    }
+
+    fn encode_request(&self, sequence: u16) -> Vec<u8> {
+        let request_type = match self.v6 {
+            true => ICMPV6_ECHO_REQUEST,
+            false => ICMPV4_ECHO_REQUEST,
+        };
+
+        let mut packet = Vec::with_capacity(ECHO_HEADER_SIZE + PAYLOAD.len());
+        packet.extend_from_slice(&[request_type, 0, 0, 0]);
+        packet.extend_from_slice(&self.identifier.to_be_bytes());
+        packet.extend_from_slice(&sequence.to_be_bytes());
+        packet.extend_from_slice(PAYLOAD);
+
+        // The kernel computes the ICMPv6 checksum, which covers a pseudo-header it builds itself.
+        if !self.v6 {
+            let checksum = checksum(&packet);
+            packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+        }
+        packet
+    }
+
+    /// Returns `true` if `packet` is the echo reply to the request with `sequence`.
+    fn is_reply(&self, packet: &[u8], sequence: u16) -> bool {
+        // Raw IPv4 sockets receive the IP header as well.
+        let packet = match (self.kind, self.v6) {
+            (IcmpSocketKind::Raw, false) => {
+                let header_len = packet.first().map_or(0, |byte| (byte & 0x0f) as usize * 4);
+                packet.get(header_len..).unwrap_or_default()
+            }
+            _ => packet,
+        };
+        if packet.len() < ECHO_HEADER_SIZE {
+            return false;
+        }
+
+        let reply_type = match self.v6 {
+            true => ICMPV6_ECHO_REPLY,
+            false => ICMPV4_ECHO_REPLY,
+        };
+        packet[0] == reply_type
+            && packet[1] == 0
+            && packet[4..6] == self.identifier.to_be_bytes()
+            && packet[6..8] == sequence.to_be_bytes()
+            && &packet[ECHO_HEADER_SIZE..] == PAYLOAD
+    }
+}
+
+/// Returns the port `fd` is bound to.
+fn local_port(fd: &OwnedFd) -> io::Result<u16> {
+    let mut storage: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+    if unsafe { libc::getsockname(fd.as_raw_fd(), (&raw mut storage).cast(), &mut len) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // The port is at the same offset in `sockaddr_in` and `sockaddr_in6`.
+    let sin: libc::sockaddr_in = unsafe { std::ptr::read((&raw const storage).cast()) };
+    Ok(u16::from_be(sin.sin_port))
+}
+
+/// Computes the internet checksum (RFC 1071) of `data`.
+fn checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = data
+        .chunks(2)
+        .map(|chunk| match chunk {
+            [high, low] => u16::from_be_bytes([*high, *low]) as u32,
+            [high] => u16::from_be_bytes([*high, 0]) as u32,
+            _ => 0,
+        })
+        .sum();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
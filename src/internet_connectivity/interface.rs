@@ -1,3 +1,25 @@
+use crate::internet_connectivity::QualityReport;
+
+/// A captive portal intercepting traffic, such as a hotel or hotspot login page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptivePortal {
+    /// The login page the portal redirected to, if it could be determined.
+    pub login_url: Option<String>,
+}
+
+/// The internet access found by [`InternetConnectivity::internet_access`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InternetAccess {
+    /// The internet is reachable.
+    Reachable,
+
+    /// The internet is unreachable.
+    Unreachable,
+
+    /// Traffic is being intercepted by a captive portal.
+    CaptivePortal(CaptivePortal),
+}
+
 /// Trait for checking internet connectivity.
 pub trait InternetConnectivity {
     /// Checks if the system is connected to a network.
@@ -5,4 +27,56 @@ pub trait InternetConnectivity {
 
     /// Checks if the system is connected to the internet.
     fn is_connected_to_internet(&self) -> bool;
+
+    /// Checks if traffic is being intercepted by a captive portal.
+    ///
+    /// The default implementation performs no check and returns `None`.
+    fn captive_portal(&self) -> Option<CaptivePortal> {
+        None
+    }
+
+    /// Checks for a captive portal and, if there is none, whether the internet is reachable.
+    ///
+    /// The default implementation calls [`InternetConnectivity::captive_portal`], then
+    /// [`InternetConnectivity::is_connected_to_internet`]. Checkers which answer both from the same
+    /// requests override it, so they are only sent once.
+    fn internet_access(&self) -> InternetAccess {
+        match self.captive_portal() {
+            Some(portal) => InternetAccess::CaptivePortal(portal),
+            None if self.is_connected_to_internet() => InternetAccess::Reachable,
+            None => InternetAccess::Unreachable,
+        }
+    }
+}
+
+/// Boxed checkers can be used wherever an [`InternetConnectivity`] is expected, so the checker can be chosen at runtime.
+impl<C: InternetConnectivity + ?Sized> InternetConnectivity for Box<C> {
+    fn is_connected_to_network(&self) -> bool {
+        (**self).is_connected_to_network()
+    }
+
+    fn is_connected_to_internet(&self) -> bool {
+        (**self).is_connected_to_internet()
+    }
+
+    fn captive_portal(&self) -> Option<CaptivePortal> {
+        (**self).captive_portal()
+    }
+
+    fn internet_access(&self) -> InternetAccess {
+        (**self).internet_access()
+    }
+}
+
+/// Trait for measuring the quality of the internet connection.
+pub trait ConnectionQuality {
+    /// Measures round-trip times, jitter and loss over a short burst of samples.
+    fn measure(&self) -> QualityReport;
+}
+
+/// Boxed probes can be used wherever a [`ConnectionQuality`] is expected, so the probe can be chosen at runtime.
+impl<Q: ConnectionQuality + ?Sized> ConnectionQuality for Box<Q> {
+    fn measure(&self) -> QualityReport {
+        (**self).measure()
+    }
 }
@@ -0,0 +1,310 @@
+use std::fs;
+use std::io;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::adapter::{InterfaceSelector, adapters_under};
+use crate::internet_connectivity::InternetConnectivity;
+use crate::internet_connectivity::gateway::ipv4_routes;
+
+/// Host used to check for internet access, matching the Windows implementation.
+const INTERNET_CHECK_HOST: &str = "www.google.com:80";
+
+/// Timeout applied to each connection attempt when checking for internet access.
+const INTERNET_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `ARPHRD_LOOPBACK`, the value of `/sys/class/net/<iface>/type` for loopback interfaces.
+const ARPHRD_LOOPBACK: &str = "772";
+
+/// Implementation of [`InternetConnectivity`] for Linux.
+///
+/// Network connectivity is determined from the interface state in `/sys/class/net` and the
+/// default route in `/proc/net/route`. Both are read relative to a configurable root directory,
+/// so the checks can be exercised against fixture directories.
+///
+/// When an [`InterfaceSelector`] is set, only the selected interfaces count towards network
+/// connectivity, and the internet check connects through each of them in turn, by binding its
+/// socket to the interface.
+pub struct LinuxInternetConnectivity {
+    root: PathBuf,
+    interface: Option<InterfaceSelector>,
+    host: String,
+}
+
+impl Default for LinuxInternetConnectivity {
+    /// Creates a new instance of [`LinuxInternetConnectivity`] reading from `/`.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinuxInternetConnectivity {
+    /// Creates a new instance of [`LinuxInternetConnectivity`] reading from `/`.
+    pub fn new() -> Self {
+        Self::with_root("/")
+    }
+
+    /// Creates a new instance of [`LinuxInternetConnectivity`] reading from `root`.
+    ///
+    /// # Arguments
+    /// - `root`: Directory containing the `sys/class/net` and `proc/net/route` entries.
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            interface: None,
+            host: INTERNET_CHECK_HOST.to_string(),
+        }
+    }
+
+    /// Scopes the checks to the interfaces chosen by `selector`.
+    pub fn with_interface(mut self, selector: InterfaceSelector) -> Self {
+        self.interface = Some(selector);
+        self
+    }
+
+    /// Sets the `host:port` connected to when checking for internet access.
+    pub fn with_internet_host(mut self, host: impl Into<String>) -> Self {
+        self.host = host.into();
+        self
+    }
+
+    /// Returns the names of the interfaces chosen by the selector, or `None` if no selector is set.
+    ///
+    /// A selector picking a single interface by name is used as is, so the interface does not need
+    /// to be listed in `sys/class/net`.
+    pub fn selected_interfaces(&self) -> Option<Vec<String>> {
+        let selector = self.interface.as_ref()?;
+        if let Some(name) = selector.single_name() {
+            return Some(vec![name.to_string()]);
+        }
+
+        let adapters = adapters_under(&self.root).unwrap_or_default();
+        Some(
+            selector
+                .select(&adapters)
+                .into_iter()
+                .map(|adapter| adapter.name.clone())
+                .collect(),
+        )
+    }
+
+    /// Returns the names of all non-loopback interfaces whose link is up.
+    ///
+    /// An interface is considered up when its `operstate` is `up`, or when it is `unknown`
+    /// (as reported by some virtual and tunnel drivers) and its `carrier` is `1`.
+    pub fn interfaces_up(&self) -> Vec<String> {
+        let net = self.root.join("sys/class/net");
+        let Ok(entries) = fs::read_dir(&net) else {
+            return Vec::new();
+        };
+
+        let mut interfaces: Vec<String> = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| Self::is_link_up(&net.join(name)))
+            .collect();
+        interfaces.sort();
+        interfaces
+    }
+
+    /// Returns the names of all interfaces carrying a usable default route.
+    pub fn default_route_interfaces(&self) -> Vec<String> {
+        ipv4_routes(&self.root)
+            .into_iter()
+            .filter(|route| route.is_default())
+            .map(|route| route.interface)
+            .collect()
+    }
+
+    /// Returns `true` if the interface directory `iface` describes a non-loopback interface whose link is up.
+    pub(crate) fn is_link_up(iface: &Path) -> bool {
+        let read = |file: &str| {
+            fs::read_to_string(iface.join(file))
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default()
+        };
+
+        if read("type") == ARPHRD_LOOPBACK {
+            return false;
+        }
+
+        match read("operstate").as_str() {
+            "up" => true,
+            "unknown" => read("carrier") == "1",
+            _ => false,
+        }
+    }
+}
+
+impl InternetConnectivity for LinuxInternetConnectivity {
+    fn is_connected_to_network(&self) -> bool {
+        let up = self.interfaces_up();
+        let selected = self.selected_interfaces();
+        self.default_route_interfaces().iter().any(|iface| {
+            up.contains(iface) && selected.as_ref().is_none_or(|names| names.contains(iface))
+        })
+    }
+
+    fn is_connected_to_internet(&self) -> bool {
+        let Ok(addrs) = self.host.to_socket_addrs() else {
+            return false;
+        };
+        let addrs: Vec<SocketAddr> = addrs.collect();
+
+        match self.selected_interfaces() {
+            None => addrs
+                .iter()
+                .any(|addr| TcpStream::connect_timeout(addr, INTERNET_CHECK_TIMEOUT).is_ok()),
+            Some(interfaces) => interfaces.iter().any(|iface| {
+                addrs
+                    .iter()
+                    .any(|addr| connect_on_interface(addr, iface, INTERNET_CHECK_TIMEOUT).is_ok())
+            }),
+        }
+    }
+}
+
+/// Opens a TCP connection to `addr` which only leaves through the interface `iface`.
+///
+/// The socket is bound to the interface with `SO_BINDTODEVICE` before connecting, so the
+/// connection fails rather than falling back to another route.
+///
+/// # Arguments
+/// - `addr`: The address to connect to.
+/// - `iface`: Name of the interface, such as `wlan0`.
+/// - `timeout`: Longest time to wait for the connection to be established.
+pub(crate) fn connect_on_interface(
+    addr: &SocketAddr,
+    iface: &str,
+    timeout: Duration,
+) -> io::Result<TcpStream> {
+    let domain = match addr {
+        SocketAddr::V4(_) => libc::AF_INET,
+        SocketAddr::V6(_) => libc::AF_INET6,
+    };
+    let fd = unsafe {
+        libc::socket(
+            domain,
+            libc::SOCK_STREAM | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+            0,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+    bind_to_device(&socket, iface)?;
+
+    let (storage, len) = sockaddr(addr);
+    let result = unsafe { libc::connect(socket.as_raw_fd(), (&raw const storage).cast(), len) };
+    if result < 0 {
+        let error = io::Error::last_os_error();
+        if error.raw_os_error() != Some(libc::EINPROGRESS) {
+            return Err(error);
+        }
+        wait_connected(&socket, timeout)?;
+    }
+
+    let stream = TcpStream::from(socket);
+    stream.set_nonblocking(false)?;
+    Ok(stream)
+}
+
+/// Binds `socket` to the interface `iface` with `SO_BINDTODEVICE`, so its traffic only leaves
+/// through that interface.
+pub(super) fn bind_to_device(socket: &impl AsRawFd, iface: &str) -> io::Result<()> {
+    let result = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_BINDTODEVICE,
+            iface.as_ptr().cast(),
+            iface.len() as libc::socklen_t,
+        )
+    };
+    match result {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+/// Returns the index of the interface `iface`, or `None` if there is no such interface.
+pub(super) fn interface_index(iface: &str) -> Option<u32> {
+    let name = std::ffi::CString::new(iface).ok()?;
+    match unsafe { libc::if_nametoindex(name.as_ptr()) } {
+        0 => None,
+        index => Some(index),
+    }
+}
+
+/// Waits for a non-blocking connect on `socket` to complete, returning the error it failed with, if any.
+fn wait_connected(socket: &OwnedFd, timeout: Duration) -> io::Result<()> {
+    let mut pollfd = libc::pollfd {
+        fd: socket.as_raw_fd(),
+        events: libc::POLLOUT,
+        revents: 0,
+    };
+    let millis = timeout.as_millis().min(libc::c_int::MAX as u128) as libc::c_int;
+
+    match unsafe { libc::poll(&mut pollfd, 1, millis) } {
+        0 => return Err(io::Error::from(io::ErrorKind::TimedOut)),
+        n if n < 0 => return Err(io::Error::last_os_error()),
+        _ => {}
+    }
+
+    let mut error: libc::c_int = 0;
+    let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+    let result = unsafe {
+        libc::getsockopt(
+            socket.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_ERROR,
+            (&raw mut error).cast(),
+            &mut len,
+        )
+    };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    match error {
+        0 => Ok(()),
+        code => Err(io::Error::from_raw_os_error(code)),
+    }
+}
+
+/// Converts `addr` into the `sockaddr` representation expected by `connect` and `bind`.
+pub(super) fn sockaddr(addr: &SocketAddr) -> (libc::sockaddr_storage, libc::socklen_t) {
+    let mut storage: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
+
+    let len = match addr {
+        SocketAddr::V4(addr) => {
+            let sin = libc::sockaddr_in {
+                sin_family: libc::AF_INET as libc::sa_family_t,
+                sin_port: addr.port().to_be(),
+                sin_addr: libc::in_addr {
+                    s_addr: u32::from_ne_bytes(addr.ip().octets()),
+                },
+                sin_zero: [0; 8],
+            };
+            unsafe { std::ptr::write((&raw mut storage).cast(), sin) };
+            std::mem::size_of::<libc::sockaddr_in>()
+        }
+        SocketAddr::V6(addr) => {
+            let sin6 = libc::sockaddr_in6 {
+                sin6_family: libc::AF_INET6 as libc::sa_family_t,
+                sin6_port: addr.port().to_be(),
+                sin6_flowinfo: addr.flowinfo(),
+                sin6_addr: libc::in6_addr {
+                    s6_addr: addr.ip().octets(),
+                },
+                sin6_scope_id: addr.scope_id(),
+            };
+            unsafe { std::ptr::write((&raw mut storage).cast(), sin6) };
+            std::mem::size_of::<libc::sockaddr_in6>()
+        }
+    };
+
+    (storage, len as libc::socklen_t)
+}
@@ -1,7 +1,38 @@
 //! Module for checking internet connectivity.
 
+mod captive;
+mod dns;
+mod http;
 mod interface;
-mod windows;
+mod quality;
+mod quorum;
+mod socket;
+mod tcp;
 
-pub use interface::InternetConnectivity;
-pub use windows::WindowsInternetConnectivity;
+pub use captive::{CaptivePortalDetector, KnownContent};
+pub use dns::{DnsOutcome, DnsProbe, DnsRecordType, DnsServerResult, DnsTransport};
+pub use http::{HttpOutcome, HttpProbe, HttpProbeResult};
+pub use interface::{CaptivePortal, ConnectionQuality, InternetAccess, InternetConnectivity};
+pub use quality::{QualityIssue, QualityProbe, QualityReport, QualityThresholds};
+pub use quorum::{QuorumMember, QuorumMemberResult, QuorumPolicy, QuorumProbe, QuorumResult};
+pub use tcp::{TcpConnectProbe, TcpEndpointResult};
+
+cfg_if::cfg_if! {
+    if #[cfg(target_os = "windows")] {
+        mod windows;
+        pub use windows::WindowsInternetConnectivity;
+
+        /// The [`InternetConnectivity`] implementation for the current OS.
+        pub type SystemInternetConnectivity = WindowsInternetConnectivity;
+    } else if #[cfg(target_os = "linux")] {
+        mod gateway;
+        mod icmp;
+        mod linux;
+        pub use gateway::{Gateway, GatewayProbe, GatewayResult};
+        pub use icmp::{IcmpProbe, IcmpSocketKind, IcmpTargetResult};
+        pub use linux::LinuxInternetConnectivity;
+
+        /// The [`InternetConnectivity`] implementation for the current OS.
+        pub type SystemInternetConnectivity = LinuxInternetConnectivity;
+    }
+}
@@ -0,0 +1,286 @@
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::internet_connectivity::{ConnectionQuality, socket};
+
+/// Default target measured by [`QualityProbe`].
+const DEFAULT_TARGET: &str = "www.google.com:443";
+
+/// Default number of samples in each burst.
+const DEFAULT_COUNT: u32 = 10;
+
+/// Default time between the start of consecutive samples.
+const DEFAULT_SPACING: Duration = Duration::from_millis(100);
+
+/// Default time after which a sample is considered lost.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Round-trip times, jitter and loss measured over a burst of samples.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QualityReport {
+    /// Number of samples sent.
+    pub sent: u32,
+
+    /// Round-trip time of each sample which received a reply, in the order they were sent.
+    pub rtts: Vec<Duration>,
+}
+
+impl QualityReport {
+    /// Creates a [`QualityReport`] from the round-trip time of each sample, or `None` for samples which were lost.
+    pub fn from_samples(samples: impl IntoIterator<Item = Option<Duration>>) -> Self {
+        let mut report = Self::default();
+        for sample in samples {
+            report.sent += 1;
+            report.rtts.extend(sample);
+        }
+        report
+    }
+
+    /// Returns the number of samples which received a reply.
+    pub fn received(&self) -> u32 {
+        self.rtts.len() as u32
+    }
+
+    /// Returns the percentage of samples which were lost, rounded to the nearest whole percent.
+    pub fn loss_percent(&self) -> u32 {
+        match self.sent {
+            0 => 0,
+            sent => ((sent - self.received()) * 100 + sent / 2) / sent,
+        }
+    }
+
+    /// Returns the shortest round-trip time, if any sample received a reply.
+    pub fn min(&self) -> Option<Duration> {
+        self.rtts.iter().min().copied()
+    }
+
+    /// Returns the longest round-trip time, if any sample received a reply.
+    pub fn max(&self) -> Option<Duration> {
+        self.rtts.iter().max().copied()
+    }
+
+    /// Returns the mean round-trip time, if any sample received a reply.
+    pub fn mean(&self) -> Option<Duration> {
+        match self.rtts.len() {
+            0 => None,
+            len => Some(self.rtts.iter().sum::<Duration>() / len as u32),
+        }
+    }
+
+    /// Returns the round-trip time below which `percentile` percent of replies arrived, using the
+    /// nearest-rank method, if any sample received a reply.
+    ///
+    /// # Arguments
+    /// - `percentile`: The percentile, from `0` to `100`; `0` is the minimum and `50` the median.
+    pub fn percentile(&self, percentile: u32) -> Option<Duration> {
+        let mut sorted = self.rtts.clone();
+        sorted.sort();
+
+        let rank = (percentile.min(100) as usize * sorted.len()).div_ceil(100);
+        sorted.get(rank.saturating_sub(1)).copied()
+    }
+
+    /// Returns the median round-trip time, if any sample received a reply.
+    pub fn median(&self) -> Option<Duration> {
+        self.percentile(50)
+    }
+
+    /// Returns the jitter, the mean difference between the round-trip times of consecutive
+    /// replies, if at least two samples received a reply.
+    pub fn jitter(&self) -> Option<Duration> {
+        let differences: Vec<Duration> = self
+            .rtts
+            .windows(2)
+            .map(|pair| pair[0].abs_diff(pair[1]))
+            .collect();
+
+        match differences.len() {
+            0 => None,
+            len => Some(differences.iter().sum::<Duration>() / len as u32),
+        }
+    }
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`QualityReport`] to enable easy printing.
+impl std::fmt::Display for QualityReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{} replies, {}% loss",
+            self.received(),
+            self.sent,
+            self.loss_percent()
+        )?;
+        if let (Some(min), Some(median), Some(max)) = (self.min(), self.median(), self.max()) {
+            write!(f, ", rtt min/median/max {min:?}/{median:?}/{max:?}")?;
+        }
+        if let Some(jitter) = self.jitter() {
+            write!(f, ", jitter {jitter:?}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A way in which a [`QualityReport`] exceeded the [`QualityThresholds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QualityIssue {
+    /// The median round-trip time was too long.
+    Latency(Duration),
+
+    /// The jitter was too high.
+    Jitter(Duration),
+
+    /// Too many samples were lost.
+    Loss {
+        /// The percentage of samples lost.
+        percent: u32,
+    },
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`QualityIssue`] to enable easy printing.
+impl std::fmt::Display for QualityIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            QualityIssue::Latency(rtt) => write!(f, "latency {rtt:?}"),
+            QualityIssue::Jitter(jitter) => write!(f, "jitter {jitter:?}"),
+            QualityIssue::Loss { percent } => write!(f, "{percent}% loss"),
+        }
+    }
+}
+
+/// Limits beyond which the connection is considered degraded.
+///
+/// Each limit is optional; the default thresholds never report an issue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QualityThresholds {
+    /// Longest acceptable median round-trip time.
+    pub max_latency: Option<Duration>,
+
+    /// Highest acceptable jitter.
+    pub max_jitter: Option<Duration>,
+
+    /// Highest acceptable percentage of lost samples.
+    pub max_loss_percent: Option<u32>,
+}
+
+impl QualityThresholds {
+    /// Returns the ways in which `report` exceeds these thresholds, if any.
+    pub fn issues(&self, report: &QualityReport) -> Vec<QualityIssue> {
+        let latency = self
+            .max_latency
+            .zip(report.median())
+            .filter(|(max, median)| median > max)
+            .map(|(_, median)| QualityIssue::Latency(median));
+        let jitter = self
+            .max_jitter
+            .zip(report.jitter())
+            .filter(|(max, jitter)| jitter > max)
+            .map(|(_, jitter)| QualityIssue::Jitter(jitter));
+        let loss = self
+            .max_loss_percent
+            .filter(|max| report.loss_percent() > *max)
+            .map(|_| QualityIssue::Loss {
+                percent: report.loss_percent(),
+            });
+
+        latency.into_iter().chain(jitter).chain(loss).collect()
+    }
+}
+
+/// Implementation of [`ConnectionQuality`] which times a burst of TCP connections.
+///
+/// Each sample opens a TCP connection to the target and times the handshake, which takes one round
+/// trip. A refused connection still received a reply, so it counts towards the round-trip times;
+/// samples which time out or fail otherwise count as lost.
+///
+/// The OS retransmits a lost SYN, typically after one second, so a sample only counts as lost if
+/// the retransmissions are lost too, or the timeout is shorter than the retransmission delay;
+/// otherwise loss shows up as latency. On Linux, the ICMP probe can be used instead to measure loss
+/// directly.
+pub struct QualityProbe {
+    target: String,
+    count: u32,
+    spacing: Duration,
+    timeout: Duration,
+    device: Option<String>,
+}
+
+impl Default for QualityProbe {
+    /// Creates a new instance of [`QualityProbe`] measuring `www.google.com:443`.
+    fn default() -> Self {
+        Self::new(DEFAULT_TARGET)
+    }
+}
+
+impl QualityProbe {
+    /// Creates a new instance of [`QualityProbe`] sending bursts of 10 samples, 100ms apart.
+    ///
+    /// # Arguments
+    /// - `target`: The `host:port` to connect to.
+    pub fn new(target: impl Into<String>) -> Self {
+        Self {
+            target: target.into(),
+            count: DEFAULT_COUNT,
+            spacing: DEFAULT_SPACING,
+            timeout: DEFAULT_TIMEOUT,
+            device: None,
+        }
+    }
+
+    /// Sets the number of samples in each burst.
+    pub fn with_count(mut self, count: u32) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Sets the time between the start of consecutive samples.
+    pub fn with_spacing(mut self, spacing: Duration) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Sets the time after which a sample is considered lost.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Connects through the interface `device` only, such as `wlan0`. Only supported on Linux
+    /// and Windows.
+    pub fn with_device(mut self, device: impl Into<String>) -> Self {
+        self.device = Some(device.into());
+        self
+    }
+
+    fn sample(&self, addr: &SocketAddr) -> Option<Duration> {
+        let start = Instant::now();
+        match socket::connect_timeout(addr, self.device.as_deref(), self.timeout) {
+            Ok(_) => Some(start.elapsed()),
+            Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => Some(start.elapsed()),
+            Err(_) => None,
+        }
+    }
+}
+
+impl ConnectionQuality for QualityProbe {
+    fn measure(&self) -> QualityReport {
+        // If the target cannot be resolved, every sample is lost.
+        let addr = self
+            .target
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addrs| addrs.next());
+
+        QualityReport::from_samples((0..self.count).map(|i| {
+            let start = Instant::now();
+            let rtt = addr.as_ref().and_then(|addr| self.sample(addr));
+
+            if i + 1 < self.count {
+                thread::sleep(self.spacing.saturating_sub(start.elapsed()));
+            }
+            rtt
+        }))
+    }
+}
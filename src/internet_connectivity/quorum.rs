@@ -0,0 +1,234 @@
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::internet_connectivity::{InternetConnectivity, SystemInternetConnectivity};
+
+/// A probe which can be run by a [`QuorumProbe`] on its own thread.
+pub type QuorumMember = Box<dyn InternetConnectivity + Send + Sync>;
+
+/// How the results of the members of a [`QuorumProbe`] are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuorumPolicy {
+    /// Connected if any member succeeds.
+    Any,
+
+    /// Connected if every member succeeds.
+    All,
+
+    /// Connected if at least this many members succeed.
+    AtLeast(usize),
+
+    /// Connected if the weights of the members which succeed add up to at least this score.
+    Weighted(u32),
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`QuorumPolicy`] to enable easy printing.
+impl std::fmt::Display for QuorumPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            QuorumPolicy::Any => write!(f, "any"),
+            QuorumPolicy::All => write!(f, "all"),
+            QuorumPolicy::AtLeast(required) => write!(f, "at least {required}"),
+            QuorumPolicy::Weighted(score) => write!(f, "score of at least {score}"),
+        }
+    }
+}
+
+/// Result of one member of a [`QuorumProbe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuorumMemberResult {
+    /// Name the member was added with, such as `http`.
+    pub name: String,
+
+    /// Weight of the member under [`QuorumPolicy::Weighted`].
+    pub weight: u32,
+
+    /// Whether the member found the internet available.
+    pub connected: bool,
+
+    /// Time the member took to decide.
+    pub elapsed: Duration,
+}
+
+/// Combined result of every member of a [`QuorumProbe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuorumResult {
+    /// The result of each member, in the order the members were added.
+    pub members: Vec<QuorumMemberResult>,
+
+    /// Sum of the weights of the members which succeeded, saturating at [`u32::MAX`].
+    pub score: u32,
+
+    /// Whether the policy was met.
+    pub connected: bool,
+}
+
+impl QuorumResult {
+    /// Returns the number of members which succeeded.
+    pub fn successes(&self) -> usize {
+        self.members
+            .iter()
+            .filter(|member| member.connected)
+            .count()
+    }
+}
+
+/// Implementation of [`InternetConnectivity`] which combines several probes.
+///
+/// Every member is run in parallel, each on its own thread, and their results are combined with a
+/// [`QuorumPolicy`], so a single unreachable or slow target does not decide the result on its own.
+/// A probe with no members is never connected. The result of the latest check is kept, so the
+/// outcome of each member can be reported.
+///
+/// Network connectivity is delegated to `N`, which defaults to the [`SystemInternetConnectivity`]
+/// for the current OS.
+pub struct QuorumProbe<N: InternetConnectivity = SystemInternetConnectivity> {
+    network: N,
+    members: Vec<(String, u32, QuorumMember)>,
+    policy: QuorumPolicy,
+    last_result: Mutex<Option<QuorumResult>>,
+}
+
+impl Default for QuorumProbe {
+    /// Creates a new instance of [`QuorumProbe`] with no members and [`QuorumPolicy::Any`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QuorumProbe {
+    /// Creates a new instance of [`QuorumProbe`] with no members and [`QuorumPolicy::Any`].
+    pub fn new() -> Self {
+        Self {
+            network: SystemInternetConnectivity::default(),
+            members: Vec::new(),
+            policy: QuorumPolicy::Any,
+            last_result: Mutex::new(None),
+        }
+    }
+}
+
+impl<N: InternetConnectivity> QuorumProbe<N> {
+    /// Replaces the checker used for [`InternetConnectivity::is_connected_to_network`].
+    pub fn with_network<M: InternetConnectivity>(self, network: M) -> QuorumProbe<M> {
+        QuorumProbe {
+            network,
+            members: self.members,
+            policy: self.policy,
+            last_result: self.last_result,
+        }
+    }
+
+    /// Sets how the results of the members are combined.
+    pub fn with_policy(mut self, policy: QuorumPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Adds a member with a weight of `1`.
+    ///
+    /// # Arguments
+    /// - `name`: Name reported in the member's result, such as `http`.
+    /// - `probe`: The probe to run.
+    pub fn with_member(
+        self,
+        name: impl Into<String>,
+        probe: impl InternetConnectivity + Send + Sync + 'static,
+    ) -> Self {
+        self.with_weighted_member(name, 1, probe)
+    }
+
+    /// Adds a member counting `weight` towards the score under [`QuorumPolicy::Weighted`].
+    ///
+    /// # Arguments
+    /// - `name`: Name reported in the member's result, such as `http`.
+    /// - `weight`: The weight of the member.
+    /// - `probe`: The probe to run.
+    pub fn with_weighted_member(
+        mut self,
+        name: impl Into<String>,
+        weight: u32,
+        probe: impl InternetConnectivity + Send + Sync + 'static,
+    ) -> Self {
+        self.members.push((name.into(), weight, Box::new(probe)));
+        self
+    }
+
+    /// Returns the policy the results of the members are combined with.
+    pub fn policy(&self) -> QuorumPolicy {
+        self.policy
+    }
+
+    /// Runs every member in parallel and combines their results.
+    pub fn probe(&self) -> QuorumResult {
+        let members: Vec<QuorumMemberResult> = thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .members
+                .iter()
+                .map(|(name, weight, probe)| {
+                    let handle = scope.spawn(move || {
+                        let start = Instant::now();
+                        let connected = probe.is_connected_to_internet();
+                        (connected, start.elapsed())
+                    });
+                    (name, *weight, handle)
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|(name, weight, handle)| {
+                    // A member which panicked counts as having failed.
+                    let (connected, elapsed) = handle.join().unwrap_or((false, Duration::ZERO));
+                    QuorumMemberResult {
+                        name: name.clone(),
+                        weight,
+                        connected,
+                        elapsed,
+                    }
+                })
+                .collect()
+        });
+
+        let successes = members.iter().filter(|member| member.connected).count();
+        let score = members
+            .iter()
+            .filter(|member| member.connected)
+            .fold(0u32, |score, member| score.saturating_add(member.weight));
+        let connected = !members.is_empty()
+            && match self.policy {
+                QuorumPolicy::Any => successes > 0,
+                QuorumPolicy::All => successes == members.len(),
+                QuorumPolicy::AtLeast(required) => successes >= required,
+                QuorumPolicy::Weighted(required) => score >= required,
+            };
+
+        QuorumResult {
+            members,
+            score,
+            connected,
+        }
+    }
+
+    /// Returns the result of the latest [`InternetConnectivity::is_connected_to_internet`] check, if any.
+    pub fn last_result(&self) -> Option<QuorumResult> {
+        self.last_result
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl<N: InternetConnectivity> InternetConnectivity for QuorumProbe<N> {
+    fn is_connected_to_network(&self) -> bool {
+        self.network.is_connected_to_network()
+    }
+
+    fn is_connected_to_internet(&self) -> bool {
+        let result = self.probe();
+        let connected = result.connected;
+        *self.last_result.lock().unwrap_or_else(|e| e.into_inner()) = Some(result);
+        connected
+    }
+}
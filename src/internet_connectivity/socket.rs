@@ -0,0 +1,62 @@
+//! Sockets whose traffic can be restricted to a single interface.
+//!
+//! On Linux, sockets are bound to the interface with `SO_BINDTODEVICE`; on Windows, their unicast
+//! traffic is restricted to the adapter with `IP_UNICAST_IF`. On other OSes the interface is
+//! ignored, and [`crate::config::Config::validate`] rejects probes scoped to one.
+
+use std::io;
+use std::net::{SocketAddr, TcpStream, UdpSocket};
+use std::time::Duration;
+
+/// Opens a TCP connection to `addr`, leaving through the interface `device` only, if set.
+///
+/// # Arguments
+/// - `addr`: The address to connect to.
+/// - `device`: Name of the interface, such as `wlan0`.
+/// - `timeout`: Longest time to wait for the connection to be established.
+pub(super) fn connect_timeout(
+    addr: &SocketAddr,
+    device: Option<&str>,
+    timeout: Duration,
+) -> io::Result<TcpStream> {
+    match device {
+        #[cfg(target_os = "linux")]
+        Some(device) => super::linux::connect_on_interface(addr, device, timeout),
+        #[cfg(target_os = "windows")]
+        Some(device) => super::windows::connect_on_interface(addr, device, timeout),
+        _ => TcpStream::connect_timeout(addr, timeout),
+    }
+}
+
+/// Binds a UDP socket to `addr`, sending through the interface `device` only, if set.
+#[cfg_attr(
+    not(any(target_os = "linux", target_os = "windows")),
+    allow(unused_variables)
+)]
+pub(super) fn bind_udp(addr: SocketAddr, device: Option<&str>) -> io::Result<UdpSocket> {
+    let socket = UdpSocket::bind(addr)?;
+    #[cfg(target_os = "linux")]
+    if let Some(device) = device {
+        super::linux::bind_to_device(&socket, device)?;
+    }
+    #[cfg(target_os = "windows")]
+    if let Some(device) = device {
+        super::windows::bind_to_interface(&socket, &addr, device)?;
+    }
+    Ok(socket)
+}
+
+/// Returns the scope ID of an IPv6 zone index, such as the `eth0` of `fe80::1%eth0`.
+///
+/// Numeric zones are used as is; interface names are only resolved on Linux and Windows.
+pub(super) fn scope_id(zone: &str) -> Option<u32> {
+    if let Ok(index) = zone.parse() {
+        return Some(index);
+    }
+    #[cfg(target_os = "linux")]
+    return super::linux::interface_index(zone);
+    #[cfg(target_os = "windows")]
+    return super::windows::interface_index(zone);
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    None
+}
@@ -0,0 +1,252 @@
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::Mutex;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::internet_connectivity::socket;
+use crate::internet_connectivity::{InternetConnectivity, SystemInternetConnectivity};
+
+/// Endpoints connected to when none are configured.
+const DEFAULT_ENDPOINTS: [&str; 2] = ["www.google.com:443", "one.one.one.one:853"];
+
+/// Default time allowed for each connection attempt.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Default time to wait for an attempt before racing the next address, as recommended by RFC 8305.
+const DEFAULT_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// Result of connecting to a single endpoint with [`TcpConnectProbe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TcpEndpointResult {
+    /// The `host:port` endpoint that was connected to.
+    pub endpoint: String,
+
+    /// The address the handshake completed with, if any.
+    pub address: Option<SocketAddr>,
+
+    /// Time the successful handshake took, or time spent before giving up.
+    pub elapsed: Duration,
+
+    /// Why no handshake completed, if none did.
+    pub error: Option<String>,
+}
+
+impl TcpEndpointResult {
+    /// Returns `true` if a handshake with the endpoint completed.
+    pub fn is_success(&self) -> bool {
+        self.address.is_some()
+    }
+}
+
+/// Implementation of [`InternetConnectivity`] which completes TCP handshakes with a list of endpoints.
+///
+/// Each endpoint is resolved, and its addresses are raced in the style of Happy Eyeballs
+/// (RFC 8305): addresses alternate between IPv6 and IPv4, starting with the family the resolver
+/// listed first, and the next address is tried whenever the previous attempt fails or has not
+/// completed within the attempt delay. The first handshake to complete wins.
+///
+/// Endpoints are tried in order, and the system is considered connected to the internet once a
+/// handshake with any of them completes. The result of that handshake is kept, so the endpoint
+/// and handshake time can be reported.
+///
+/// Network connectivity is delegated to `N`, which defaults to the [`SystemInternetConnectivity`]
+/// for the current OS.
+pub struct TcpConnectProbe<N: InternetConnectivity = SystemInternetConnectivity> {
+    network: N,
+    endpoints: Vec<String>,
+    timeout: Duration,
+    attempt_delay: Duration,
+    device: Option<String>,
+    last_success: Mutex<Option<TcpEndpointResult>>,
+}
+
+impl Default for TcpConnectProbe {
+    /// Creates a new instance of [`TcpConnectProbe`] connecting to `www.google.com:443` and `one.one.one.one:853`.
+    fn default() -> Self {
+        Self::new(DEFAULT_ENDPOINTS)
+    }
+}
+
+impl TcpConnectProbe {
+    /// Creates a new instance of [`TcpConnectProbe`] allowing 3s for each attempt and racing
+    /// addresses 250ms apart.
+    ///
+    /// # Arguments
+    /// - `endpoints`: The `host:port` endpoints to connect to, in order.
+    pub fn new<S: Into<String>>(endpoints: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            network: SystemInternetConnectivity::default(),
+            endpoints: endpoints.into_iter().map(Into::into).collect(),
+            timeout: DEFAULT_TIMEOUT,
+            attempt_delay: DEFAULT_ATTEMPT_DELAY,
+            device: None,
+            last_success: Mutex::new(None),
+        }
+    }
+}
+
+impl<N: InternetConnectivity> TcpConnectProbe<N> {
+    /// Replaces the checker used for [`InternetConnectivity::is_connected_to_network`].
+    pub fn with_network<M: InternetConnectivity>(self, network: M) -> TcpConnectProbe<M> {
+        TcpConnectProbe {
+            network,
+            endpoints: self.endpoints,
+            timeout: self.timeout,
+            attempt_delay: self.attempt_delay,
+            device: self.device,
+            last_success: self.last_success,
+        }
+    }
+
+    /// Sets the time allowed for each connection attempt.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the time to wait for an attempt before racing the next address.
+    pub fn with_attempt_delay(mut self, attempt_delay: Duration) -> Self {
+        self.attempt_delay = attempt_delay;
+        self
+    }
+
+    /// Connects through the interface `device` only, such as `wlan0`. Only supported on Linux
+    /// and Windows.
+    pub fn with_device(mut self, device: impl Into<String>) -> Self {
+        self.device = Some(device.into());
+        self
+    }
+
+    /// Returns the endpoints that will be connected to.
+    pub fn endpoints(&self) -> &[String] {
+        &self.endpoints
+    }
+
+    /// Connects to every endpoint and returns the result for each, in order.
+    pub fn probe(&self) -> Vec<TcpEndpointResult> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| self.connect(endpoint))
+            .collect()
+    }
+
+    /// Resolves `endpoint` and races its addresses until a handshake completes.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The `host:port` to connect to.
+    pub fn connect(&self, endpoint: &str) -> TcpEndpointResult {
+        let started = Instant::now();
+        let outcome = endpoint
+            .to_socket_addrs()
+            .and_then(|addrs| self.race(&addrs.collect::<Vec<_>>()));
+
+        match outcome {
+            Ok((address, handshake)) => TcpEndpointResult {
+                endpoint: endpoint.to_string(),
+                address: Some(address),
+                elapsed: handshake,
+                error: None,
+            },
+            Err(e) => TcpEndpointResult {
+                endpoint: endpoint.to_string(),
+                address: None,
+                elapsed: started.elapsed(),
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
+    /// Races connection attempts to `addresses`, returning the address whose handshake completed
+    /// first and the time that handshake took.
+    ///
+    /// Returns the error of the last attempt if none completed.
+    pub fn race(&self, addresses: &[SocketAddr]) -> io::Result<(SocketAddr, Duration)> {
+        let mut pending = interleave(addresses).into_iter();
+        let (sender, receiver) = mpsc::channel();
+        let mut running = 0;
+        let mut last_error = io::Error::new(io::ErrorKind::NotFound, "no addresses to connect to");
+
+        loop {
+            // Start the next attempt, unless every address has been tried.
+            if let Some(address) = pending.next() {
+                let sender = sender.clone();
+                let timeout = self.timeout;
+                let device = self.device.clone();
+                // Attempts outliving the race are left to finish on their own, bounded by the timeout.
+                thread::spawn(move || {
+                    let started = Instant::now();
+                    let result = socket::connect_timeout(&address, device.as_deref(), timeout);
+                    let _ = sender.send((address, result.map(|_| started.elapsed())));
+                });
+                running += 1;
+            }
+            if running == 0 {
+                return Err(last_error);
+            }
+
+            let wait = match pending.len() {
+                0 => self.timeout,
+                _ => self.attempt_delay,
+            };
+            match receiver.recv_timeout(wait) {
+                Ok((address, Ok(handshake))) => return Ok((address, handshake)),
+                Ok((_, Err(e))) => {
+                    running -= 1;
+                    last_error = e;
+                }
+                Err(RecvTimeoutError::Timeout) if pending.len() == 0 => {
+                    return Err(io::Error::from(io::ErrorKind::TimedOut));
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    /// Returns the result of the handshake which satisfied the latest
+    /// [`InternetConnectivity::is_connected_to_internet`] check, if any.
+    pub fn last_success(&self) -> Option<TcpEndpointResult> {
+        self.last_success
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl<N: InternetConnectivity> InternetConnectivity for TcpConnectProbe<N> {
+    fn is_connected_to_network(&self) -> bool {
+        self.network.is_connected_to_network()
+    }
+
+    fn is_connected_to_internet(&self) -> bool {
+        let success = self
+            .endpoints
+            .iter()
+            .map(|endpoint| self.connect(endpoint))
+            .find(TcpEndpointResult::is_success);
+        let connected = success.is_some();
+        *self.last_success.lock().unwrap_or_else(|e| e.into_inner()) = success;
+        connected
+    }
+}
+
+/// Orders `addresses` alternating between address families, starting with the family of the
+/// first address and otherwise keeping the resolver's order.
+fn interleave(addresses: &[SocketAddr]) -> Vec<SocketAddr> {
+    let Some(first) = addresses.first() else {
+        return Vec::new();
+    };
+    let (mut preferred, mut other): (Vec<SocketAddr>, Vec<SocketAddr>) = addresses
+        .iter()
+        .partition(|address| address.is_ipv6() == first.is_ipv6());
+    preferred.reverse();
+    other.reverse();
+
+    let mut ordered = Vec::with_capacity(addresses.len());
+    while !preferred.is_empty() || !other.is_empty() {
+        ordered.extend(preferred.pop());
+        ordered.extend(other.pop());
+    }
+    ordered
+}
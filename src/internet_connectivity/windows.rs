@@ -1,28 +1,255 @@
+use crate::adapter::InterfaceSelector;
 use crate::internet_connectivity::InternetConnectivity;
+use std::io;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::os::windows::io::{AsRawSocket, FromRawSocket, RawSocket};
+use std::sync::Once;
+use std::time::Duration;
+use windows::Win32::NetworkManagement::IpHelper::{
+    ConvertInterfaceAliasToLuid, ConvertInterfaceLuidToIndex,
+};
+use windows::Win32::NetworkManagement::Ndis::NET_LUID_LH;
 use windows::Win32::Networking::WinInet::{FLAG_ICC_FORCE_CONNECTION, InternetCheckConnectionW};
 use windows::Win32::Networking::WinInet::{INTERNET_CONNECTION, InternetGetConnectedState};
+use windows::Win32::Networking::WinSock::{
+    AF_INET, AF_INET6, IN_ADDR, IN_ADDR_0, IN6_ADDR, IN6_ADDR_0, IP_UNICAST_IF, IPPROTO_IP,
+    IPPROTO_IPV6, IPPROTO_TCP, IPV6_UNICAST_IF, POLLWRNORM, SOCK_STREAM, SOCKADDR_IN, SOCKADDR_IN6,
+    SOCKADDR_IN6_0, SOCKADDR_INET, SOCKET, WSADATA, WSAEWOULDBLOCK, WSAGetLastError,
+    WSAPOLL_EVENT_FLAGS, WSAPOLLFD, WSAPoll, WSAStartup, connect, setsockopt, socket,
+};
+
+/// Host connected to by the internet check when it is scoped to an interface.
+const CHECK_HOST: &str = "www.google.com:80";
+
+/// Longest time the scoped internet check waits for each connection.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Implementation of [`InternetConnectivity`] for Windows OS.
-pub struct WindowsInternetConnectivity;
+///
+/// When an [`InterfaceSelector`] is set, the network is only considered connected while a
+/// selected adapter is up, and the internet only while a TCP connection made through one of the
+/// selected adapters that are up succeeds. Without a selector, the internet check follows the
+/// system routing table.
+#[derive(Default)]
+pub struct WindowsInternetConnectivity {
+    interface: Option<InterfaceSelector>,
+}
+
+impl WindowsInternetConnectivity {
+    /// Scopes the checks to the adapters chosen by `selector`.
+    pub fn with_interface(mut self, selector: InterfaceSelector) -> Self {
+        self.interface = Some(selector);
+        self
+    }
+
+    /// Returns the names of the adapters chosen by the selector, or `None` if no selector is set.
+    ///
+    /// A selector picking a single adapter by name is used as is, so the adapter does not need to
+    /// be present.
+    pub fn selected_interfaces(&self) -> Option<Vec<String>> {
+        let selector = self.interface.as_ref()?;
+        if let Some(name) = selector.single_name() {
+            return Some(vec![name.to_string()]);
+        }
+
+        Some(
+            selector
+                .resolve()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|adapter| adapter.name)
+                .collect(),
+        )
+    }
+
+    /// Returns `true` if no selector is set, or if an adapter it selects is up.
+    fn selected_adapter_up(&self) -> bool {
+        match &self.interface {
+            None => true,
+            Some(selector) => selector
+                .resolve()
+                .is_ok_and(|adapters| adapters.iter().any(|adapter| adapter.up)),
+        }
+    }
+}
 
 impl InternetConnectivity for WindowsInternetConnectivity {
     fn is_connected_to_network(&self) -> bool {
         let mut flags = INTERNET_CONNECTION(0);
-        unsafe { InternetGetConnectedState(&mut flags, Some(0)).is_ok() }
+        self.selected_adapter_up()
+            && unsafe { InternetGetConnectedState(&mut flags, Some(0)).is_ok() }
     }
 
     fn is_connected_to_internet(&self) -> bool {
-        let url: Vec<u16> = "http://www.google.com"
-            .encode_utf16()
-            .chain(std::iter::once(0))
-            .collect();
-        unsafe {
-            InternetCheckConnectionW(
-                windows::core::PCWSTR(url.as_ptr()),
-                FLAG_ICC_FORCE_CONNECTION,
-                0,
-            )
-            .is_ok()
+        let Some(selector) = &self.interface else {
+            let url: Vec<u16> = "http://www.google.com"
+                .encode_utf16()
+                .chain(std::iter::once(0))
+                .collect();
+            return unsafe {
+                InternetCheckConnectionW(
+                    windows::core::PCWSTR(url.as_ptr()),
+                    FLAG_ICC_FORCE_CONNECTION,
+                    0,
+                )
+                .is_ok()
+            };
+        };
+
+        let Ok(adapters) = selector.resolve() else {
+            return false;
+        };
+        let Ok(addrs) = CHECK_HOST.to_socket_addrs() else {
+            return false;
+        };
+        let addrs: Vec<SocketAddr> = addrs.collect();
+
+        adapters.iter().filter(|adapter| adapter.up).any(|adapter| {
+            addrs
+                .iter()
+                .any(|addr| connect_on_interface(addr, &adapter.name, CHECK_TIMEOUT).is_ok())
+        })
+    }
+}
+
+/// Opens a TCP connection to `addr` that only leaves through the adapter named `iface`, waiting
+/// at most `timeout` for it to be established.
+pub(super) fn connect_on_interface(
+    addr: &SocketAddr,
+    iface: &str,
+    timeout: Duration,
+) -> io::Result<TcpStream> {
+    startup();
+    let family = match addr {
+        SocketAddr::V4(_) => AF_INET,
+        SocketAddr::V6(_) => AF_INET6,
+    };
+    let handle = unsafe { socket(i32::from(family.0), SOCK_STREAM, IPPROTO_TCP.0) }?;
+    let stream = unsafe { TcpStream::from_raw_socket(handle.0 as RawSocket) };
+    bind_to_interface(&stream, addr, iface)?;
+    stream.set_nonblocking(true)?;
+
+    let (storage, len) = sockaddr(addr);
+    let result = unsafe { connect(handle, (&raw const storage).cast(), len) };
+    if result != 0 {
+        let error = unsafe { WSAGetLastError() };
+        if error != WSAEWOULDBLOCK {
+            return Err(io::Error::from_raw_os_error(error.0));
         }
+        wait_connected(&stream, timeout)?;
+    }
+
+    stream.set_nonblocking(false)?;
+    Ok(stream)
+}
+
+/// Restricts the unicast traffic of `socket` to the adapter named `iface`, with `IP_UNICAST_IF` or
+/// `IPV6_UNICAST_IF` depending on the address family of `addr`.
+pub(super) fn bind_to_interface(
+    socket: &impl AsRawSocket,
+    addr: &SocketAddr,
+    iface: &str,
+) -> io::Result<()> {
+    let index = interface_index(iface).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, format!("no adapter named {iface}"))
+    })?;
+    // IPv4 expects the index in network byte order, IPv6 in host byte order.
+    let (level, option, value) = match addr {
+        SocketAddr::V4(_) => (IPPROTO_IP, IP_UNICAST_IF, index.to_be()),
+        SocketAddr::V6(_) => (IPPROTO_IPV6, IPV6_UNICAST_IF, index),
+    };
+    let result = unsafe {
+        setsockopt(
+            SOCKET(socket.as_raw_socket() as usize),
+            level.0,
+            option,
+            Some(&value.to_ne_bytes()),
+        )
+    };
+    match result {
+        0 => Ok(()),
+        _ => Err(io::Error::from_raw_os_error(unsafe { WSAGetLastError() }.0)),
+    }
+}
+
+/// Returns the index of the adapter named `iface`, such as `Wi-Fi`, or `None` if there is no such
+/// adapter.
+pub(super) fn interface_index(iface: &str) -> Option<u32> {
+    let alias: Vec<u16> = iface.encode_utf16().chain(std::iter::once(0)).collect();
+    let mut luid = NET_LUID_LH::default();
+    let mut index = 0;
+    let found = unsafe {
+        ConvertInterfaceAliasToLuid(windows::core::PCWSTR(alias.as_ptr()), &mut luid).is_ok()
+            && ConvertInterfaceLuidToIndex(&luid, &mut index).is_ok()
+    };
+    found.then_some(index)
+}
+
+/// Initializes Winsock, which the standard library only does once it creates a socket itself.
+fn startup() {
+    static STARTUP: Once = Once::new();
+    STARTUP.call_once(|| {
+        let mut data = WSADATA::default();
+        unsafe { WSAStartup(0x202, &mut data) };
+    });
+}
+
+/// Waits for a non-blocking connect on `socket` to complete, returning the error it failed with, if any.
+fn wait_connected(socket: &TcpStream, timeout: Duration) -> io::Result<()> {
+    let mut pollfd = WSAPOLLFD {
+        fd: SOCKET(socket.as_raw_socket() as usize),
+        events: POLLWRNORM,
+        revents: WSAPOLL_EVENT_FLAGS(0),
+    };
+    let millis = timeout.as_millis().min(i32::MAX as u128) as i32;
+
+    match unsafe { WSAPoll(&mut pollfd, 1, millis) } {
+        0 => return Err(io::Error::from(io::ErrorKind::TimedOut)),
+        n if n < 0 => return Err(io::Error::from_raw_os_error(unsafe { WSAGetLastError() }.0)),
+        _ => {}
+    }
+
+    match socket.take_error()? {
+        None => Ok(()),
+        Some(error) => Err(error),
+    }
+}
+
+/// Converts `addr` into the `SOCKADDR_INET` representation expected by `connect`.
+fn sockaddr(addr: &SocketAddr) -> (SOCKADDR_INET, i32) {
+    match addr {
+        SocketAddr::V4(addr) => (
+            SOCKADDR_INET {
+                Ipv4: SOCKADDR_IN {
+                    sin_family: AF_INET,
+                    sin_port: addr.port().to_be(),
+                    sin_addr: IN_ADDR {
+                        S_un: IN_ADDR_0 {
+                            S_addr: u32::from_ne_bytes(addr.ip().octets()),
+                        },
+                    },
+                    sin_zero: [0; 8],
+                },
+            },
+            size_of::<SOCKADDR_IN>() as i32,
+        ),
+        SocketAddr::V6(addr) => (
+            SOCKADDR_INET {
+                Ipv6: SOCKADDR_IN6 {
+                    sin6_family: AF_INET6,
+                    sin6_port: addr.port().to_be(),
+                    sin6_flowinfo: addr.flowinfo(),
+                    sin6_addr: IN6_ADDR {
+                        u: IN6_ADDR_0 {
+                            Byte: addr.ip().octets(),
+                        },
+                    },
+                    Anonymous: SOCKADDR_IN6_0 {
+                        sin6_scope_id: addr.scope_id(),
+                    },
+                },
+            },
+            size_of::<SOCKADDR_IN6>() as i32,
+        ),
     }
 }
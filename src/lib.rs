@@ -1,8 +1,21 @@
 //! A Rust application to monitor internet connectivity, and if no internet available, attempt to reconnect the network.
 //!
-//! This is currently only implemented for Windows OS, as an over-engineered method of resolving internet connectivity issues
-//! when using my personal phone's hotspot for connectivity.
+//! This is an over-engineered method of resolving internet connectivity issues when using my personal phone's hotspot for
+//! connectivity. Connectivity checks are implemented for Windows and Linux. Reconnecting is implemented for Windows,
+//! and for Linux machines running either NetworkManager or wpa_supplicant.
 
+pub mod adapter;
 pub mod app;
+pub mod cancellation;
+pub mod config;
+pub mod diagnosis;
+pub mod error;
+pub mod events;
+pub mod history;
+pub mod hysteresis;
 pub mod internet_connectivity;
+pub mod logging;
 pub mod network_manager;
+pub mod notifications;
+pub mod remediation;
+pub mod report;
@@ -0,0 +1,234 @@
+//! Module for configuring where diagnostics are written.
+//!
+//! The library emits structured [`tracing`] events. This module defines the [`LogFormat`]s the
+//! binary can write them in, and [`init`] which installs the matching subscriber.
+
+use std::io;
+use std::str::FromStr;
+
+use tracing::Level;
+use tracing_subscriber::EnvFilter;
+
+/// Environment variable which overrides the log filter, using the [`EnvFilter`] syntax.
+pub const FILTER_ENV: &str = "RUST_LOG";
+
+/// Socket the local syslog daemon receives messages on.
+pub const SYSLOG_SOCKET: &str = "/dev/log";
+
+/// The `daemon` syslog facility, which messages are sent with.
+const SYSLOG_FACILITY: u8 = 3;
+
+/// The format diagnostics are written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Human-readable lines on standard error.
+    #[default]
+    Text,
+
+    /// One JSON object per line on standard error.
+    Json,
+
+    /// Structured entries sent to the systemd journal (Linux only).
+    Journald,
+
+    /// RFC 3164 messages sent to the local syslog daemon through [`SYSLOG_SOCKET`] (Unix only).
+    Syslog,
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`LogFormat`] to enable easy printing.
+impl std::fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LogFormat::Text => write!(f, "text"),
+            LogFormat::Json => write!(f, "json"),
+            LogFormat::Journald => write!(f, "journald"),
+            LogFormat::Syslog => write!(f, "syslog"),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            "journald" => Ok(LogFormat::Journald),
+            "syslog" => Ok(LogFormat::Syslog),
+            _ => Err(format!(
+                "unknown log format {s:?}, expected text, json, journald or syslog"
+            )),
+        }
+    }
+}
+
+/// Installs a global subscriber writing events in `format`.
+///
+/// Events at `level` and above are written, unless [`FILTER_ENV`] is set, in which case it
+/// decides which events are written.
+///
+/// # Arguments
+/// - `format`: The format to write events in.
+/// - `level`: The least severe level to write.
+pub fn init(format: LogFormat, level: Level) -> io::Result<()> {
+    let filter = EnvFilter::builder()
+        .with_default_directive(level.into())
+        .with_env_var(FILTER_ENV)
+        .from_env_lossy();
+
+    let result = match format {
+        LogFormat::Text => tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_writer(io::stderr)
+            .try_init(),
+        LogFormat::Json => tracing_subscriber::fmt()
+            .json()
+            .with_env_filter(filter)
+            .with_writer(io::stderr)
+            .try_init(),
+        LogFormat::Journald => return init_journald(filter),
+        LogFormat::Syslog => return init_syslog(filter),
+    };
+
+    result.map_err(io::Error::other)
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(target_os = "linux")] {
+        fn init_journald(filter: EnvFilter) -> io::Result<()> {
+            use tracing_subscriber::layer::SubscriberExt;
+            use tracing_subscriber::util::SubscriberInitExt;
+
+            tracing_subscriber::registry()
+                .with(filter)
+                .with(tracing_journald::layer()?)
+                .try_init()
+                .map_err(io::Error::other)
+        }
+    } else {
+        fn init_journald(_filter: EnvFilter) -> io::Result<()> {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "journald logging is only supported on Linux",
+            ))
+        }
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(unix)] {
+        use std::os::unix::net::UnixDatagram;
+        use std::path::Path;
+        use std::sync::Arc;
+
+        use tracing_subscriber::fmt::MakeWriter;
+
+        fn init_syslog(filter: EnvFilter) -> io::Result<()> {
+            tracing_subscriber::fmt()
+                .with_env_filter(filter)
+                .with_writer(SyslogWriter::connect(SYSLOG_SOCKET)?)
+                .with_ansi(false)
+                .without_time()
+                .with_level(false)
+                .try_init()
+                .map_err(io::Error::other)
+        }
+
+        /// A [`MakeWriter`] sending each event to a syslog daemon as an RFC 3164 message.
+        ///
+        /// Messages carry the `daemon` facility, a severity matching the level of the event, and
+        /// the tag and process ID, such as `<28>internet_reloader[412]: Connection lost`. The
+        /// timestamp and hostname are left to the daemon, as for messages sent by `syslog(3)`.
+        #[derive(Debug, Clone)]
+        pub struct SyslogWriter {
+            socket: Arc<UnixDatagram>,
+            tag: String,
+        }
+
+        impl SyslogWriter {
+            /// Creates a new instance of [`SyslogWriter`] sending to the datagram socket at `path`,
+            /// such as [`SYSLOG_SOCKET`], tagged with the name of the binary.
+            pub fn connect(path: impl AsRef<Path>) -> io::Result<Self> {
+                let socket = UnixDatagram::unbound()?;
+                socket.connect(path)?;
+                Ok(Self {
+                    socket: Arc::new(socket),
+                    tag: env!("CARGO_PKG_NAME").to_string(),
+                })
+            }
+
+            /// Sets the tag identifying the messages, in place of the name of the binary.
+            pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+                self.tag = tag.into();
+                self
+            }
+
+            fn message(&self, severity: u8) -> SyslogMessage<'_> {
+                SyslogMessage {
+                    writer: self,
+                    severity,
+                    buffer: Vec::new(),
+                }
+            }
+        }
+
+        impl<'a> MakeWriter<'a> for SyslogWriter {
+            type Writer = SyslogMessage<'a>;
+
+            fn make_writer(&'a self) -> Self::Writer {
+                self.message(6)
+            }
+
+            fn make_writer_for(&'a self, meta: &tracing::Metadata<'_>) -> Self::Writer {
+                let severity = match *meta.level() {
+                    Level::ERROR => 3,
+                    Level::WARN => 4,
+                    Level::INFO => 6,
+                    _ => 7,
+                };
+                self.message(severity)
+            }
+        }
+
+        /// A single event being formatted, sent to syslog once it is complete.
+        pub struct SyslogMessage<'a> {
+            writer: &'a SyslogWriter,
+            severity: u8,
+            buffer: Vec<u8>,
+        }
+
+        impl io::Write for SyslogMessage<'_> {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.buffer.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        impl Drop for SyslogMessage<'_> {
+            fn drop(&mut self) {
+                let text = String::from_utf8_lossy(&self.buffer);
+                let message = format!(
+                    "<{}>{}[{}]: {}",
+                    SYSLOG_FACILITY * 8 + self.severity,
+                    self.writer.tag,
+                    std::process::id(),
+                    text.trim_end()
+                );
+                // Logging has nowhere to report its own failures, so a lost message is dropped.
+                let _ = self.writer.socket.send(message.as_bytes());
+            }
+        }
+    } else {
+        fn init_syslog(_filter: EnvFilter) -> io::Result<()> {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "syslog logging is only supported on Unix",
+            ))
+        }
+    }
+}
@@ -1,7 +1,331 @@
-use internet_reloader::app;
-fn main() {
-    let app = app::NetworkApp::default();
-    let res = app.poll();
+mod cli;
 
-    println!("{}", res);
+use std::process::ExitCode;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use clap::Parser;
+use cli::{
+    Cli, Command, Options, ProbeArgs, ProbeName, ReportArgs, diagnosis_exit_code, exit,
+    status_exit_code,
+};
+use internet_reloader::adapter;
+use internet_reloader::cancellation::CancellationToken;
+use internet_reloader::config::{Config, ConfigWatcher, DnsProbeConfig, HttpProbeConfig};
+use internet_reloader::history::HistoryLog;
+use internet_reloader::internet_connectivity::{
+    CaptivePortalDetector, HttpProbeResult, InternetConnectivity,
+};
+use internet_reloader::logging;
+use internet_reloader::report::UptimeReport;
+use tracing::{error, info, warn};
+
+/// How often `watch` checks whether the configuration file has changed.
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    if let Err(e) = logging::init(cli.options.log_format, cli.options.level()) {
+        eprintln!(
+            "Failed to initialise {} logging: {e}",
+            cli.options.log_format
+        );
+        return ExitCode::from(exit::FAILURE);
+    }
+
+    let config = || {
+        cli.options
+            .load_config()
+            .inspect_err(|e| eprintln!("Invalid configuration: {e}"))
+    };
+
+    let code = match cli.command {
+        Command::Status => config().map_or(exit::FAILURE, |config| status(&config)),
+        Command::Diagnose => config().map_or(exit::FAILURE, |config| diagnose(&config)),
+        Command::Watch { count } => {
+            config().map_or(exit::FAILURE, |config| watch(&cli.options, config, count))
+        }
+        Command::Reconnect { timeout } => {
+            config().map_or(exit::FAILURE, |config| reconnect(&config, timeout))
+        }
+        Command::Probe(args) => config().map_or(exit::FAILURE, |config| {
+            probe(&args, &config, cli.options.config.is_some())
+        }),
+        Command::Interfaces => interfaces(),
+        Command::Report(args) => match args.history.clone() {
+            Some(path) => report(&args, HistoryLog::new(path)),
+            None => config().map_or(exit::FAILURE, |config| match config.history.path {
+                Some(path) => report(&args, HistoryLog::new(path)),
+                None => {
+                    eprintln!("No history file, set `[history] path` or pass --history");
+                    exit::FAILURE
+                }
+            }),
+        },
+    };
+
+    ExitCode::from(code)
+}
+
+fn status(config: &Config) -> u8 {
+    let status = config.app().check();
+    println!("{status}");
+    status_exit_code(&status)
+}
+
+fn diagnose(config: &Config) -> u8 {
+    let report = config.app().diagnose();
+    print!("{report}");
+    diagnosis_exit_code(&report)
+}
+
+/// Supervises the network, rebuilding the app with the new settings whenever the configuration
+/// file is reloaded, carrying on from the state of the previous app.
+fn watch(options: &Options, mut config: Config, count: Option<u64>) -> u8 {
+    let mut watcher = options.config.as_ref().map(ConfigWatcher::new);
+    #[cfg(target_os = "linux")]
+    if let Some(Err(e)) = watcher.as_ref().map(ConfigWatcher::watch_sighup) {
+        warn!(error = %e, "Failed to watch for SIGHUP, reloading on file change only");
+    }
+
+    let mut app = config.app();
+    let mut polls = 0;
+    loop {
+        let token = CancellationToken::new();
+        let stop_watching = CancellationToken::new();
+
+        let summary = thread::scope(|scope| {
+            if let Some(watcher) = watcher.as_mut() {
+                scope.spawn(|| {
+                    if watcher.wait(CONFIG_POLL_INTERVAL, &stop_watching) {
+                        token.cancel();
+                    }
+                });
+            }
+
+            let summary = app.run_until(config.monitor.interval, &token, |status| {
+                println!("{status}");
+                polls += 1;
+                count.is_some_and(|count| polls >= count)
+            });
+            stop_watching.cancel();
+            summary
+        });
+
+        if !token.is_cancelled() {
+            return summary
+                .last_status
+                .as_ref()
+                .map_or(exit::SUCCESS, status_exit_code);
+        }
+
+        match options.load_config() {
+            Ok(reloaded) => {
+                info!(path = ?options.config, "Configuration reloaded");
+                if reloaded.subscribers_differ(&config) {
+                    warn!("Changes to notifications and history take effect after restarting");
+                }
+                app = reloaded.reload_app(app);
+                config = reloaded;
+            }
+            Err(e) => {
+                error!(error = %e, "Invalid configuration, keeping the previous one");
+            }
+        }
+    }
+}
+
+fn reconnect(config: &Config, timeout: Option<Duration>) -> u8 {
+    let mut verification = config.recovery_verification();
+    if let Some(timeout) = timeout {
+        verification.timeout = timeout;
+    }
+    let app = config.app().with_recovery_verification(verification);
+
+    match app.reconnect() {
+        Ok(verification) if verification.recovered => {
+            println!(
+                "Reconnected, internet restored after {:?}",
+                verification.elapsed
+            );
+            exit::SUCCESS
+        }
+        Ok(verification) => {
+            println!(
+                "Reconnected, internet not restored within {:?}",
+                verification.elapsed
+            );
+            exit::NETWORK_ONLY
+        }
+        Err(e) => {
+            eprintln!("Reconnect failed: {e}");
+            exit::FAILURE
+        }
+    }
+}
+
+/// Runs the probe named by `args`, as set up by `config` and scoped to its `[network]` interface.
+///
+/// Probes with a `[probes]` section in the configuration use it; the others use the arguments.
+/// Captive portal detection uses `[probes.captive]` when a configuration file is `configured`.
+fn probe(args: &ProbeArgs, config: &Config, configured: bool) -> u8 {
+    match args.probe {
+        ProbeName::System => {
+            let checker = config.system_checker();
+            let network = checker.is_connected_to_network();
+            let internet = checker.is_connected_to_internet();
+            println!("network\t{network}");
+            println!("internet\t{internet}");
+            match (network, internet) {
+                (_, true) => exit::SUCCESS,
+                (true, false) => exit::NETWORK_ONLY,
+                (false, false) => exit::DISCONNECTED,
+            }
+        }
+        ProbeName::Http if config.network.interface.is_some() => {
+            eprintln!("The http probe cannot be scoped to an interface");
+            exit::FAILURE
+        }
+        ProbeName::Http => {
+            let http = config.probes.http.clone().unwrap_or(HttpProbeConfig {
+                urls: args.urls.clone(),
+                expected_status: args.expect_status,
+                expected_body: None,
+                timeout: args.timeout,
+            });
+            let results = config.http_probe(&http).probe();
+            print_http_results(&results);
+            http_exit_code(&results)
+        }
+        ProbeName::Dns => {
+            let dns = config.probes.dns.clone().unwrap_or(DnsProbeConfig {
+                servers: args.servers.clone(),
+                query: args.query.clone(),
+                timeout: args.timeout,
+                ..DnsProbeConfig::default()
+            });
+            let results = config.dns_probe(&dns).probe();
+            for result in &results {
+                println!(
+                    "{}\t{}\t{:?}\t{:?}",
+                    result.server,
+                    result
+                        .transport
+                        .map_or("-".to_string(), |transport| format!("{transport:?}")),
+                    result.outcome,
+                    result.latency,
+                );
+            }
+            match results.iter().any(|result| result.is_success()) {
+                true => exit::SUCCESS,
+                false => exit::NETWORK_ONLY,
+            }
+        }
+        ProbeName::Captive => {
+            let detector = match configured {
+                true => config.captive_portal_detector(config.system_checker()),
+                false => {
+                    CaptivePortalDetector::new(config.system_checker()).with_timeout(args.timeout)
+                }
+            };
+            let results = detector.probe();
+            print_http_results(&results);
+            http_exit_code(&results)
+        }
+        ProbeName::Quorum => probe_quorum(config),
+    }
+}
+
+fn probe_quorum(config: &Config) -> u8 {
+    let Some(quorum) = &config.probes.quorum else {
+        eprintln!("No quorum configured, add a `[probes.quorum]` section");
+        return exit::FAILURE;
+    };
+
+    let probe = config.quorum_probe(quorum);
+    let result = probe.probe();
+    for member in &result.members {
+        println!(
+            "{}\tweight {}\t{}\t{:?}",
+            member.name, member.weight, member.connected, member.elapsed
+        );
+    }
+    println!(
+        "quorum\t{}\tscore {}\t{}",
+        probe.policy(),
+        result.score,
+        result.connected
+    );
+    match result.connected {
+        true => exit::SUCCESS,
+        false => exit::NETWORK_ONLY,
+    }
+}
+
+fn print_http_results(results: &[HttpProbeResult]) {
+    for result in results {
+        println!(
+            "{}\t{}\t{:?}\t{:?}{}",
+            result.url,
+            result
+                .status
+                .map_or("-".to_string(), |status| status.to_string()),
+            result.outcome,
+            result.elapsed,
+            result
+                .location
+                .as_ref()
+                .map_or(String::new(), |location| format!("\t{location}")),
+        );
+    }
+}
+
+fn http_exit_code(results: &[HttpProbeResult]) -> u8 {
+    if results.iter().any(HttpProbeResult::is_success) {
+        exit::SUCCESS
+    } else if results
+        .iter()
+        .any(|result| result.captive_portal().is_some())
+    {
+        exit::CAPTIVE_PORTAL
+    } else {
+        exit::NETWORK_ONLY
+    }
+}
+
+fn interfaces() -> u8 {
+    match adapter::adapters() {
+        Ok(adapters) => {
+            for adapter in adapters {
+                println!("{adapter}");
+            }
+            exit::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Failed to list interfaces: {e}");
+            exit::FAILURE
+        }
+    }
+}
+
+fn report(args: &ReportArgs, log: HistoryLog) -> u8 {
+    let to = SystemTime::now();
+    let from = to.checked_sub(args.since).unwrap_or(SystemTime::UNIX_EPOCH);
+
+    match log.records() {
+        Ok(records) if records.is_empty() => {
+            eprintln!("No history recorded in {}", log.path().display());
+            exit::FAILURE
+        }
+        Ok(records) => {
+            let report = UptimeReport::new(&records, args.period.into(), from, to);
+            print!("{}", report.render(args.format.into()));
+            exit::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Failed to read {}: {e}", log.path().display());
+            exit::FAILURE
+        }
+    }
 }
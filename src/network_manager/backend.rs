@@ -0,0 +1,72 @@
+use serde::Deserialize;
+
+use crate::adapter::InterfaceSelector;
+use crate::network_manager::NetworkManager;
+
+/// Interface managed by single-interface backends when none is configured.
+pub const DEFAULT_INTERFACE: &str = "wlan0";
+
+/// The backends available to reconnect the network, selectable at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Backend {
+    /// The default backend for the current OS
+    #[default]
+    Auto,
+
+    /// NetworkManager over D-Bus
+    #[cfg(target_os = "linux")]
+    NetworkManager,
+
+    /// The wpa_supplicant control socket of the managed interface
+    #[cfg(target_os = "linux")]
+    WpaSupplicant,
+
+    /// The Windows WLAN API
+    #[cfg(target_os = "windows")]
+    Wlan,
+}
+
+impl Backend {
+    /// Creates the [`NetworkManager`] for this backend.
+    ///
+    /// # Arguments
+    /// - `interface`: Selects the interfaces to reconnect. Without one, backends which find the
+    ///   interface themselves reconnect the first wireless interface, and the others manage
+    ///   [`DEFAULT_INTERFACE`].
+    pub fn manager(self, interface: Option<&InterfaceSelector>) -> Box<dyn NetworkManager> {
+        cfg_if::cfg_if! {
+            if #[cfg(target_os = "windows")] {
+                use crate::network_manager::{WindowsNetworkManager, WlanApiImpl};
+
+                let manager = WindowsNetworkManager::<WlanApiImpl>::new();
+                match (self, interface) {
+                    (Backend::Auto | Backend::Wlan, None) => Box::new(manager),
+                    (Backend::Auto | Backend::Wlan, Some(selector)) => {
+                        Box::new(manager.with_interface(selector.clone()))
+                    }
+                }
+            } else {
+                use crate::network_manager::{
+                    DbusNetworkManager, WpaSupplicantInterfaces, WpaSupplicantNetworkManager,
+                };
+
+                match (self, interface) {
+                    (Backend::Auto | Backend::NetworkManager, None) => {
+                        Box::new(DbusNetworkManager::new())
+                    }
+                    (Backend::Auto | Backend::NetworkManager, Some(selector)) => {
+                        Box::new(DbusNetworkManager::new().with_interface(selector.clone()))
+                    }
+                    (Backend::WpaSupplicant, None) => {
+                        Box::new(WpaSupplicantNetworkManager::new(DEFAULT_INTERFACE))
+                    }
+                    (Backend::WpaSupplicant, Some(selector)) => match selector.single_name() {
+                        Some(name) => Box::new(WpaSupplicantNetworkManager::new(name)),
+                        None => Box::new(WpaSupplicantInterfaces::new(selector.clone())),
+                    },
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use tracing::{info, warn};
+use zbus::DBusError;
+use zbus::blocking::Connection;
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath};
+
+use crate::adapter::{Adapter, InterfaceSelector};
+use crate::error::{Error, Result, Step};
+use crate::network_manager::NetworkManager;
+
+/// Connection type reported by NetworkManager for Wi-Fi connections.
+const WIRELESS_CONNECTION_TYPE: &str = "802-11-wireless";
+
+/// Proxy for the root `org.freedesktop.NetworkManager` object.
+#[zbus::proxy(
+    interface = "org.freedesktop.NetworkManager",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager"
+)]
+trait NetworkManagerService {
+    /// Activates a connection profile on the given device.
+    fn activate_connection(
+        &self,
+        connection: &ObjectPath<'_>,
+        device: &ObjectPath<'_>,
+        specific_object: &ObjectPath<'_>,
+    ) -> zbus::Result<OwnedObjectPath>;
+
+    /// Deactivates an active connection.
+    fn deactivate_connection(&self, active_connection: &ObjectPath<'_>) -> zbus::Result<()>;
+
+    /// Object paths of the currently active connections.
+    #[zbus(property)]
+    fn active_connections(&self) -> zbus::Result<Vec<OwnedObjectPath>>;
+}
+
+/// Proxy for an `org.freedesktop.NetworkManager.Connection.Active` object.
+#[zbus::proxy(
+    interface = "org.freedesktop.NetworkManager.Connection.Active",
+    default_service = "org.freedesktop.NetworkManager"
+)]
+trait ActiveConnection {
+    /// Name of the connection profile.
+    #[zbus(property)]
+    fn id(&self) -> zbus::Result<String>;
+
+    /// Type of the connection, such as `802-11-wireless`.
+    #[zbus(property, name = "Type")]
+    fn connection_type(&self) -> zbus::Result<String>;
+
+    /// Object path of the connection profile this active connection was created from.
+    #[zbus(property)]
+    fn connection(&self) -> zbus::Result<OwnedObjectPath>;
+
+    /// Object paths of the devices this connection is active on.
+    #[zbus(property)]
+    fn devices(&self) -> zbus::Result<Vec<OwnedObjectPath>>;
+}
+
+/// Proxy for an `org.freedesktop.NetworkManager.Device` object.
+#[zbus::proxy(
+    interface = "org.freedesktop.NetworkManager.Device",
+    default_service = "org.freedesktop.NetworkManager"
+)]
+trait Device {
+    /// Name of the kernel interface, such as `wlan0`.
+    #[zbus(property, name = "Interface")]
+    fn interface_name(&self) -> zbus::Result<String>;
+
+    /// Hardware (MAC) address of the device.
+    #[zbus(property)]
+    fn hw_address(&self) -> zbus::Result<String>;
+
+    /// Name of the driver of the device.
+    #[zbus(property)]
+    fn driver(&self) -> zbus::Result<String>;
+}
+
+/// An active Wi-Fi connection found by [`DbusNetworkManager`].
+struct WirelessConnection {
+    active: OwnedObjectPath,
+    profile: OwnedObjectPath,
+    device: OwnedObjectPath,
+    id: String,
+}
+
+/// Implementation of [`NetworkManager`] for Linux machines running NetworkManager.
+///
+/// Reconnecting finds the active Wi-Fi connection over D-Bus, deactivates it, then re-activates
+/// the same connection profile on the same device. With an [`InterfaceSelector`], only the
+/// connections active on the selected devices are reconnected.
+#[derive(Default)]
+pub struct DbusNetworkManager {
+    connection: Option<Connection>,
+    interface: Option<InterfaceSelector>,
+}
+
+impl DbusNetworkManager {
+    /// Creates a new instance of [`DbusNetworkManager`] which connects to the system bus on each reconnect.
+    pub fn new() -> Self {
+        Self {
+            connection: None,
+            interface: None,
+        }
+    }
+
+    /// Creates a new instance of [`DbusNetworkManager`] which uses an existing bus connection.
+    ///
+    /// # Arguments
+    /// - `connection`: A connection to the bus the NetworkManager service is available on.
+    pub fn with_connection(connection: Connection) -> Self {
+        Self {
+            connection: Some(connection),
+            interface: None,
+        }
+    }
+
+    /// Reconnects the connections active on the devices chosen by `selector`, instead of the first
+    /// active Wi-Fi connection.
+    pub fn with_interface(mut self, selector: InterfaceSelector) -> Self {
+        self.interface = Some(selector);
+        self
+    }
+
+    fn open_connection(&self) -> Result<Connection> {
+        match &self.connection {
+            Some(connection) => Ok(connection.clone()),
+            None => Connection::system().map_err(|e| dbus_error(Step::Open, e)),
+        }
+    }
+
+    fn find_wireless_connections(
+        &self,
+        connection: &Connection,
+        service: &NetworkManagerServiceProxyBlocking,
+    ) -> Result<Vec<WirelessConnection>> {
+        let active_connections = service
+            .active_connections()
+            .map_err(|e| dbus_error(Step::FindInterface, e))?;
+
+        let mut wireless = active_connections.into_iter().filter_map(|active| {
+            let proxy = ActiveConnectionProxyBlocking::builder(connection)
+                .path(active.clone())
+                .ok()?
+                .cache_properties(CacheProperties::No)
+                .build()
+                .ok()?;
+
+            if proxy.connection_type().ok()? != WIRELESS_CONNECTION_TYPE {
+                return None;
+            }
+
+            Some(WirelessConnection {
+                profile: proxy.connection().ok()?,
+                device: proxy.devices().ok()?.into_iter().next()?,
+                id: proxy.id().unwrap_or_default(),
+                active,
+            })
+        });
+
+        let selected: Vec<WirelessConnection> = match &self.interface {
+            None => wireless.next().into_iter().collect(),
+            Some(selector) => {
+                let candidates: Vec<(WirelessConnection, Adapter)> = wireless
+                    .filter_map(|wireless| {
+                        let adapter = Self::device_adapter(connection, &wireless.device)?;
+                        Some((wireless, adapter))
+                    })
+                    .collect();
+                let selected = selector.select(candidates.iter().map(|(_, adapter)| adapter));
+                let keep: Vec<bool> = candidates
+                    .iter()
+                    .map(|(_, adapter)| selected.iter().any(|s| std::ptr::eq(*s, adapter)))
+                    .collect();
+
+                candidates
+                    .into_iter()
+                    .zip(keep)
+                    .filter_map(|((wireless, _), keep)| keep.then_some(wireless))
+                    .collect()
+            }
+        };
+
+        match selected.is_empty() {
+            true => Err(Error::NotConnected),
+            false => Ok(selected),
+        }
+    }
+
+    /// Describes the device at `path` as an [`Adapter`], so it can be matched by a selector.
+    fn device_adapter(connection: &Connection, path: &OwnedObjectPath) -> Option<Adapter> {
+        let device = DeviceProxyBlocking::builder(connection)
+            .path(path.clone())
+            .ok()?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .ok()?;
+
+        Some(Adapter {
+            name: device.interface_name().ok()?,
+            description: device.driver().ok().filter(|driver| !driver.is_empty()),
+            mac: device
+                .hw_address()
+                .ok()
+                .filter(|mac| !mac.is_empty())
+                .map(|mac| mac.to_ascii_lowercase()),
+            guid: None,
+            up: true,
+            wireless: true,
+        })
+    }
+
+    fn disconnect(
+        service: &NetworkManagerServiceProxyBlocking,
+        wireless: &WirelessConnection,
+    ) -> Result<()> {
+        service
+            .deactivate_connection(&wireless.active)
+            .map_err(|e| dbus_error(Step::Disconnect, e))?;
+        info!(
+            profile = %wireless.id,
+            device = %wireless.device.as_str(),
+            "Disconnected from Wi-Fi connection"
+        );
+        Ok(())
+    }
+
+    fn connect(
+        service: &NetworkManagerServiceProxyBlocking,
+        wireless: &WirelessConnection,
+    ) -> Result<()> {
+        let specific_object = ObjectPath::from_static_str_unchecked("/");
+
+        service
+            .activate_connection(&wireless.profile, &wireless.device, &specific_object)
+            .map_err(|e| dbus_error(Step::Connect, e))?;
+        info!(
+            profile = %wireless.id,
+            device = %wireless.device.as_str(),
+            "Reconnect initiated"
+        );
+        Ok(())
+    }
+}
+
+impl NetworkManager for DbusNetworkManager {
+    fn reconnect(&self) -> Result<()> {
+        let connection = self.open_connection()?;
+        let service = NetworkManagerServiceProxyBlocking::builder(&connection)
+            .cache_properties(CacheProperties::No)
+            .build()
+            .map_err(|e| dbus_error(Step::Open, e))?;
+
+        // Every selected connection is reconnected, even if an earlier one fails.
+        let results: Vec<Result<()>> = self
+            .find_wireless_connections(&connection, &service)?
+            .iter()
+            .map(|wireless| {
+                if let Err(e) = Self::disconnect(&service, wireless) {
+                    warn!(error = %e, "Disconnect failed, continuing to reconnect");
+                }
+                Self::connect(&service, wireless)
+            })
+            .collect();
+        results.into_iter().collect()
+    }
+}
+
+/// Converts a D-Bus error into an [`Error`], keeping the D-Bus error name as the backend error code.
+fn dbus_error(step: Step, error: zbus::Error) -> Error {
+    match error {
+        zbus::Error::MethodError(name, message, _) => Error::Backend {
+            step,
+            message: message.unwrap_or_else(|| name.to_string()),
+            code: Some(name.to_string()),
+        },
+        zbus::Error::FDO(e) => Error::Backend {
+            step,
+            code: Some(e.name().to_string()),
+            message: e.description().unwrap_or_default().to_string(),
+        },
+        zbus::Error::InputOutput(e) => Error::io(step, &e),
+        e => Error::Backend {
+            step,
+            code: None,
+            message: e.to_string(),
+        },
+    }
+}
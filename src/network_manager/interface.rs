@@ -1,5 +1,16 @@
+use crate::error::Result;
+
 /// Trait for managing network connections.
 pub trait NetworkManager {
     /// Attempts to reconnect to the network.
-    fn reconnect(&self) -> bool;
+    ///
+    /// Returns an [`crate::error::Error`] describing the step which failed if the reconnect could not be initiated.
+    fn reconnect(&self) -> Result<()>;
+}
+
+/// Boxed managers can be used wherever a [`NetworkManager`] is expected, so the backend can be chosen at runtime.
+impl<M: NetworkManager + ?Sized> NetworkManager for Box<M> {
+    fn reconnect(&self) -> Result<()> {
+        (**self).reconnect()
+    }
 }
@@ -1,9 +1,21 @@
 //! Module for managing network connections.
 
+mod backend;
 mod interface;
-mod windows;
 
+pub use backend::{Backend, DEFAULT_INTERFACE};
 pub use interface::NetworkManager;
-pub use windows::WindowsNetworkManager;
-pub use windows::WlanApi;
-pub use windows::WlanApiImpl;
+
+cfg_if::cfg_if! {
+    if #[cfg(target_os = "windows")] {
+        mod windows;
+        pub use windows::WindowsNetworkManager;
+        pub use windows::WlanApi;
+        pub use windows::WlanApiImpl;
+    } else if #[cfg(target_os = "linux")] {
+        mod dbus;
+        mod wpa_supplicant;
+        pub use dbus::DbusNetworkManager;
+        pub use wpa_supplicant::{WpaNetwork, WpaSupplicantInterfaces, WpaSupplicantNetworkManager};
+    }
+}
@@ -1,5 +1,9 @@
 use std::ffi::c_void;
 
+use tracing::{debug, info, warn};
+
+use crate::adapter::{self, Adapter, InterfaceSelector};
+use crate::error::{Error, Result, Step};
 use crate::network_manager::NetworkManager;
 use windows::Win32::Foundation::{ERROR_SUCCESS, HANDLE};
 use windows::Win32::NetworkManagement::WiFi::*;
@@ -161,7 +165,12 @@ impl WlanApi for WlanApiImpl {
 /// Implementation of [`NetworkManager`] for Windows OS.
 ///
 /// `Api`: A type that implements the [`WlanApi`] trait.
+///
+/// Without an [`InterfaceSelector`], the first wireless interface is reconnected. With one, the
+/// wireless interfaces it selects are reconnected, matched by their GUID and description and by
+/// the name and MAC address of the corresponding adapter.
 pub struct WindowsNetworkManager<Api: WlanApi> {
+    interface: Option<InterfaceSelector>,
     _marker: std::marker::PhantomData<Api>,
 }
 
@@ -176,58 +185,66 @@ impl<A: WlanApi> WindowsNetworkManager<A> {
     /// Creates a new instance of [`WindowsNetworkManager`]`.
     pub fn new() -> Self {
         Self {
+            interface: None,
             _marker: std::marker::PhantomData,
         }
     }
+
+    /// Reconnects the wireless interfaces chosen by `selector`, instead of the first one.
+    pub fn with_interface(mut self, selector: InterfaceSelector) -> Self {
+        self.interface = Some(selector);
+        self
+    }
 }
 
 impl<Api: WlanApi> NetworkManager for WindowsNetworkManager<Api> {
-    fn reconnect(&self) -> bool {
-        Self::open_handle()
-            .and_then(|handle| Self::get_network_interface(handle).map(|guid| (handle, guid)))
-            .and_then(|(handle, guid)| {
-                let profile_name = Self::get_current_profile_name(handle, &guid)?;
-                Some((handle, guid, profile_name))
-            })
-            .map(|(handle, guid, profile)| {
-                Self::disconnect(handle, &guid);
-                (handle, guid, profile)
-            })
-            .and_then(|(handle, guid, profile)| {
-                let success = Self::connect(handle, &guid, &profile);
-                unsafe { Api::close_handle(handle, None) };
-                success
-            })
-            .is_some()
+    fn reconnect(&self) -> Result<()> {
+        let handle = Self::open_handle()?;
+
+        // Every selected interface is reconnected, even if an earlier one fails.
+        let result = self.get_network_interfaces(handle).and_then(|guids| {
+            let results: Vec<Result<()>> = guids
+                .iter()
+                .map(|guid| Self::reconnect_interface(handle, guid))
+                .collect();
+            results.into_iter().collect()
+        });
+
+        unsafe { Api::close_handle(handle, None) };
+        result
     }
 }
 impl<Api: WlanApi> WindowsNetworkManager<Api> {
-    fn open_handle() -> Option<HANDLE> {
+    fn open_handle() -> Result<HANDLE> {
         let mut client_handle = HANDLE(std::ptr::null_mut());
         let mut negotiated_version = 0u32;
 
         let result =
             unsafe { Api::open_handle(2, None, &mut negotiated_version, &mut client_handle) };
 
-        if result == ERROR_SUCCESS.0 {
-            Some(client_handle)
-        } else {
-            println!("Failed to open WLAN handle: {}", result);
-            None
+        check(Step::Open, result).map(|()| client_handle)
+    }
+
+    fn reconnect_interface(handle: HANDLE, guid: &GUID) -> Result<()> {
+        let profile = Self::get_current_profile_name(handle, guid)?;
+        info!(interface = ?guid, profile = %profile, "Reconnecting");
+
+        if let Err(e) = Self::disconnect(handle, guid) {
+            warn!(interface = ?guid, error = %e, "Disconnect failed, continuing to reconnect");
         }
+        Self::connect(handle, guid, &profile)
     }
 
-    fn get_network_interface(handle: HANDLE) -> Option<GUID> {
-        Self::enum_interfaces(handle).and_then(|iface_list_ptr: *mut WLAN_INTERFACE_INFO_LIST| {
-            let guid = Self::first_interface_guid(iface_list_ptr);
+    fn get_network_interfaces(&self, handle: HANDLE) -> Result<Vec<GUID>> {
+        let iface_list_ptr = Self::enum_interfaces(handle)?;
+        let guids = self.selected_interface_guids(iface_list_ptr);
 
-            unsafe { Api::free_memory(iface_list_ptr as _) };
+        unsafe { Api::free_memory(iface_list_ptr as _) };
 
-            guid
-        })
+        guids
     }
 
-    fn get_current_profile_name(handle: HANDLE, iface: &GUID) -> Option<String> {
+    fn get_current_profile_name(handle: HANDLE, iface: &GUID) -> Result<String> {
         let mut data_ptr: *mut std::ffi::c_void = std::ptr::null_mut();
         let mut data_size: u32 = 0;
         let mut opcode: WLAN_OPCODE_VALUE_TYPE = WLAN_OPCODE_VALUE_TYPE(0);
@@ -244,8 +261,9 @@ impl<Api: WlanApi> WindowsNetworkManager<Api> {
             )
         };
 
-        if result != ERROR_SUCCESS.0 || data_ptr.is_null() {
-            return None;
+        check(Step::QueryConnection, result)?;
+        if data_ptr.is_null() {
+            return Err(Error::NotConnected);
         }
 
         let conn_info = unsafe { &*(data_ptr as *const WLAN_CONNECTION_ATTRIBUTES) };
@@ -255,21 +273,18 @@ impl<Api: WlanApi> WindowsNetworkManager<Api> {
 
         unsafe { Api::free_memory(data_ptr as *mut _) };
 
-        Some(profile_name)
+        Ok(profile_name)
     }
 
-    fn disconnect(client_handle: HANDLE, iface: &GUID) -> Option<()> {
+    fn disconnect(client_handle: HANDLE, iface: &GUID) -> Result<()> {
         let result = unsafe { Api::disconnect(client_handle, iface, None) };
 
-        if result == ERROR_SUCCESS.0 {
-            println!("Disconnected from current Wi-Fi network");
-            Some(())
-        } else {
-            println!("WlanDisconnect failed: {result}");
-            None
-        }
+        check(Step::Disconnect, result)?;
+        info!(interface = ?iface, "Disconnected from current Wi-Fi network");
+        Ok(())
     }
-    fn connect(handle: HANDLE, iface: &GUID, profile: &str) -> Option<()> {
+
+    fn connect(handle: HANDLE, iface: &GUID, profile: &str) -> Result<()> {
         let utf16: Vec<u16> = profile.encode_utf16().chain(Some(0)).collect();
         let profile_pcwstr = PCWSTR(utf16.as_ptr());
 
@@ -289,35 +304,111 @@ impl<Api: WlanApi> WindowsNetworkManager<Api> {
             )
         };
 
-        if result == ERROR_SUCCESS.0 {
-            println!("Reconnect initiated");
-            Some(())
-        } else {
-            println!("Reconnect failed (error code {result})");
-            None
-        }
+        check(Step::Connect, result)?;
+        info!(interface = ?iface, profile = %profile, "Reconnect initiated");
+        Ok(())
     }
 
-    fn enum_interfaces(client_handle: HANDLE) -> Option<*mut WLAN_INTERFACE_INFO_LIST> {
+    fn enum_interfaces(client_handle: HANDLE) -> Result<*mut WLAN_INTERFACE_INFO_LIST> {
         let mut iface_list_ptr: *mut WLAN_INTERFACE_INFO_LIST = std::ptr::null_mut();
 
         let result = unsafe { Api::enum_interfaces(client_handle, None, &mut iface_list_ptr) };
 
-        if result == ERROR_SUCCESS.0 && !iface_list_ptr.is_null() {
-            Some(iface_list_ptr)
-        } else {
-            println!("Failed to enumerate WLAN interfaces: {}", result);
-            None
+        check(Step::FindInterface, result)?;
+        if iface_list_ptr.is_null() {
+            return Err(Error::NoInterface);
         }
+        Ok(iface_list_ptr)
     }
 
-    fn first_interface_guid(iface_list_ptr: *mut WLAN_INTERFACE_INFO_LIST) -> Option<GUID> {
+    fn selected_interface_guids(
+        &self,
+        iface_list_ptr: *mut WLAN_INTERFACE_INFO_LIST,
+    ) -> Result<Vec<GUID>> {
         let iface_list = unsafe { &*iface_list_ptr };
-        if iface_list.dwNumberOfItems == 0 {
-            println!("No WLAN interfaces found");
-            None
+        let infos = unsafe {
+            std::slice::from_raw_parts(
+                iface_list.InterfaceInfo.as_ptr(),
+                iface_list.dwNumberOfItems as usize,
+            )
+        };
+
+        let guids: Vec<GUID> = match &self.interface {
+            None => infos
+                .iter()
+                .take(1)
+                .map(|info| info.InterfaceGuid)
+                .collect(),
+            Some(selector) => {
+                let known = adapter::adapters().unwrap_or_default();
+                let candidates: Vec<(GUID, Adapter)> = infos
+                    .iter()
+                    .map(|info| (info.InterfaceGuid, wlan_adapter(info, &known)))
+                    .collect();
+                let selected = selector.select(candidates.iter().map(|(_, adapter)| adapter));
+
+                candidates
+                    .iter()
+                    .filter(|(_, adapter)| selected.iter().any(|s| std::ptr::eq(*s, adapter)))
+                    .map(|(guid, _)| *guid)
+                    .collect()
+            }
+        };
+
+        if guids.is_empty() {
+            Err(Error::NoInterface)
         } else {
-            Some(iface_list.InterfaceInfo[0].InterfaceGuid)
+            Ok(guids)
         }
     }
 }
+
+/// Returns the [`Adapter`] describing a WLAN interface, taken from `known` adapters where its GUID
+/// is listed, so the selector can match it by name and MAC address too.
+fn wlan_adapter(info: &WLAN_INTERFACE_INFO, known: &[Adapter]) -> Adapter {
+    let guid = guid_string(&info.InterfaceGuid);
+    let description = String::from_utf16_lossy(&info.strInterfaceDescription)
+        .trim_end_matches('\0')
+        .to_string();
+
+    known
+        .iter()
+        .find(|adapter| {
+            adapter.guid.as_deref().is_some_and(|candidate| {
+                candidate
+                    .trim_matches(|c| c == '{' || c == '}')
+                    .eq_ignore_ascii_case(&guid)
+            })
+        })
+        .cloned()
+        .unwrap_or(Adapter {
+            name: description.clone(),
+            description: Some(description),
+            mac: None,
+            guid: Some(guid),
+            up: false,
+            wireless: true,
+        })
+}
+
+/// Formats a GUID as lowercase hex groups, such as `01234567-89ab-cdef-0123-456789abcdef`.
+fn guid_string(guid: &GUID) -> String {
+    let d = guid.data4;
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+        guid.data1, guid.data2, guid.data3, d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7]
+    )
+}
+
+/// Converts a WLAN API result code into a [`Result`].
+fn check(step: Step, result: u32) -> Result<()> {
+    if result == ERROR_SUCCESS.0 {
+        Ok(())
+    } else {
+        debug!(%step, code = result, "WLAN API call failed");
+        Err(Error::Os {
+            step,
+            code: result as i32,
+        })
+    }
+}
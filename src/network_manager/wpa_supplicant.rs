@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::io;
+use std::os::unix::net::UnixDatagram;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tracing::{info, warn};
+
+use crate::adapter::{InterfaceSelector, adapters_under};
+use crate::error::{Error, Result, Step};
+use crate::network_manager::NetworkManager;
+
+/// Directory wpa_supplicant creates its per-interface control sockets in.
+const CONTROL_DIRECTORY: &str = "/var/run/wpa_supplicant";
+
+/// Default time to wait for a reply from wpa_supplicant.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest reply wpa_supplicant sends over the control interface.
+const MAX_REPLY_SIZE: usize = 4096;
+
+/// Counter used to give each client socket a unique path.
+static CLIENT_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// The network wpa_supplicant is currently associated with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WpaNetwork {
+    /// Identifier of the network block in the wpa_supplicant configuration.
+    pub id: u32,
+
+    /// SSID of the network.
+    pub ssid: String,
+}
+
+/// Implementation of [`NetworkManager`] for machines managed directly by wpa_supplicant.
+///
+/// Commands are sent over the wpa_supplicant control interface, a Unix datagram socket per
+/// wireless interface. Reconnecting disconnects from the current network, then asks
+/// wpa_supplicant to reconnect.
+pub struct WpaSupplicantNetworkManager {
+    socket_path: PathBuf,
+    timeout: Duration,
+}
+
+impl WpaSupplicantNetworkManager {
+    /// Creates a new instance of [`WpaSupplicantNetworkManager`] for a wireless interface.
+    ///
+    /// # Arguments
+    /// - `interface`: Name of the interface, such as `wlan0`.
+    pub fn new(interface: &str) -> Self {
+        Self::with_socket_path(Path::new(CONTROL_DIRECTORY).join(interface))
+    }
+
+    /// Creates a new instance of [`WpaSupplicantNetworkManager`] using an explicit control socket.
+    ///
+    /// # Arguments
+    /// - `socket_path`: Path of the wpa_supplicant control socket.
+    pub fn with_socket_path(socket_path: impl Into<PathBuf>) -> Self {
+        Self {
+            socket_path: socket_path.into(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Sets the time to wait for each reply from wpa_supplicant.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sends a raw control command and returns the reply.
+    ///
+    /// # Arguments
+    /// - `command`: The command to send, such as `STATUS` or `SELECT_NETWORK 0`.
+    pub fn request(&self, command: &str) -> io::Result<String> {
+        let client = ClientSocket::bind()?;
+        client.socket.set_read_timeout(Some(self.timeout))?;
+        client.socket.connect(&self.socket_path)?;
+        client.socket.send(command.as_bytes())?;
+
+        let mut buffer = [0u8; MAX_REPLY_SIZE];
+        loop {
+            let len = client.socket.recv(&mut buffer)?;
+            let reply = String::from_utf8_lossy(&buffer[..len]).into_owned();
+
+            // Unsolicited event messages are prefixed with their priority, e.g. `<3>`.
+            if !reply.starts_with('<') {
+                return Ok(reply);
+            }
+        }
+    }
+
+    /// Returns the key/value pairs reported by the `STATUS` command.
+    pub fn status(&self) -> io::Result<HashMap<String, String>> {
+        Ok(self
+            .request("STATUS")?
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect())
+    }
+
+    /// Returns the network wpa_supplicant is currently associated with, if any.
+    pub fn current_network(&self) -> io::Result<Option<WpaNetwork>> {
+        let status = self.status()?;
+
+        Ok(status
+            .get("id")
+            .and_then(|id| id.parse().ok())
+            .map(|id| WpaNetwork {
+                id,
+                ssid: status.get("ssid").cloned().unwrap_or_default(),
+            }))
+    }
+
+    /// Selects the network with the given identifier, disabling all others.
+    pub fn select_network(&self, id: u32) -> Result<()> {
+        self.command(Step::Connect, &format!("SELECT_NETWORK {id}"))
+    }
+
+    /// Sends a command which is expected to reply with `OK`.
+    fn command(&self, step: Step, command: &str) -> Result<()> {
+        let reply = self.request(command).map_err(|e| Error::io(step, &e))?;
+
+        match reply.trim() {
+            "OK" => Ok(()),
+            reply => Err(Error::Backend {
+                step,
+                code: Some(reply.to_string()),
+                message: format!("{command} returned {reply}"),
+            }),
+        }
+    }
+
+    fn disconnect(&self) -> Result<()> {
+        self.command(Step::Disconnect, "DISCONNECT")?;
+        info!(socket = %self.socket_path.display(), "Disconnected from current Wi-Fi network");
+        Ok(())
+    }
+
+    fn connect(&self) -> Result<()> {
+        self.command(Step::Connect, "RECONNECT")?;
+        info!(socket = %self.socket_path.display(), "Reconnect initiated");
+        Ok(())
+    }
+}
+
+impl NetworkManager for WpaSupplicantNetworkManager {
+    fn reconnect(&self) -> Result<()> {
+        match self
+            .current_network()
+            .map_err(|e| Error::io(Step::QueryConnection, &e))?
+        {
+            Some(network) => {
+                info!(network_id = network.id, ssid = %network.ssid, "Reconnecting")
+            }
+            None => info!("Not associated with a network, reconnecting"),
+        }
+
+        if let Err(e) = self.disconnect() {
+            warn!(error = %e, "Disconnect failed, continuing to reconnect");
+        }
+        self.connect()
+    }
+}
+
+/// Implementation of [`NetworkManager`] which reconnects the wireless interfaces chosen by an
+/// [`InterfaceSelector`], each through its own wpa_supplicant control socket.
+///
+/// The interfaces are listed from `/sys/class/net` on each reconnect, so adapters plugged in after
+/// startup are found.
+pub struct WpaSupplicantInterfaces {
+    selector: InterfaceSelector,
+    control_directory: PathBuf,
+    root: PathBuf,
+    timeout: Duration,
+}
+
+impl WpaSupplicantInterfaces {
+    /// Creates a new instance of [`WpaSupplicantInterfaces`] for the interfaces chosen by `selector`.
+    pub fn new(selector: InterfaceSelector) -> Self {
+        Self {
+            selector,
+            control_directory: PathBuf::from(CONTROL_DIRECTORY),
+            root: PathBuf::from("/"),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Sets the directory containing the per-interface control sockets.
+    pub fn with_control_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.control_directory = directory.into();
+        self
+    }
+
+    /// Sets the directory containing the `sys/class/net` entries the interfaces are listed from.
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = root.into();
+        self
+    }
+
+    /// Sets the time to wait for each reply from wpa_supplicant.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the names of the selected wireless interfaces.
+    pub fn interfaces(&self) -> Result<Vec<String>> {
+        let adapters =
+            adapters_under(&self.root).map_err(|e| Error::io(Step::FindInterface, &e))?;
+        let names: Vec<String> = self
+            .selector
+            .select(adapters.iter().filter(|adapter| adapter.wireless))
+            .into_iter()
+            .map(|adapter| adapter.name.clone())
+            .collect();
+
+        match names.is_empty() {
+            true => Err(Error::NoInterface),
+            false => Ok(names),
+        }
+    }
+}
+
+impl NetworkManager for WpaSupplicantInterfaces {
+    fn reconnect(&self) -> Result<()> {
+        // Every selected interface is reconnected, even if an earlier one fails.
+        let results: Vec<Result<()>> = self
+            .interfaces()?
+            .iter()
+            .map(|interface| {
+                info!(interface = %interface, "Reconnecting interface");
+                WpaSupplicantNetworkManager::with_socket_path(
+                    self.control_directory.join(interface),
+                )
+                .with_timeout(self.timeout)
+                .reconnect()
+            })
+            .collect();
+        results.into_iter().collect()
+    }
+}
+
+/// A client socket bound to a unique temporary path, removed when dropped.
+///
+/// wpa_supplicant replies to the address of the sender, so the client socket must be bound.
+struct ClientSocket {
+    socket: UnixDatagram,
+    path: PathBuf,
+}
+
+impl ClientSocket {
+    fn bind() -> io::Result<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "internet_reloader_wpa_{}_{}",
+            std::process::id(),
+            CLIENT_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        Ok(Self {
+            socket: UnixDatagram::bind(&path)?,
+            path,
+        })
+    }
+}
+
+impl Drop for ClientSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
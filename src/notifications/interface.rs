@@ -0,0 +1,14 @@
+use crate::notifications::{DeliveryError, Notification};
+
+/// Trait for delivering [`Notification`]s to another service.
+pub trait NotificationSink {
+    /// Attempts to deliver `notification` once, without retrying.
+    fn send(&self, notification: &Notification) -> Result<(), DeliveryError>;
+}
+
+/// Boxed sinks can be used wherever a [`NotificationSink`] is expected, so they can be chosen at runtime.
+impl<S: NotificationSink + ?Sized> NotificationSink for Box<S> {
+    fn send(&self, notification: &Notification) -> Result<(), DeliveryError> {
+        (**self).send(notification)
+    }
+}
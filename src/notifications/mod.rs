@@ -0,0 +1,149 @@
+//! Module for notifying other services when the internet goes down, is remediated and returns.
+//!
+//! This module defines the [`Notification`]s sent, the [`NotificationSink`] trait which delivers
+//! them, and the [`WebhookSink`] which posts them to a URL. A [`Notifier`] subscribes to the
+//! [`crate::events::NetworkEvent`]s of [`crate::app::NetworkApp`], turns them into notifications,
+//! and retries or queues those which cannot be delivered until the internet returns.
+
+mod interface;
+mod notifier;
+mod queue;
+mod webhook;
+
+pub use interface::NotificationSink;
+pub use notifier::{Notifier, RetryPolicy};
+pub use queue::NotificationQueue;
+pub use webhook::{WebhookFormat, WebhookSink};
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// The occurrence a [`Notification`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// The internet became unavailable.
+    OutageStarted,
+
+    /// A rung of the remediation ladder was attempted during an outage.
+    RemediationAttempted,
+
+    /// The internet became available again.
+    Recovered,
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`NotificationKind`] to enable easy printing.
+impl std::fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            NotificationKind::OutageStarted => write!(f, "outage started"),
+            NotificationKind::RemediationAttempted => write!(f, "remediation attempted"),
+            NotificationKind::Recovered => write!(f, "recovered"),
+        }
+    }
+}
+
+/// A notification sent to a [`NotificationSink`].
+///
+/// This is also the JSON payload posted by a [`WebhookSink`] using [`WebhookFormat::Json`], with
+/// an additional `message` field holding the [`std::fmt::Display`] text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notification {
+    /// The occurrence reported.
+    pub event: NotificationKind,
+
+    /// The network status at the time, such as `NetworkOnly` or `Connected`.
+    pub status: String,
+
+    /// The managed interface, if one is configured.
+    pub interface: Option<String>,
+
+    /// Time of the occurrence, in seconds since the Unix epoch.
+    pub timestamp: u64,
+
+    /// Seconds since the outage started, for remediations and recoveries.
+    pub duration_secs: Option<u64>,
+
+    /// The remediation attempted, for remediations.
+    pub action: Option<String>,
+
+    /// The error the remediation failed with, if it failed.
+    pub error: Option<String>,
+}
+
+impl Notification {
+    /// Returns how long the outage had lasted, for remediations and recoveries.
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration_secs.map(Duration::from_secs)
+    }
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`Notification`] to enable easy printing.
+impl std::fmt::Display for Notification {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let on = match &self.interface {
+            Some(interface) => format!(" on {interface}"),
+            None => String::new(),
+        };
+        let after = match self.duration() {
+            Some(duration) => format!(" after {duration:?}"),
+            None => String::new(),
+        };
+        let action = self.action.as_deref().unwrap_or("remediation");
+
+        match (self.event, &self.error) {
+            (NotificationKind::OutageStarted, _) => {
+                write!(f, "Internet outage{on}, status is {}", self.status)
+            }
+            (NotificationKind::RemediationAttempted, Some(error)) => {
+                write!(f, "{action}{on} failed{after}: {error}")
+            }
+            (NotificationKind::RemediationAttempted, None) => write!(
+                f,
+                "{action}{on} succeeded{after}, status is {}",
+                self.status
+            ),
+            (NotificationKind::Recovered, _) => {
+                write!(f, "Internet restored{on}{after}, status is {}", self.status)
+            }
+        }
+    }
+}
+
+/// Errors returned when a [`NotificationSink`] fails to deliver a [`Notification`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryError {
+    /// The receiver responded with an unsuccessful HTTP status.
+    Status(u16),
+
+    /// The notification could not be sent, such as because the internet is unavailable.
+    Failed(String),
+}
+
+impl DeliveryError {
+    /// Returns `true` if delivering the same notification again may succeed.
+    ///
+    /// Client errors other than `408 Request Timeout` and `429 Too Many Requests` are not retried,
+    /// as the receiver rejected the notification itself.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            DeliveryError::Status(status) => {
+                !(400..500).contains(status) || [408, 429].contains(status)
+            }
+            DeliveryError::Failed(_) => true,
+        }
+    }
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`DeliveryError`] to enable easy printing.
+impl std::fmt::Display for DeliveryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DeliveryError::Status(status) => write!(f, "receiver responded with status {status}"),
+            DeliveryError::Failed(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for DeliveryError {}
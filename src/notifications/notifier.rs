@@ -0,0 +1,251 @@
+use std::cell::{Cell, RefCell};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tracing::{debug, warn};
+
+use crate::app::NetworkStatus;
+use crate::events::{EventSubscriber, NetworkEvent};
+use crate::notifications::{
+    DeliveryError, Notification, NotificationKind, NotificationQueue, NotificationSink,
+};
+
+/// Settings for retrying a notification which could not be delivered.
+///
+/// The delay before each retry doubles, starting from `backoff`, up to `max_backoff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of times delivery is attempted before the notification is left queued.
+    pub attempts: u32,
+
+    /// Delay before the first retry.
+    pub backoff: Duration,
+
+    /// Longest delay between retries.
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Returns the delay before retry `retry`, where `0` is the first retry.
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Implementation of [`EventSubscriber`] which sends a [`Notification`] to a [`NotificationSink`]
+/// when an outage starts, a remediation is attempted, and the internet recovers.
+///
+/// An outage starts when the status becomes [`NetworkStatus::NetworkOnly`],
+/// [`NetworkStatus::Disconnected`] or [`NetworkStatus::CaptivePortal`], and ends when it becomes
+/// [`NetworkStatus::Connected`] or [`NetworkStatus::Degraded`].
+///
+/// Every notification is added to a [`NotificationQueue`], then the queue is delivered oldest
+/// first, retrying as described by the [`RetryPolicy`]. A notification which still cannot be
+/// delivered stays queued, along with every later one, until the next notification is sent or
+/// the status next becomes healthy, so notifications queued during an outage, or saved by a
+/// previous run, are delivered once the internet is back. Notifications rejected by the receiver
+/// are dropped.
+///
+/// Delivery blocks while retrying, so the notifier should be moved to another thread with
+/// [`crate::events::ChannelSubscriber::spawn`].
+pub struct Notifier<S: NotificationSink> {
+    sink: S,
+    interface: Option<String>,
+    retry: RetryPolicy,
+    queue: RefCell<NotificationQueue>,
+    status: RefCell<Option<NetworkStatus>>,
+    outage: Cell<Option<Instant>>,
+}
+
+impl<S: NotificationSink> Notifier<S> {
+    /// Creates a new instance of [`Notifier`] delivering to `sink`, queuing in memory.
+    pub fn new(sink: S) -> Self {
+        Self {
+            sink,
+            interface: None,
+            retry: RetryPolicy::default(),
+            queue: RefCell::new(NotificationQueue::new()),
+            status: RefCell::new(None),
+            outage: Cell::new(None),
+        }
+    }
+
+    /// Sets the interface named in notifications.
+    pub fn with_interface(mut self, interface: impl Into<String>) -> Self {
+        self.interface = Some(interface.into());
+        self
+    }
+
+    /// Sets how delivery is retried.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets the [`NotificationQueue`] holding notifications until they are delivered, such as one
+    /// saved to a file with [`NotificationQueue::open`].
+    pub fn with_queue(mut self, queue: NotificationQueue) -> Self {
+        self.queue = RefCell::new(queue);
+        self
+    }
+
+    /// Returns the sink notifications are delivered to.
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// Returns the number of notifications waiting to be delivered.
+    pub fn queued(&self) -> usize {
+        self.queue.borrow().len()
+    }
+
+    /// Queues `notification`, then delivers the queue.
+    ///
+    /// Returns `true` if every queued notification was delivered or dropped.
+    pub fn notify(&self, notification: Notification) -> bool {
+        debug!(event = %notification.event, status = %notification.status, "Queuing notification");
+        self.queue.borrow_mut().push(notification);
+        self.flush()
+    }
+
+    /// Delivers queued notifications, oldest first, until one cannot be delivered.
+    ///
+    /// Returns `true` if every queued notification was delivered or dropped.
+    pub fn flush(&self) -> bool {
+        loop {
+            let Some(notification) = self.queue.borrow().front().cloned() else {
+                return true;
+            };
+
+            match self.deliver(&notification) {
+                Ok(()) => {
+                    debug!(event = %notification.event, "Notification delivered");
+                }
+                Err(e) if !e.is_retryable() => {
+                    warn!(
+                        event = %notification.event,
+                        error = %e,
+                        "Notification rejected, dropping"
+                    );
+                }
+                Err(e) => {
+                    warn!(
+                        event = %notification.event,
+                        error = %e,
+                        queued = self.queued(),
+                        "Failed to deliver notification, keeping queued"
+                    );
+                    return false;
+                }
+            }
+            self.queue.borrow_mut().pop();
+        }
+    }
+
+    /// Attempts to deliver `notification`, retrying retryable errors with backoff.
+    fn deliver(&self, notification: &Notification) -> Result<(), DeliveryError> {
+        let mut retry = 0;
+        loop {
+            match self.sink.send(notification) {
+                Ok(()) => return Ok(()),
+                Err(e) if !e.is_retryable() || retry + 1 >= self.retry.attempts => return Err(e),
+                Err(e) => {
+                    let delay = self.retry.delay(retry);
+                    debug!(
+                        error = %e,
+                        delay_ms = delay.as_millis() as u64,
+                        "Retrying notification"
+                    );
+                    thread::sleep(delay);
+                    retry += 1;
+                }
+            }
+        }
+    }
+
+    /// Returns the notification describing `event`, if it starts or ends an outage or reports a
+    /// remediation, tracking the status and when the outage started.
+    fn notification(&self, event: &NetworkEvent) -> Option<Notification> {
+        match event {
+            NetworkEvent::StatusChanged { to, .. } => {
+                self.status.replace(Some(to.clone()));
+                match (to.is_outage(), self.outage.get()) {
+                    (true, None) => {
+                        let notification = self.build(NotificationKind::OutageStarted, to);
+                        self.outage.set(Some(Instant::now()));
+                        Some(notification)
+                    }
+                    (false, Some(_)) => {
+                        let notification = self.build(NotificationKind::Recovered, to);
+                        self.outage.set(None);
+                        Some(notification)
+                    }
+                    _ => None,
+                }
+            }
+            NetworkEvent::RemediationResult {
+                attempt,
+                verification,
+            } => {
+                let status = match verification {
+                    Some(verification) if verification.recovered => NetworkStatus::Connected,
+                    _ => self
+                        .status
+                        .borrow()
+                        .clone()
+                        .unwrap_or(NetworkStatus::NetworkOnly),
+                };
+                let mut notification = self.build(NotificationKind::RemediationAttempted, &status);
+                notification.action = Some(attempt.action.clone());
+                notification.error = attempt.result.as_ref().err().map(ToString::to_string);
+                Some(notification)
+            }
+            _ => None,
+        }
+    }
+
+    /// Builds a notification of `event` at the current time, with the duration of the current
+    /// outage, if any.
+    fn build(&self, event: NotificationKind, status: &NetworkStatus) -> Notification {
+        Notification {
+            event,
+            status: status.to_string(),
+            interface: self.interface.clone(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            duration_secs: self.outage.get().map(|started| started.elapsed().as_secs()),
+            action: None,
+            error: None,
+        }
+    }
+}
+
+impl<S: NotificationSink> EventSubscriber for Notifier<S> {
+    fn on_event(&self, event: &NetworkEvent) {
+        match (self.notification(event), event) {
+            (Some(notification), _) => {
+                self.notify(notification);
+            }
+            (None, NetworkEvent::StatusChanged { to, .. })
+                if !to.is_outage() && self.queued() > 0 =>
+            {
+                self.flush();
+            }
+            _ => {}
+        }
+    }
+}
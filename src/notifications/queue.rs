@@ -0,0 +1,166 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use tracing::warn;
+
+use crate::notifications::Notification;
+
+/// Default largest number of notifications kept in a [`NotificationQueue`].
+const DEFAULT_CAPACITY: usize = 1000;
+
+/// Notifications waiting to be delivered, in the order they occurred.
+///
+/// A queue opened with [`NotificationQueue::open`] is saved to a file after every change, one JSON
+/// notification per line, so notifications which could not be delivered survive a restart. Once
+/// the queue is full, the oldest notification is dropped to make room for the newest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationQueue {
+    path: Option<PathBuf>,
+    entries: VecDeque<Notification>,
+    capacity: usize,
+}
+
+impl NotificationQueue {
+    /// Creates a new, empty [`NotificationQueue`] held only in memory.
+    pub fn new() -> Self {
+        Self {
+            path: None,
+            entries: VecDeque::new(),
+            capacity: DEFAULT_CAPACITY,
+        }
+    }
+
+    /// Opens the [`NotificationQueue`] saved at `path`, or an empty queue if the file does not exist.
+    ///
+    /// Lines which are not valid notifications are skipped.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let entries = match fs::read_to_string(&path) {
+            Ok(contents) => parse(&path, &contents),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => VecDeque::new(),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self {
+            path: Some(path),
+            entries,
+            capacity: DEFAULT_CAPACITY,
+        })
+    }
+
+    /// Sets the largest number of notifications kept, dropping the oldest if more are queued.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self.truncate();
+        self
+    }
+
+    /// Returns the file the queue is saved to, if any.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Returns the number of queued notifications.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no notifications are queued.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the oldest queued notification, if any.
+    pub fn front(&self) -> Option<&Notification> {
+        self.entries.front()
+    }
+
+    /// Returns the queued notifications, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Notification> {
+        self.entries.iter()
+    }
+
+    /// Adds `notification` to the back of the queue and saves it.
+    pub fn push(&mut self, notification: Notification) {
+        self.entries.push_back(notification);
+        self.truncate();
+        self.save();
+    }
+
+    /// Removes the oldest queued notification and saves the queue.
+    pub fn pop(&mut self) -> Option<Notification> {
+        let notification = self.entries.pop_front()?;
+        self.save();
+        Some(notification)
+    }
+
+    fn truncate(&mut self) {
+        while self.entries.len() > self.capacity {
+            if let Some(dropped) = self.entries.pop_front() {
+                warn!(
+                    event = %dropped.event,
+                    timestamp = dropped.timestamp,
+                    "Notification queue full, dropping oldest"
+                );
+            }
+        }
+    }
+
+    /// Saves the queue, logging rather than returning errors so delivery can continue from memory.
+    fn save(&self) {
+        let Some(path) = &self.path else { return };
+        if let Err(e) = self.write(path) {
+            warn!(path = %path.display(), error = %e, "Failed to save notification queue");
+        }
+    }
+
+    /// Writes the queue to a temporary file, then renames it over `path`.
+    fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        let temporary = PathBuf::from(temporary);
+
+        let mut file = fs::File::create(&temporary)?;
+        for notification in &self.entries {
+            serde_json::to_writer(&mut file, notification)?;
+            file.write_all(b"\n")?;
+        }
+        file.sync_all()?;
+        fs::rename(&temporary, path)
+    }
+}
+
+impl Default for NotificationQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse(path: &Path, contents: &str) -> VecDeque<Notification> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(i, line)| match serde_json::from_str(line) {
+            Ok(notification) => Some(notification),
+            Err(e) => {
+                warn!(
+                    path = %path.display(),
+                    line = i + 1,
+                    error = %e,
+                    "Skipping invalid queued notification"
+                );
+                None
+            }
+        })
+        .collect()
+}
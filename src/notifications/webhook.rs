@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use ureq::Agent;
+
+use crate::notifications::{DeliveryError, Notification, NotificationSink};
+
+/// Default time allowed for each request.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The body posted by a [`WebhookSink`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WebhookFormat {
+    /// The [`Notification`] as JSON, with an additional `message` field.
+    #[default]
+    Json,
+
+    /// A Slack incoming webhook message.
+    Slack,
+
+    /// A Discord webhook message.
+    Discord,
+
+    /// A Microsoft Teams incoming webhook message card.
+    Teams,
+}
+
+impl WebhookFormat {
+    /// Returns the JSON body describing `notification` in this format.
+    pub fn body(&self, notification: &Notification) -> String {
+        let message = notification.to_string();
+        let body = match self {
+            WebhookFormat::Json => {
+                let mut body = serde_json::to_value(notification).unwrap_or_default();
+                if let Value::Object(fields) = &mut body {
+                    fields.insert("message".to_string(), Value::String(message));
+                }
+                body
+            }
+            WebhookFormat::Slack => json!({ "text": message }),
+            WebhookFormat::Discord => json!({ "content": message }),
+            WebhookFormat::Teams => json!({
+                "@type": "MessageCard",
+                "@context": "https://schema.org/extensions",
+                "summary": message,
+                "title": format!("Internet {}", notification.event),
+                "text": message,
+            }),
+        };
+        body.to_string()
+    }
+}
+
+/// Implementation of [`NotificationSink`] which posts each notification as JSON to a URL.
+///
+/// The body is chosen by the [`WebhookFormat`], or by a template set with
+/// [`WebhookSink::with_template`]. A response with a `2xx` status is a successful delivery.
+pub struct WebhookSink {
+    url: String,
+    format: WebhookFormat,
+    template: Option<String>,
+    timeout: Duration,
+}
+
+impl WebhookSink {
+    /// Creates a new instance of [`WebhookSink`] posting [`WebhookFormat::Json`] bodies to `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            format: WebhookFormat::default(),
+            template: None,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Sets the [`WebhookFormat`] of the body.
+    pub fn with_format(mut self, format: WebhookFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets a template used for the body instead of the [`WebhookFormat`].
+    ///
+    /// The placeholders `{event}`, `{status}`, `{interface}`, `{timestamp}`, `{duration}`,
+    /// `{action}`, `{error}` and `{message}` are replaced by the fields of the notification,
+    /// escaped for use inside a JSON string. Fields without a value are replaced by nothing.
+    pub fn with_template(mut self, template: impl Into<String>) -> Self {
+        self.template = Some(template.into());
+        self
+    }
+
+    /// Sets the time allowed for each request, including connecting and reading the response.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the URL notifications are posted to.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the body posted for `notification`.
+    pub fn body(&self, notification: &Notification) -> String {
+        match &self.template {
+            Some(template) => render(template, notification),
+            None => self.format.body(notification),
+        }
+    }
+}
+
+impl NotificationSink for WebhookSink {
+    fn send(&self, notification: &Notification) -> Result<(), DeliveryError> {
+        let agent = Agent::config_builder()
+            .timeout_global(Some(self.timeout))
+            .http_status_as_error(false)
+            .build()
+            .new_agent();
+
+        let response = agent
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .send(self.body(notification))
+            .map_err(|e| DeliveryError::Failed(e.to_string()))?;
+
+        match response.status().as_u16() {
+            200..=299 => Ok(()),
+            status => Err(DeliveryError::Status(status)),
+        }
+    }
+}
+
+/// Replaces the placeholders of `template` with the JSON-escaped fields of `notification`, in a
+/// single pass so placeholders within the fields are kept.
+fn render(template: &str, notification: &Notification) -> String {
+    let fields = [
+        ("event", Some(notification.event.to_string())),
+        ("status", Some(notification.status.clone())),
+        ("interface", notification.interface.clone()),
+        ("timestamp", Some(notification.timestamp.to_string())),
+        (
+            "duration",
+            notification.duration().map(|d| format!("{d:?}")),
+        ),
+        ("action", notification.action.clone()),
+        ("error", notification.error.clone()),
+        ("message", Some(notification.to_string())),
+    ];
+
+    let mut body = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        body.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let placeholder = fields
+            .iter()
+            .find(|(name, _)| rest.starts_with(&format!("{{{name}}}")));
+        match placeholder {
+            Some((name, value)) => {
+                body.push_str(&escape(value.as_deref().unwrap_or("")));
+                rest = &rest[name.len() + 2..];
+            }
+            None => {
+                body.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    body.push_str(rest);
+    body
+}
+
+/// Escapes `value` for use inside a JSON string, without the surrounding quotes.
+fn escape(value: &str) -> String {
+    let quoted = Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
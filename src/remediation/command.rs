@@ -0,0 +1,140 @@
+use std::process::{Command, Stdio};
+
+use crate::error::{Error, Result, Step};
+use crate::remediation::RemediationAction;
+
+/// A [`RemediationAction`] which runs a sequence of external commands.
+///
+/// The action succeeds if every command exits successfully, and otherwise fails with the error of
+/// the first command which failed. Commands run in order, and the
+/// sequence stops at the first failure.
+pub struct CommandAction {
+    name: String,
+    commands: Vec<(String, Vec<String>)>,
+}
+
+impl CommandAction {
+    /// Creates a new [`CommandAction`] with no commands.
+    ///
+    /// # Arguments
+    /// - `name`: Short name of the action, used when reporting attempts.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// Adds a command to run after all existing commands.
+    ///
+    /// # Arguments
+    /// - `program`: The program to run.
+    /// - `args`: The arguments to pass to the program.
+    pub fn with_command<I, S>(mut self, program: impl Into<String>, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.commands
+            .push((program.into(), args.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    fn run(program: &str, args: &[String]) -> Result<()> {
+        let status = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|e| Error::io(Step::RunCommand, &e))?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(Error::Command {
+                program: program.to_string(),
+                code: status.code(),
+            })
+        }
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(target_os = "windows")] {
+        impl CommandAction {
+            /// Disables then re-enables a network interface using `netsh`.
+            ///
+            /// # Arguments
+            /// - `interface`: Name of the interface, such as `Wi-Fi`.
+            pub fn bounce_interface(interface: &str) -> Self {
+                let name = format!("name={interface}");
+                Self::new("bounce interface")
+                    .with_command("netsh", ["interface", "set", "interface", &name, "admin=disabled"])
+                    .with_command("netsh", ["interface", "set", "interface", &name, "admin=enabled"])
+            }
+
+            /// Releases then renews the DHCP lease of a network interface using `ipconfig`.
+            ///
+            /// # Arguments
+            /// - `interface`: Name of the interface, such as `Wi-Fi`.
+            pub fn renew_dhcp(interface: &str) -> Self {
+                Self::new("renew DHCP")
+                    .with_command("ipconfig", ["/release", interface])
+                    .with_command("ipconfig", ["/renew", interface])
+            }
+
+            /// Connects to a different Wi-Fi profile using `netsh`.
+            ///
+            /// # Arguments
+            /// - `profile`: Name of the fallback profile.
+            pub fn connect_to(profile: &str) -> Self {
+                Self::new("switch to fallback network")
+                    .with_command("netsh", ["wlan", "connect", &format!("name={profile}")])
+            }
+        }
+    } else if #[cfg(target_os = "linux")] {
+        impl CommandAction {
+            /// Sets a network interface down then up using `ip`.
+            ///
+            /// # Arguments
+            /// - `interface`: Name of the interface, such as `wlan0`.
+            pub fn bounce_interface(interface: &str) -> Self {
+                Self::new("bounce interface")
+                    .with_command("ip", ["link", "set", "dev", interface, "down"])
+                    .with_command("ip", ["link", "set", "dev", interface, "up"])
+            }
+
+            /// Releases then renews the DHCP lease of a network interface using `dhclient`.
+            ///
+            /// # Arguments
+            /// - `interface`: Name of the interface, such as `wlan0`.
+            pub fn renew_dhcp(interface: &str) -> Self {
+                Self::new("renew DHCP")
+                    .with_command("dhclient", ["-r", interface])
+                    .with_command("dhclient", [interface])
+            }
+
+            /// Activates a different connection profile using `nmcli`.
+            ///
+            /// # Arguments
+            /// - `profile`: Name of the fallback profile.
+            pub fn connect_to(profile: &str) -> Self {
+                Self::new("switch to fallback network")
+                    .with_command("nmcli", ["connection", "up", "id", profile])
+            }
+        }
+    }
+}
+
+impl RemediationAction for CommandAction {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn remediate(&self) -> Result<()> {
+        self.commands
+            .iter()
+            .try_for_each(|(program, args)| Self::run(program, args))
+    }
+}
@@ -0,0 +1,24 @@
+use crate::error::Result;
+use crate::network_manager::NetworkManager;
+
+/// Trait for a single step of the remediation ladder.
+pub trait RemediationAction {
+    /// Short name of the action, used when reporting attempts.
+    fn name(&self) -> &str;
+
+    /// Attempts the remediation.
+    ///
+    /// Returns an [`crate::error::Error`] describing why the remediation failed, if it did.
+    fn remediate(&self) -> Result<()>;
+}
+
+/// Every [`NetworkManager`] can reconnect the current profile, which is the first rung of the ladder.
+impl<M: NetworkManager> RemediationAction for M {
+    fn name(&self) -> &str {
+        "reconnect"
+    }
+
+    fn remediate(&self) -> Result<()> {
+        self.reconnect()
+    }
+}
@@ -0,0 +1,102 @@
+use std::cell::Cell;
+
+use crate::error::Result;
+use crate::remediation::RemediationAction;
+
+/// The result of attempting one rung of a [`RemediationLadder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemediationAttempt {
+    /// Index of the rung attempted, where `0` is the first rung.
+    pub rung: usize,
+
+    /// Name of the action attempted.
+    pub action: String,
+
+    /// The result reported by the action.
+    pub result: Result<()>,
+}
+
+impl RemediationAttempt {
+    /// Returns `true` if the action reported success.
+    pub fn is_success(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// An ordered list of [`RemediationAction`]s which escalates across consecutive failed polls.
+///
+/// The first rung is supplied on each attempt (by [`crate::app::NetworkApp`], its
+/// [`crate::network_manager::NetworkManager`]), followed by the rungs added with
+/// [`RemediationLadder::with_rung`]. Each attempt moves the ladder up one rung, staying on the last
+/// rung once it is reached, until [`RemediationLadder::reset`] is called.
+#[derive(Default)]
+pub struct RemediationLadder {
+    rungs: Vec<Box<dyn RemediationAction>>,
+    level: Cell<usize>,
+}
+
+impl RemediationLadder {
+    /// Creates a new [`RemediationLadder`] with only the first rung.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rung above all existing rungs.
+    pub fn with_rung(mut self, action: impl RemediationAction + 'static) -> Self {
+        self.rungs.push(Box::new(action));
+        self
+    }
+
+    /// Returns the total number of rungs, including the first.
+    pub fn rung_count(&self) -> usize {
+        self.rungs.len() + 1
+    }
+
+    /// Returns the index of the rung the next attempt will use.
+    pub fn level(&self) -> usize {
+        self.level.get()
+    }
+
+    /// Returns the ladder to the first rung.
+    pub fn reset(&self) {
+        self.level.set(0);
+    }
+
+    /// Moves the ladder to rung `level`, or to the last rung if there are fewer, such as to carry
+    /// on from the ladder it replaces.
+    pub fn resume_at(&self, level: usize) {
+        self.level.set(level.min(self.rungs.len()));
+    }
+
+    /// Returns the action the next attempt will use.
+    ///
+    /// # Arguments
+    /// - `first`: The action used for the first rung.
+    pub fn next_action<'a>(
+        &'a self,
+        first: &'a dyn RemediationAction,
+    ) -> &'a dyn RemediationAction {
+        match self.level.get() {
+            0 => first,
+            rung => self.rungs[rung - 1].as_ref(),
+        }
+    }
+
+    /// Attempts the current rung, then escalates to the next one.
+    ///
+    /// # Arguments
+    /// - `first`: The action used for the first rung.
+    pub fn attempt(&self, first: &dyn RemediationAction) -> RemediationAttempt {
+        let rung = self.level.get();
+        let action = self.next_action(first);
+
+        let result = action.remediate();
+        self.level.set((rung + 1).min(self.rungs.len()));
+
+        RemediationAttempt {
+            rung,
+            action: action.name().to_string(),
+            result,
+        }
+    }
+}
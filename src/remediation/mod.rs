@@ -0,0 +1,12 @@
+//! Module for remediating lost internet connectivity.
+//!
+//! Remediation escalates through a [`RemediationLadder`] of [`RemediationAction`]s, starting from
+//! reconnecting the current profile with a [`crate::network_manager::NetworkManager`].
+
+mod command;
+mod interface;
+mod ladder;
+
+pub use command::CommandAction;
+pub use interface::RemediationAction;
+pub use ladder::{RemediationAttempt, RemediationLadder};
@@ -0,0 +1,137 @@
+//! UTC calendar arithmetic for bucketing and printing times, using the proleptic Gregorian
+//! calendar.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// A date in the UTC calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Returns the date `time` falls on.
+    pub fn of(time: SystemTime) -> Self {
+        Self::from_days(seconds(time) / SECS_PER_DAY)
+    }
+
+    /// Returns the date `days` days after the Unix epoch.
+    fn from_days(days: u64) -> Self {
+        // Shift the epoch to 0000-03-01, so leap days fall at the end of each 400-year era.
+        let days = days as i64 + 719_468;
+        let era = days / 146_097;
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Self { year, month, day }
+    }
+
+    /// Returns the number of days from the Unix epoch to the date.
+    fn days(&self) -> u64 {
+        let year = self.year - i64::from(self.month <= 2);
+        let era = year / 400;
+        let year_of_era = year - era * 400;
+        let shifted_month = i64::from((self.month + 9) % 12);
+        let day_of_year = (153 * shifted_month + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        (era * 146_097 + day_of_era - 719_468).max(0) as u64
+    }
+
+    /// Returns the start of the date.
+    pub fn start(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.days() * SECS_PER_DAY)
+    }
+
+    /// Returns the number of days since the preceding Monday, from `0` on Mondays to `6` on
+    /// Sundays.
+    pub fn days_since_monday(&self) -> u64 {
+        // The Unix epoch was a Thursday.
+        (self.days() + 3) % 7
+    }
+
+    /// Returns the first day of the month after the date.
+    pub fn next_month(&self) -> Self {
+        match self.month {
+            12 => Self {
+                year: self.year + 1,
+                month: 1,
+                day: 1,
+            },
+            month => Self {
+                year: self.year,
+                month: month + 1,
+                day: 1,
+            },
+        }
+    }
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`Date`] to enable easy printing.
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Returns the seconds from the Unix epoch to `time`, or `0` for earlier times.
+fn seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Formats `time` as a UTC date and time to the minute, such as `2024-03-01 14:05`.
+pub(super) fn format_time(time: SystemTime) -> String {
+    let of_day = seconds(time) % SECS_PER_DAY;
+    format!(
+        "{} {:02}:{:02}",
+        Date::of(time),
+        of_day / 3600,
+        of_day % 3600 / 60
+    )
+}
+
+/// Formats `time` as an RFC 3339 UTC timestamp, such as `2024-03-01T14:05:09Z`.
+pub(super) fn format_timestamp(time: SystemTime) -> String {
+    let of_day = seconds(time) % SECS_PER_DAY;
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        Date::of(time),
+        of_day / 3600,
+        of_day % 3600 / 60,
+        of_day % 60
+    )
+}
+
+/// Formats `duration` in days, hours, minutes and seconds, such as `1d 2h 5m` or `45s`.
+pub(super) fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let parts: Vec<String> = [
+        (secs / SECS_PER_DAY, "d"),
+        (secs % SECS_PER_DAY / 3600, "h"),
+        (secs % 3600 / 60, "m"),
+        (secs % 60, "s"),
+    ]
+    .into_iter()
+    .filter(|(value, _)| *value > 0)
+    .map(|(value, unit)| format!("{value}{unit}"))
+    .collect();
+
+    match parts.is_empty() {
+        true => "0s".to_string(),
+        false => parts.join(" "),
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::report::UptimeReport;
+use crate::report::calendar::format_timestamp;
+
+/// Renders `report` as three tables of comma-separated values, separated by blank lines: the
+/// availability of each period, a summary of metrics, and the outages of each failure type.
+///
+/// Times are RFC 3339 timestamps and durations are in whole seconds, so the tables are easy to
+/// import into a spreadsheet.
+pub(super) fn render(report: &UptimeReport) -> String {
+    let mut csv = String::new();
+
+    csv.push_str("period,start,end,availability,downtime_secs,outages,records\n");
+    for period in &report.periods {
+        let _ = writeln!(
+            csv,
+            "{},{},{},{:.4},{},{},{}",
+            period.label,
+            format_timestamp(period.start),
+            format_timestamp(period.end),
+            period.availability(),
+            period.downtime.as_secs(),
+            period.outages,
+            period.records
+        );
+    }
+
+    let longest = report.longest_outage();
+    let secs = |duration: Option<Duration>| {
+        duration.map_or(String::new(), |duration| duration.as_secs().to_string())
+    };
+    csv.push_str("\nmetric,value\n");
+    for (metric, value) in [
+        ("from", format_timestamp(report.from)),
+        ("to", format_timestamp(report.to)),
+        ("availability", format!("{:.4}", report.availability())),
+        ("downtime_secs", report.downtime.as_secs().to_string()),
+        ("outages", report.outages.len().to_string()),
+        ("mtbf_secs", secs(report.mtbf())),
+        ("mttr_secs", secs(report.mttr())),
+        (
+            "longest_outage_secs",
+            secs(longest.map(|outage| outage.duration(report.to))),
+        ),
+        (
+            "longest_outage_start",
+            longest.map_or(String::new(), |outage| format_timestamp(outage.started)),
+        ),
+    ] {
+        let _ = writeln!(csv, "{metric},{value}");
+    }
+
+    csv.push_str("\nfailure_type,outages,downtime_secs\n");
+    for failure in &report.failures {
+        let _ = writeln!(
+            csv,
+            "{},{},{}",
+            failure.failure,
+            failure.outages,
+            failure.downtime.as_secs()
+        );
+    }
+
+    csv
+}
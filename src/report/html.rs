@@ -0,0 +1,149 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::report::calendar::{format_duration, format_time};
+use crate::report::{FailureType, Period, UptimeReport};
+
+/// Styles inlined into the page, so it can be opened or mailed without any other files.
+const STYLE: &str = "\
+body { font-family: system-ui, sans-serif; margin: 2em auto; max-width: 56em; color: #222; }
+h1 { font-size: 1.5em; }
+h2 { font-size: 1.15em; margin-top: 2em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { padding: 0.3em 0.6em; border-bottom: 1px solid #ddd; text-align: left; }
+td.number { text-align: right; font-variant-numeric: tabular-nums; }
+.bar { background: #e74c3c; height: 0.8em; min-width: 8em; }
+.bar span { display: block; background: #2ecc71; height: 100%; }
+.empty { color: #777; }";
+
+/// Renders `report` as a self-contained HTML page, with a bar showing the availability of each
+/// period.
+pub(super) fn render(report: &UptimeReport) -> String {
+    let title = format!(
+        "Uptime from {} to {} UTC",
+        format_time(report.from),
+        format_time(report.to)
+    );
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>\n<h1>{title}</h1>\n",
+        title = escape(&title)
+    );
+
+    html.push_str("<h2>Summary</h2>\n<table>\n");
+    let optional = |duration: Option<Duration>| duration.map_or("-".to_string(), format_duration);
+    let longest = report.longest_outage().map_or("-".to_string(), |outage| {
+        format!(
+            "{} from {} ({})",
+            format_duration(outage.duration(report.to)),
+            format_time(outage.started),
+            FailureType::of(outage)
+        )
+    });
+    for (metric, value) in [
+        ("Availability", format!("{:.3}%", report.availability())),
+        ("Downtime", format_duration(report.downtime)),
+        ("Outages", report.outages.len().to_string()),
+        ("Mean time between failures", optional(report.mtbf())),
+        ("Mean time to recovery", optional(report.mttr())),
+        ("Longest outage", longest),
+    ] {
+        let _ = writeln!(
+            html,
+            "<tr><th>{metric}</th><td>{}</td></tr>",
+            escape(&value)
+        );
+    }
+    html.push_str("</table>\n");
+
+    let heading = match report.period {
+        Period::Daily => "Daily availability",
+        Period::Weekly => "Weekly availability",
+        Period::Monthly => "Monthly availability",
+    };
+    let _ = writeln!(
+        html,
+        "<h2>{heading}</h2>\n<table>\n<tr><th>Period</th><th>Availability</th><th></th>\
+         <th>Downtime</th><th>Outages</th><th>Records</th></tr>"
+    );
+    for period in &report.periods {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td class=\"number\">{:.3}%</td>\
+             <td><div class=\"bar\"><span style=\"width: {:.3}%\"></span></div></td>\
+             <td class=\"number\">{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td></tr>",
+            escape(&period.label),
+            period.availability(),
+            period.availability(),
+            escape(&format_duration(period.downtime)),
+            period.outages,
+            period.records
+        );
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Failure types</h2>\n");
+    if report.failures.is_empty() {
+        html.push_str("<p class=\"empty\">No outages.</p>\n");
+    } else {
+        html.push_str("<table>\n<tr><th>Failure type</th><th>Outages</th><th>Downtime</th></tr>\n");
+        for failure in &report.failures {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td></tr>",
+                escape(&failure.failure.to_string()),
+                failure.outages,
+                escape(&format_duration(failure.downtime))
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("<h2>Outages</h2>\n");
+    if report.outages.is_empty() {
+        html.push_str("<p class=\"empty\">No outages.</p>\n");
+    } else {
+        html.push_str(
+            "<table>\n<tr><th>Started</th><th>Duration</th><th>Failure type</th><th>Status</th>\
+             <th>Evidence</th></tr>\n",
+        );
+        for outage in &report.outages {
+            let evidence: Vec<String> = outage.evidence.iter().map(|line| escape(line)).collect();
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td class=\"number\">{}{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&format_time(outage.started)),
+                escape(&format_duration(outage.duration(report.to))),
+                match outage.ended {
+                    Some(_) => "",
+                    None => " (ongoing)",
+                },
+                escape(&FailureType::of(outage).to_string()),
+                escape(&outage.status),
+                evidence.join("<br>")
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Escapes `text` for use as the content of an HTML element or a quoted attribute.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
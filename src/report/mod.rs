@@ -0,0 +1,436 @@
+//! Module for reporting uptime from the history of outages.
+//!
+//! This module defines the [`UptimeReport`] built from the [`Outage`]s in a
+//! [`crate::history::HistoryLog`], with the availability of each day, week or month, the mean time
+//! between failures and to recovery, the longest outage and a breakdown by [`FailureType`]. Reports
+//! can be rendered as plain text, CSV or a self-contained HTML page. Every time is in UTC.
+
+mod calendar;
+mod csv;
+mod html;
+
+use std::time::{Duration, SystemTime};
+
+use crate::diagnosis::Layer;
+use crate::history::{HistoryRecord, Outage, outages};
+use calendar::{Date, format_duration, format_time};
+
+/// The cause of an [`Outage`], as reported by an [`UptimeReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FailureType {
+    /// No interface had a link or an address.
+    Link,
+
+    /// The default gateway was missing or unreachable.
+    Gateway,
+
+    /// Names could not be resolved.
+    Dns,
+
+    /// The local network worked, but the internet beyond it was unreachable.
+    Upstream,
+
+    /// A captive portal intercepted requests.
+    CaptivePortal,
+}
+
+impl FailureType {
+    /// Every failure type, in the order they are reported.
+    pub const ALL: [FailureType; 5] = [
+        FailureType::Link,
+        FailureType::Gateway,
+        FailureType::Dns,
+        FailureType::Upstream,
+        FailureType::CaptivePortal,
+    ];
+
+    /// Returns the cause of `outage`, from the layer its diagnosis found failing, or from its
+    /// status if it was not diagnosed.
+    pub fn of(outage: &Outage) -> Self {
+        if outage.status.starts_with("CaptivePortal") {
+            return FailureType::CaptivePortal;
+        }
+
+        match outage.failed_layer {
+            Some(Layer::Link | Layer::Address) => FailureType::Link,
+            Some(Layer::Gateway) => FailureType::Gateway,
+            Some(Layer::Dns) => FailureType::Dns,
+            Some(Layer::Internet) => FailureType::Upstream,
+            None if outage.status == "Disconnected" => FailureType::Link,
+            None => FailureType::Upstream,
+        }
+    }
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`FailureType`] to enable easy printing.
+impl std::fmt::Display for FailureType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FailureType::Link => write!(f, "link"),
+            FailureType::Gateway => write!(f, "gateway"),
+            FailureType::Dns => write!(f, "dns"),
+            FailureType::Upstream => write!(f, "upstream"),
+            FailureType::CaptivePortal => write!(f, "captive portal"),
+        }
+    }
+}
+
+/// The length of each period an [`UptimeReport`] reports availability for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Period {
+    /// Calendar days.
+    #[default]
+    Daily,
+
+    /// Weeks starting on Monday.
+    Weekly,
+
+    /// Calendar months.
+    Monthly,
+}
+
+impl Period {
+    /// Returns the start of the period containing `time`.
+    pub fn start(&self, time: SystemTime) -> SystemTime {
+        let date = Date::of(time);
+        match self {
+            Period::Daily => date.start(),
+            Period::Weekly => date.start() - Duration::from_secs(date.days_since_monday() * 86_400),
+            Period::Monthly => Date { day: 1, ..date }.start(),
+        }
+    }
+
+    /// Returns the start of the period after the one containing `time`.
+    pub fn next(&self, time: SystemTime) -> SystemTime {
+        match self {
+            Period::Daily => self.start(time) + Duration::from_secs(86_400),
+            Period::Weekly => self.start(time) + Duration::from_secs(7 * 86_400),
+            Period::Monthly => Date::of(time).next_month().start(),
+        }
+    }
+
+    /// Returns the name of the period containing `time`, such as `2024-03-04` for days and weeks,
+    /// which are named after their first day, or `2024-03` for months.
+    pub fn label(&self, time: SystemTime) -> String {
+        let date = Date::of(self.start(time));
+        match self {
+            Period::Daily | Period::Weekly => date.to_string(),
+            Period::Monthly => format!("{:04}-{:02}", date.year, date.month),
+        }
+    }
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`Period`] to enable easy printing.
+impl std::fmt::Display for Period {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Period::Daily => write!(f, "daily"),
+            Period::Weekly => write!(f, "weekly"),
+            Period::Monthly => write!(f, "monthly"),
+        }
+    }
+}
+
+/// The formats an [`UptimeReport`] can be rendered in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    /// Aligned plain text.
+    #[default]
+    Text,
+
+    /// Tables of comma-separated values, separated by blank lines.
+    Csv,
+
+    /// A self-contained HTML page.
+    Html,
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`ReportFormat`] to enable easy printing.
+impl std::fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ReportFormat::Text => write!(f, "text"),
+            ReportFormat::Csv => write!(f, "csv"),
+            ReportFormat::Html => write!(f, "html"),
+        }
+    }
+}
+
+/// Availability during a single day, week or month of an [`UptimeReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeriodSummary {
+    /// Name of the period, such as `2024-03-04` or `2024-03`.
+    pub label: String,
+
+    /// Start of the period, or of the report if it started later.
+    pub start: SystemTime,
+
+    /// End of the period, or of the report if it ended earlier.
+    pub end: SystemTime,
+
+    /// Time the internet was unavailable during the period.
+    pub downtime: Duration,
+
+    /// Number of outages during at least part of the period.
+    pub outages: usize,
+
+    /// Number of history records written during the period.
+    ///
+    /// A period without records is assumed to have kept the status of the last record before it,
+    /// which is only evidence if monitoring was running throughout.
+    pub records: usize,
+}
+
+impl PeriodSummary {
+    /// Returns the percentage of the period the internet was available.
+    pub fn availability(&self) -> f64 {
+        availability(self.start, self.end, self.downtime)
+    }
+}
+
+/// Outages of a single [`FailureType`] in an [`UptimeReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FailureSummary {
+    /// The cause of the outages.
+    pub failure: FailureType,
+
+    /// Number of outages.
+    pub outages: usize,
+
+    /// Time the internet was unavailable because of them.
+    pub downtime: Duration,
+}
+
+/// Uptime between two times, derived from the [`Outage`]s recorded in a history.
+///
+/// Only the part of each outage between the start and end of the report counts as downtime, and
+/// an ongoing outage is treated as lasting until the end of the report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UptimeReport {
+    /// Start of the report, which is no earlier than the first history record.
+    pub from: SystemTime,
+
+    /// End of the report.
+    pub to: SystemTime,
+
+    /// Length of each of [`UptimeReport::periods`].
+    pub period: Period,
+
+    /// Availability of each day, week or month, oldest first.
+    pub periods: Vec<PeriodSummary>,
+
+    /// The outages, in the order they started.
+    pub outages: Vec<Outage>,
+
+    /// Time the internet was unavailable.
+    pub downtime: Duration,
+
+    /// Outages of each failure type which occurred, in the order of [`FailureType::ALL`].
+    pub failures: Vec<FailureSummary>,
+}
+
+impl UptimeReport {
+    /// Creates a new [`UptimeReport`] of the outages recorded by `records` between `from` and
+    /// `to`, with the availability of each `period`.
+    ///
+    /// The report starts no earlier than the first record, as nothing is known of the time before
+    /// monitoring began, so without records it covers no time at all. Outages which do not overlap
+    /// the report are ignored.
+    pub fn new(
+        records: &[HistoryRecord],
+        period: Period,
+        from: SystemTime,
+        to: SystemTime,
+    ) -> Self {
+        let first = records.first().map_or(to, HistoryRecord::time);
+        let from = from.max(first);
+        let to = to.max(from);
+        let outages: Vec<Outage> = outages(records)
+            .into_iter()
+            .filter(|outage| outage.overlaps(from, to))
+            .collect();
+        let downtime = |start, end, outages: &[&Outage]| -> Duration {
+            outages
+                .iter()
+                .map(|outage| outage.overlap(start, end, to))
+                .sum()
+        };
+
+        let mut periods = Vec::new();
+        let mut start = from;
+        while start < to {
+            let end = period.next(start).min(to);
+            let during: Vec<&Outage> = outages
+                .iter()
+                .filter(|outage| outage.overlaps(start, end))
+                .collect();
+            periods.push(PeriodSummary {
+                label: period.label(start),
+                start,
+                end,
+                downtime: downtime(start, end, &during),
+                outages: during.len(),
+                records: records
+                    .iter()
+                    .filter(|record| (start..end).contains(&record.time()))
+                    .count(),
+            });
+            start = end;
+        }
+
+        let failures = FailureType::ALL
+            .into_iter()
+            .map(|failure| {
+                let of_type: Vec<&Outage> = outages
+                    .iter()
+                    .filter(|outage| FailureType::of(outage) == failure)
+                    .collect();
+                FailureSummary {
+                    failure,
+                    outages: of_type.len(),
+                    downtime: downtime(from, to, &of_type),
+                }
+            })
+            .filter(|summary| summary.outages > 0)
+            .collect();
+
+        Self {
+            from,
+            to,
+            period,
+            periods,
+            downtime: downtime(from, to, &outages.iter().collect::<Vec<_>>()),
+            outages,
+            failures,
+        }
+    }
+
+    /// Returns the percentage of the report the internet was available.
+    pub fn availability(&self) -> f64 {
+        availability(self.from, self.to, self.downtime)
+    }
+
+    /// Returns the time the internet was available.
+    pub fn uptime(&self) -> Duration {
+        let length = self.to.duration_since(self.from).unwrap_or_default();
+        length.saturating_sub(self.downtime)
+    }
+
+    /// Returns the mean time between failures, which is the uptime divided by the number of
+    /// outages, or `None` if there were no outages.
+    pub fn mtbf(&self) -> Option<Duration> {
+        let outages = u32::try_from(self.outages.len()).ok()?;
+        self.uptime().checked_div(outages)
+    }
+
+    /// Returns the mean time to recovery, which is the mean length of the outages which ended,
+    /// including any part outside the report, or `None` if none ended.
+    pub fn mttr(&self) -> Option<Duration> {
+        let ended: Vec<Duration> = self
+            .outages
+            .iter()
+            .filter(|outage| outage.ended.is_some())
+            .map(|outage| outage.duration(self.to))
+            .collect();
+        let count = u32::try_from(ended.len()).ok()?;
+        ended.iter().sum::<Duration>().checked_div(count)
+    }
+
+    /// Returns the outage which lasted longest, including any part outside the report, or `None`
+    /// if there were no outages.
+    pub fn longest_outage(&self) -> Option<&Outage> {
+        self.outages
+            .iter()
+            .max_by_key(|outage| outage.duration(self.to))
+    }
+
+    /// Renders the report in `format`.
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Text => self.to_string(),
+            ReportFormat::Csv => csv::render(self),
+            ReportFormat::Html => html::render(self),
+        }
+    }
+}
+
+/// Implementing the [`std::fmt::Display`] trait for [`UptimeReport`] to enable easy printing.
+impl std::fmt::Display for UptimeReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Uptime from {} to {} UTC",
+            format_time(self.from),
+            format_time(self.to)
+        )?;
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<12}{:>14}{:>16}{:>9}{:>9}",
+            "Period", "Availability", "Downtime", "Outages", "Records"
+        )?;
+        for period in &self.periods {
+            writeln!(
+                f,
+                "{:<12}{:>13.3}%{:>16}{:>9}{:>9}",
+                period.label,
+                period.availability(),
+                format_duration(period.downtime),
+                period.outages,
+                period.records
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "{:<16}{:.3}%", "Availability", self.availability())?;
+        writeln!(f, "{:<16}{}", "Downtime", format_duration(self.downtime))?;
+        writeln!(f, "{:<16}{}", "Outages", self.outages.len())?;
+        writeln!(f, "{:<16}{}", "MTBF", optional_duration(self.mtbf()))?;
+        writeln!(f, "{:<16}{}", "MTTR", optional_duration(self.mttr()))?;
+        match self.longest_outage() {
+            Some(outage) => writeln!(
+                f,
+                "{:<16}{} from {} ({})",
+                "Longest outage",
+                format_duration(outage.duration(self.to)),
+                format_time(outage.started),
+                FailureType::of(outage)
+            )?,
+            None => writeln!(f, "{:<16}-", "Longest outage")?,
+        }
+
+        if !self.failures.is_empty() {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "{:<16}{:>9}{:>16}",
+                "Failure type", "Outages", "Downtime"
+            )?;
+            for failure in &self.failures {
+                writeln!(
+                    f,
+                    "{:<16}{:>9}{:>16}",
+                    failure.failure.to_string(),
+                    failure.outages,
+                    format_duration(failure.downtime)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the percentage of the time between `start` and `end` not covered by `downtime`, or
+/// `100` if no time passed.
+fn availability(start: SystemTime, end: SystemTime, downtime: Duration) -> f64 {
+    let length = end.duration_since(start).unwrap_or_default();
+    match length.is_zero() {
+        true => 100.0,
+        false => 100.0 * (1.0 - downtime.as_secs_f64() / length.as_secs_f64()).max(0.0),
+    }
+}
+
+fn optional_duration(duration: Option<Duration>) -> String {
+    duration.map_or("-".to_string(), format_duration)
+}
@@ -0,0 +1,227 @@
+//! Helpers shared between integration tests.
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+/// A request received by [`TestHttpServer`].
+#[derive(Debug, Clone)]
+pub struct TestRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+/// A canned response returned by [`TestHttpServer`].
+#[derive(Debug, Clone)]
+pub struct TestResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl TestResponse {
+    pub fn new(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.to_string(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+type Handler = dyn Fn(&TestRequest) -> TestResponse + Send + Sync;
+
+/// A minimal HTTP/1.1 server on a loopback port, stopped when dropped.
+pub struct TestHttpServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<TestRequest>>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl TestHttpServer {
+    pub fn start(handler: impl Fn(&TestRequest) -> TestResponse + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let thread = {
+            let requests = requests.clone();
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let Ok(stream) = stream else { continue };
+                    Self::serve(stream, handler.as_ref(), &requests);
+                }
+            })
+        };
+
+        Self {
+            addr,
+            requests,
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// Returns the URL of `path` on this server.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    /// Returns the address this server listens on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the requests received so far.
+    pub fn requests(&self) -> Vec<TestRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Reads one request, records it, then writes the response, so a client which has received
+    /// the response always finds its request recorded.
+    fn serve(
+        stream: TcpStream,
+        handler: &Handler,
+        requests: &Mutex<Vec<TestRequest>>,
+    ) -> Option<()> {
+        let mut reader = BufReader::new(stream.try_clone().ok()?);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).ok()?;
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next()?.to_string();
+        let path = parts.next()?.to_string();
+
+        let mut headers = Vec::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).ok()?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+            }
+        }
+
+        let length = headers
+            .iter()
+            .find(|(name, _)| name == "content-length")
+            .and_then(|(_, value)| value.parse().ok())
+            .unwrap_or(0);
+        let mut body = vec![0u8; length];
+        reader.read_exact(&mut body).ok()?;
+
+        let request = TestRequest {
+            method,
+            path,
+            headers,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        };
+        let response = handler(&request);
+        requests.lock().unwrap().push(request);
+
+        let mut head = format!("HTTP/1.1 {} Test\r\n", response.status);
+        for (name, value) in &response.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str(&format!(
+            "Content-Length: {}\r\nConnection: close\r\n\r\n",
+            response.body.len()
+        ));
+
+        let mut stream = stream;
+        stream.write_all(head.as_bytes()).ok()?;
+        stream.write_all(response.body.as_bytes()).ok()
+    }
+}
+
+impl Drop for TestHttpServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = TcpStream::connect(self.addr);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Header line of `/proc/net/route`.
+pub const ROUTE_HEADER: &str =
+    "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT";
+
+/// Header line of `/proc/net/arp`.
+pub const ARP_HEADER: &str =
+    "IP address       HW type     Flags       HW address            Mask     Device";
+
+/// Adds the interface `name` to the `sys/class/net` fixture under `root`, returning its directory.
+pub fn add_interface(
+    root: &Path,
+    name: &str,
+    operstate: &str,
+    carrier: &str,
+    kind: &str,
+) -> PathBuf {
+    let dir = root.join("sys/class/net").join(name);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("operstate"), format!("{operstate}\n")).unwrap();
+    fs::write(dir.join("carrier"), format!("{carrier}\n")).unwrap();
+    fs::write(dir.join("type"), format!("{kind}\n")).unwrap();
+    dir
+}
+
+/// Writes `lines` to the `proc/net/{file}` fixture under `root`, after `header` if there is one.
+pub fn write_proc(root: &Path, file: &str, header: Option<&str>, lines: &[&str]) {
+    let dir = root.join("proc/net");
+    fs::create_dir_all(&dir).unwrap();
+    let contents: Vec<&str> = header.into_iter().chain(lines.iter().copied()).collect();
+    fs::write(dir.join(file), contents.join("\n")).unwrap();
+}
+
+/// A route line, with the destination, gateway and mask in the kernel's little-endian hex.
+pub fn route(iface: &str, destination: &str, gateway: &str, flags: &str, mask: &str) -> String {
+    format!("{iface}\t{destination}\t{gateway}\t{flags}\t0\t0\t600\t{mask}\t0\t0\t0")
+}
+
+/// A default route leaving `iface` through 192.168.2.1.
+pub fn default_route(iface: &str, flags: &str) -> String {
+    route(iface, "00000000", "0102A8C0", flags, "00000000")
+}
+
+/// The prefix route of 192.168.2.0/24 on `iface`.
+pub fn subnet_route(iface: &str) -> String {
+    route(iface, "0002A8C0", "00000000", "0001", "00FFFFFF")
+}
+
+/// A default route line of `/proc/net/ipv6_route`, with the next hop as 32 hex digits.
+pub fn ipv6_default_route(iface: &str, next_hop: &str, flags: &str) -> String {
+    format!(
+        "00000000000000000000000000000000 00 00000000000000000000000000000000 00 {next_hop} 00000400 00000001 00000000 {flags}     {iface}"
+    )
+}
+
+/// A line of `/proc/net/arp` resolving `ip` on `iface`.
+pub fn arp_entry(ip: &str, flags: &str, iface: &str) -> String {
+    format!("{ip}      0x1         {flags}         02:fc:00:00:00:05     *        {iface}")
+}
@@ -0,0 +1,82 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use internet_reloader::adapter::{Adapter, adapters_under};
+use tempfile::TempDir;
+
+/// Adds an adapter with the hardware address `address` to the fixture under `root`.
+fn add_adapter(root: &Path, name: &str, operstate: &str, kind: &str, address: &str) {
+    let dir = common::add_interface(root, name, operstate, "1", kind);
+    fs::write(dir.join("address"), format!("{address}\n")).unwrap();
+}
+
+#[test]
+fn lists_non_loopback_adapters_sorted_by_name() {
+    let root = TempDir::new().unwrap();
+    add_adapter(root.path(), "wlan0", "up", "1", "02:00:00:00:01:00");
+    add_adapter(root.path(), "lo", "unknown", "772", "00:00:00:00:00:00");
+    add_adapter(root.path(), "eth0", "down", "1", "02:00:00:00:02:00");
+    add_adapter(root.path(), "tun0", "unknown", "65534", "00:00:00:00:00:00");
+    fs::create_dir(root.path().join("sys/class/net/wlan0/phy80211")).unwrap();
+
+    let adapters = adapters_under(root.path()).unwrap();
+
+    assert_eq!(
+        adapters,
+        vec![
+            Adapter {
+                name: "eth0".to_string(),
+                description: None,
+                mac: Some("02:00:00:00:02:00".to_string()),
+                guid: None,
+                up: false,
+                wireless: false,
+            },
+            Adapter {
+                name: "tun0".to_string(),
+                description: None,
+                mac: None,
+                guid: None,
+                up: true,
+                wireless: false,
+            },
+            Adapter {
+                name: "wlan0".to_string(),
+                description: None,
+                mac: Some("02:00:00:00:01:00".to_string()),
+                guid: None,
+                up: true,
+                wireless: true,
+            },
+        ]
+    );
+}
+
+#[test]
+fn describes_adapters_by_driver() {
+    let root = TempDir::new().unwrap();
+    add_adapter(root.path(), "wlan0", "up", "1", "02:00:00:00:01:00");
+    let driver = root.path().join("drivers/iwlwifi");
+    fs::create_dir_all(&driver).unwrap();
+    fs::create_dir(root.path().join("sys/class/net/wlan0/device")).unwrap();
+    std::os::unix::fs::symlink(
+        &driver,
+        root.path().join("sys/class/net/wlan0/device/driver"),
+    )
+    .unwrap();
+
+    let adapters = adapters_under(root.path()).unwrap();
+
+    assert_eq!(adapters[0].description.as_deref(), Some("iwlwifi"));
+}
+
+#[test]
+fn fails_without_sysfs() {
+    let root = TempDir::new().unwrap();
+
+    assert!(adapters_under(root.path()).is_err());
+}
@@ -1,14 +1,24 @@
 #[cfg(test)]
 use mockall::{mock, predicate::*};
 
-use internet_reloader::app::{NetworkApp, NetworkStatus};
-use internet_reloader::internet_connectivity::InternetConnectivity;
+use std::time::Duration;
+
+use internet_reloader::app::{NetworkApp, NetworkStatus, RecoveryVerification};
+use internet_reloader::cancellation::CancellationToken;
+use internet_reloader::diagnosis::{Layer, LayerCheck, LayerStatus, NetworkDiagnostics};
+use internet_reloader::error::{Error, Result, Step};
+use internet_reloader::hysteresis::{Hysteresis, LinkState, Transition};
+use internet_reloader::internet_connectivity::{
+    CaptivePortal, ConnectionQuality, InternetConnectivity, QualityIssue, QualityReport,
+    QualityThresholds,
+};
 use internet_reloader::network_manager::NetworkManager;
+use internet_reloader::remediation::{RemediationAction, RemediationLadder};
 
 mock! {
     NetworkManager {}
     impl NetworkManager for NetworkManager {
-        fn reconnect(&self) -> bool;
+        fn reconnect(&self) -> Result<()>;
     }
 }
 
@@ -17,13 +27,37 @@ mock! {
     impl InternetConnectivity for InternetConnectivity {
         fn is_connected_to_network(&self) -> bool;
         fn is_connected_to_internet(&self) -> bool;
+        fn captive_portal(&self) -> Option<CaptivePortal>;
+    }
+}
+
+mock! {
+    ConnectionQuality {}
+    impl ConnectionQuality for ConnectionQuality {
+        fn measure(&self) -> QualityReport;
     }
 }
 
+mock! {
+    NetworkDiagnostics {}
+    impl NetworkDiagnostics for NetworkDiagnostics {
+        fn check_link(&self) -> LayerCheck;
+        fn check_address(&self) -> LayerCheck;
+        fn check_gateway(&self) -> LayerCheck;
+        fn check_dns(&self) -> LayerCheck;
+    }
+}
+
+/// Verification which probes once, without waiting.
+const NO_WAIT_VERIFICATION: RecoveryVerification = RecoveryVerification {
+    timeout: Duration::ZERO,
+    interval: Duration::ZERO,
+};
+
 struct PollCase {
     network_ok: bool,
     internet_ok: bool,
-    reconnect_returns: Option<bool>,
+    reconnect_returns: Option<Result<()>>,
     expected_status: NetworkStatus,
 }
 
@@ -45,18 +79,19 @@ macro_rules! poll_tests {
                     .expect_is_connected_to_internet()
                     .return_const(case.internet_ok);
 
+                checker.expect_captive_portal().return_const(None);
+
                 match case.reconnect_returns {
-                    Some(_) => {
-                        manager
-                            .expect_reconnect()
-                            .return_const(case.reconnect_returns.unwrap());
+                    Some(result) => {
+                        manager.expect_reconnect().return_const(result);
                     }
                     None => {
                         manager.expect_reconnect().times(0);
                     }
                 }
 
-                let app = NetworkApp::new(checker, manager);
+                let app = NetworkApp::new(checker, manager)
+                    .with_recovery_verification(NO_WAIT_VERIFICATION);
                 assert_eq!(app.poll(), case.expected_status);
             }
         )*
@@ -77,16 +112,814 @@ poll_tests! {
         expected_status: NetworkStatus::Disconnected,
     },
 
-    network_only_reconnect_success: PollCase {
+    network_only_reconnect_success_unverified: PollCase {
         network_ok: true,
         internet_ok: false,
-        reconnect_returns: Some(true),
-        expected_status: NetworkStatus::Connected,
+        reconnect_returns: Some(Ok(())),
+        expected_status: NetworkStatus::NetworkOnly,
     },
     network_only_reconnect_fail: PollCase {
         network_ok: true,
         internet_ok: false,
-        reconnect_returns: Some(false),
+        reconnect_returns: Some(Err(Error::NotConnected)),
         expected_status: NetworkStatus::NetworkOnly,
     },
 }
+
+fn connected_checker() -> MockInternetConnectivity {
+    let mut checker = MockInternetConnectivity::new();
+    checker.expect_is_connected_to_network().return_const(true);
+    checker.expect_is_connected_to_internet().return_const(true);
+    checker.expect_captive_portal().return_const(None);
+    checker
+}
+
+#[test]
+fn run_until_stops_when_predicate_returns_true() {
+    let app = NetworkApp::new(connected_checker(), MockNetworkManager::new());
+    let token = CancellationToken::new();
+
+    let mut remaining = 3;
+    let summary = app.run_until(Duration::ZERO, &token, |_| {
+        remaining -= 1;
+        remaining == 0
+    });
+
+    assert_eq!(summary.polls, 3);
+    assert_eq!(summary.connected, 3);
+    assert_eq!(summary.status_changes, 0);
+    assert_eq!(summary.last_status, Some(NetworkStatus::Connected));
+}
+
+#[test]
+fn run_does_not_poll_when_already_cancelled() {
+    let mut checker = MockInternetConnectivity::new();
+    checker.expect_is_connected_to_network().times(0);
+    checker.expect_is_connected_to_internet().times(0);
+
+    let app = NetworkApp::new(checker, MockNetworkManager::new());
+    let token = CancellationToken::new();
+    token.cancel();
+
+    let summary = app.run(Duration::ZERO, &token);
+
+    assert_eq!(summary.polls, 0);
+    assert_eq!(summary.last_status, None);
+}
+
+#[test]
+fn run_stops_when_cancelled_during_interval() {
+    let app = NetworkApp::new(connected_checker(), MockNetworkManager::new());
+    let token = CancellationToken::new();
+
+    let canceller = {
+        let token = token.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            token.cancel();
+        })
+    };
+
+    let summary = app.run(Duration::from_secs(60), &token);
+    canceller.join().unwrap();
+
+    assert_eq!(summary.polls, 1);
+    assert!(summary.elapsed < Duration::from_secs(60));
+}
+
+#[test]
+fn run_until_counts_status_changes() {
+    let mut checker = MockInternetConnectivity::new();
+    let mut network = [true, false, true].into_iter();
+    checker
+        .expect_is_connected_to_network()
+        .returning(move || network.next().unwrap());
+    checker.expect_is_connected_to_internet().return_const(true);
+    checker.expect_captive_portal().return_const(None);
+
+    let app = NetworkApp::new(checker, MockNetworkManager::new());
+    let token = CancellationToken::new();
+
+    let mut polls = 0;
+    let summary = app.run_until(Duration::ZERO, &token, |_| {
+        polls += 1;
+        polls == 3
+    });
+
+    assert_eq!(summary.connected, 2);
+    assert_eq!(summary.disconnected, 1);
+    assert_eq!(summary.status_changes, 2);
+}
+
+#[test]
+fn captive_portal_is_reported_without_reconnecting() {
+    let mut checker = MockInternetConnectivity::new();
+    let mut manager = MockNetworkManager::new();
+
+    checker.expect_is_connected_to_network().return_const(true);
+    checker
+        .expect_is_connected_to_internet()
+        .return_const(false);
+    checker
+        .expect_captive_portal()
+        .return_const(Some(CaptivePortal {
+            login_url: Some("http://portal.example/login".to_string()),
+        }));
+    manager.expect_reconnect().times(0);
+
+    let app = NetworkApp::new(checker, manager);
+
+    assert_eq!(
+        app.poll(),
+        NetworkStatus::CaptivePortal {
+            login_url: Some("http://portal.example/login".to_string()),
+        }
+    );
+}
+
+#[test]
+fn captive_portal_takes_precedence_over_internet_check() {
+    let mut checker = MockInternetConnectivity::new();
+
+    checker.expect_is_connected_to_network().return_const(true);
+    checker.expect_is_connected_to_internet().return_const(true);
+    checker
+        .expect_captive_portal()
+        .return_const(Some(CaptivePortal { login_url: None }));
+
+    let app = NetworkApp::new(checker, MockNetworkManager::new());
+
+    assert_eq!(app.poll(), NetworkStatus::CaptivePortal { login_url: None });
+}
+
+#[test]
+fn captive_portal_is_not_checked_when_disconnected() {
+    let mut checker = MockInternetConnectivity::new();
+
+    checker.expect_is_connected_to_network().return_const(false);
+    checker.expect_captive_portal().times(0);
+
+    let app = NetworkApp::new(checker, MockNetworkManager::new());
+
+    assert_eq!(app.poll(), NetworkStatus::Disconnected);
+}
+
+/// A remediation action which counts its attempts.
+struct CountingAction {
+    attempts: std::rc::Rc<std::cell::Cell<u32>>,
+    result: Result<()>,
+}
+
+impl RemediationAction for CountingAction {
+    fn name(&self) -> &str {
+        "counting"
+    }
+
+    fn remediate(&self) -> Result<()> {
+        self.attempts.set(self.attempts.get() + 1);
+        self.result.clone()
+    }
+}
+
+#[test]
+fn remediation_escalates_across_failed_polls_and_resets_on_recovery() {
+    let mut checker = MockInternetConnectivity::new();
+    let mut manager = MockNetworkManager::new();
+    let mut internet = [false, false, false, true, false].into_iter();
+
+    checker.expect_is_connected_to_network().return_const(true);
+    checker.expect_captive_portal().return_const(None);
+    checker
+        .expect_is_connected_to_internet()
+        .returning(move || internet.next().unwrap());
+    manager
+        .expect_reconnect()
+        .times(2)
+        .return_const(Err(Error::NotConnected));
+
+    let attempts = std::rc::Rc::new(std::cell::Cell::new(0));
+    let ladder = RemediationLadder::new().with_rung(CountingAction {
+        attempts: attempts.clone(),
+        result: Err(Error::Command {
+            program: "ip".to_string(),
+            code: Some(1),
+        }),
+    });
+    let app = NetworkApp::new(checker, manager).with_remediation_ladder(ladder);
+
+    // Reconnect, then the second rung twice, as the ladder stays on its last rung.
+    assert_eq!(app.poll(), NetworkStatus::NetworkOnly);
+    assert_eq!(app.poll(), NetworkStatus::NetworkOnly);
+    assert_eq!(app.poll(), NetworkStatus::NetworkOnly);
+    assert_eq!(attempts.get(), 2);
+
+    // Recovery resets the ladder, so the next outage starts by reconnecting again.
+    assert_eq!(app.poll(), NetworkStatus::Connected);
+    assert_eq!(app.remediation_ladder().level(), 0);
+    assert_eq!(app.poll(), NetworkStatus::NetworkOnly);
+    assert_eq!(attempts.get(), 2);
+}
+
+fn network_only_checker() -> MockInternetConnectivity {
+    let mut checker = MockInternetConnectivity::new();
+    checker.expect_is_connected_to_network().return_const(true);
+    checker
+        .expect_is_connected_to_internet()
+        .return_const(false);
+    checker.expect_captive_portal().return_const(None);
+    checker
+}
+
+#[test]
+fn resumed_app_carries_on_up_the_ladder() {
+    let failing = || CountingAction {
+        attempts: std::rc::Rc::new(std::cell::Cell::new(0)),
+        result: Err(Error::NotConnected),
+    };
+    let mut manager = MockNetworkManager::new();
+    manager
+        .expect_reconnect()
+        .times(1)
+        .return_const(Err(Error::NotConnected));
+    let previous = NetworkApp::new(network_only_checker(), manager)
+        .with_remediation_ladder(RemediationLadder::new().with_rung(failing()));
+    previous.poll();
+
+    let attempts = std::rc::Rc::new(std::cell::Cell::new(0));
+    let ladder = RemediationLadder::new().with_rung(CountingAction {
+        attempts: attempts.clone(),
+        result: Err(Error::NotConnected),
+    });
+    let mut manager = MockNetworkManager::new();
+    manager.expect_reconnect().times(0);
+    let app = NetworkApp::new(network_only_checker(), manager)
+        .with_remediation_ladder(ladder)
+        .resume(previous);
+
+    let report = app.poll_report();
+    assert_eq!(report.state, LinkState::Down);
+    assert_eq!(report.transition, None);
+    assert_eq!(attempts.get(), 1);
+}
+
+#[test]
+fn successful_higher_rung_reports_connected_once_verified() {
+    let mut checker = MockInternetConnectivity::new();
+    let mut manager = MockNetworkManager::new();
+    let mut internet = [false, false, true].into_iter();
+
+    checker.expect_is_connected_to_network().return_const(true);
+    checker
+        .expect_is_connected_to_internet()
+        .returning(move || internet.next().unwrap());
+    checker.expect_captive_portal().return_const(None);
+    manager
+        .expect_reconnect()
+        .times(1)
+        .return_const(Err(Error::NotConnected));
+
+    let attempts = std::rc::Rc::new(std::cell::Cell::new(0));
+    let ladder = RemediationLadder::new().with_rung(CountingAction {
+        attempts: attempts.clone(),
+        result: Ok(()),
+    });
+    let app = NetworkApp::new(checker, manager)
+        .with_remediation_ladder(ladder)
+        .with_recovery_verification(NO_WAIT_VERIFICATION);
+
+    assert_eq!(app.poll(), NetworkStatus::NetworkOnly);
+    assert_eq!(app.poll(), NetworkStatus::Connected);
+    assert_eq!(attempts.get(), 1);
+}
+
+#[test]
+fn hysteresis_delays_outage_and_recovery() {
+    let raw = [false, false, true, false, false, false, true, true];
+
+    let mut checker = MockInternetConnectivity::new();
+    let mut manager = MockNetworkManager::new();
+    let mut internet = raw.into_iter();
+
+    checker.expect_is_connected_to_network().return_const(true);
+    checker.expect_captive_portal().return_const(None);
+    checker
+        .expect_is_connected_to_internet()
+        .returning(move || internet.next().unwrap());
+    manager
+        .expect_reconnect()
+        .times(1)
+        .return_const(Err(Error::NotConnected));
+
+    let app = NetworkApp::new(checker, manager).with_hysteresis(
+        Hysteresis::new()
+            .with_consecutive_failures(3)
+            .with_recovery_successes(2),
+    );
+
+    let reports: Vec<_> = raw.iter().map(|_| app.poll_report()).collect();
+
+    assert_eq!(
+        reports.iter().map(|r| r.internet).collect::<Vec<_>>(),
+        raw.map(Some)
+    );
+    assert_eq!(
+        reports.iter().map(|r| r.state).collect::<Vec<_>>(),
+        [
+            [LinkState::Up; 5].as_slice(),
+            &[LinkState::Down; 2],
+            &[LinkState::Up]
+        ]
+        .concat()
+    );
+    assert_eq!(
+        reports.iter().map(|r| r.status.clone()).collect::<Vec<_>>(),
+        [
+            vec![NetworkStatus::Connected; 5],
+            vec![NetworkStatus::NetworkOnly; 2],
+            vec![NetworkStatus::Connected],
+        ]
+        .concat()
+    );
+    assert_eq!(
+        reports[5].transition,
+        Some(Transition {
+            from: LinkState::Up,
+            to: LinkState::Down,
+        })
+    );
+    assert!(reports[5].remediation.is_some());
+    assert!(reports[6].remediation.is_none());
+    assert_eq!(
+        reports[7].transition,
+        Some(Transition {
+            from: LinkState::Down,
+            to: LinkState::Up,
+        })
+    );
+}
+
+#[test]
+fn reconnect_is_verified_until_internet_returns() {
+    let mut checker = MockInternetConnectivity::new();
+    let mut manager = MockNetworkManager::new();
+    let mut internet = [false, false, false, true].into_iter();
+
+    checker.expect_is_connected_to_network().return_const(true);
+    checker.expect_captive_portal().return_const(None);
+    checker
+        .expect_is_connected_to_internet()
+        .times(4)
+        .returning(move || internet.next().unwrap());
+    manager.expect_reconnect().times(1).return_const(Ok(()));
+
+    let app = NetworkApp::new(checker, manager).with_recovery_verification(RecoveryVerification {
+        timeout: Duration::from_secs(10),
+        interval: Duration::from_millis(10),
+    });
+
+    let report = app.poll_report();
+    let verification = report.verification.unwrap();
+
+    assert_eq!(report.status, NetworkStatus::Connected);
+    assert!(verification.recovered);
+    assert_eq!(verification.probes, 3);
+    assert!(verification.elapsed >= Duration::from_millis(20));
+}
+
+#[test]
+fn verified_recovery_is_debounced() {
+    let mut checker = MockInternetConnectivity::new();
+    let mut manager = MockNetworkManager::new();
+    let mut internet = [false, true, true, true].into_iter();
+
+    checker.expect_is_connected_to_network().return_const(true);
+    checker.expect_captive_portal().return_const(None);
+    checker
+        .expect_is_connected_to_internet()
+        .times(4)
+        .returning(move || internet.next().unwrap());
+    manager.expect_reconnect().times(1).return_const(Ok(()));
+
+    let app = NetworkApp::new(checker, manager)
+        .with_hysteresis(Hysteresis::new().with_recovery_successes(2))
+        .with_recovery_verification(RecoveryVerification {
+            timeout: Duration::from_secs(10),
+            interval: Duration::ZERO,
+        });
+
+    let report = app.poll_report();
+    let verification = report.verification.unwrap();
+
+    assert_eq!(report.status, NetworkStatus::Connected);
+    assert_eq!(report.state, LinkState::Up);
+    assert!(verification.recovered);
+    assert_eq!(verification.probes, 2);
+    assert_eq!(app.poll(), NetworkStatus::Connected);
+}
+
+#[test]
+fn cancelling_stops_verification() {
+    let mut checker = MockInternetConnectivity::new();
+    let mut manager = MockNetworkManager::new();
+
+    checker.expect_is_connected_to_network().return_const(true);
+    checker.expect_captive_portal().return_const(None);
+    checker
+        .expect_is_connected_to_internet()
+        .return_const(false);
+    manager.expect_reconnect().times(1).return_const(Ok(()));
+
+    let app = NetworkApp::new(checker, manager).with_recovery_verification(RecoveryVerification {
+        timeout: Duration::from_secs(60),
+        interval: Duration::from_secs(60),
+    });
+    let token = CancellationToken::new();
+
+    let canceller = {
+        let token = token.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            token.cancel();
+        })
+    };
+
+    let summary = app.run(Duration::from_secs(60), &token);
+    canceller.join().unwrap();
+
+    assert_eq!(summary.polls, 1);
+    assert!(summary.elapsed < Duration::from_secs(60));
+}
+
+#[test]
+fn reconnect_reports_failure_when_verification_times_out() {
+    let mut checker = MockInternetConnectivity::new();
+    let mut manager = MockNetworkManager::new();
+
+    checker.expect_is_connected_to_network().return_const(true);
+    checker.expect_captive_portal().return_const(None);
+    checker
+        .expect_is_connected_to_internet()
+        .return_const(false);
+    manager.expect_reconnect().times(1).return_const(Ok(()));
+
+    let app = NetworkApp::new(checker, manager).with_recovery_verification(RecoveryVerification {
+        timeout: Duration::from_millis(50),
+        interval: Duration::from_millis(10),
+    });
+
+    let report = app.poll_report();
+    let verification = report.verification.unwrap();
+
+    assert_eq!(report.status, NetworkStatus::NetworkOnly);
+    assert!(report.remediation.unwrap().is_success());
+    assert!(!verification.recovered);
+    assert!(verification.probes > 1);
+    assert!(verification.elapsed >= Duration::from_millis(50));
+}
+
+#[test]
+fn failed_reconnect_is_not_verified() {
+    let mut checker = MockInternetConnectivity::new();
+    let mut manager = MockNetworkManager::new();
+
+    checker.expect_is_connected_to_network().return_const(true);
+    checker.expect_captive_portal().return_const(None);
+    checker
+        .expect_is_connected_to_internet()
+        .times(1)
+        .return_const(false);
+    manager
+        .expect_reconnect()
+        .times(1)
+        .return_const(Err(Error::NotConnected));
+
+    let app = NetworkApp::new(checker, manager);
+
+    assert_eq!(app.poll_report().verification, None);
+}
+
+#[test]
+fn failed_remediation_reports_why_it_failed() {
+    let mut checker = MockInternetConnectivity::new();
+    let mut manager = MockNetworkManager::new();
+    let error = Error::Os {
+        step: Step::Connect,
+        code: 1168,
+    };
+
+    checker.expect_is_connected_to_network().return_const(true);
+    checker.expect_captive_portal().return_const(None);
+    checker
+        .expect_is_connected_to_internet()
+        .return_const(false);
+    manager
+        .expect_reconnect()
+        .times(1)
+        .return_const(Err(error.clone()));
+
+    let app = NetworkApp::new(checker, manager);
+    let report = app.poll_report();
+    let remediation = report.remediation.unwrap();
+
+    assert_eq!(report.status, NetworkStatus::NetworkOnly);
+    assert_eq!(remediation.action, "reconnect");
+    assert_eq!(remediation.result, Err(error));
+}
+
+/// Thresholds allowing a median round trip of 500ms and 10% loss.
+const QUALITY_THRESHOLDS: QualityThresholds = QualityThresholds {
+    max_latency: Some(Duration::from_millis(500)),
+    max_jitter: None,
+    max_loss_percent: Some(10),
+};
+
+/// A burst of ten samples taking `rtt` each, `lost` of which were lost.
+fn quality_report(rtt: Duration, lost: usize) -> QualityReport {
+    QualityReport::from_samples((0..10).map(|i| (i >= lost).then_some(rtt)))
+}
+
+/// A quality probe returning each report in turn.
+fn quality_probe(reports: Vec<QualityReport>) -> MockConnectionQuality {
+    let mut probe = MockConnectionQuality::new();
+    let mut reports = reports.into_iter();
+    probe
+        .expect_measure()
+        .times(reports.len())
+        .returning(move || reports.next().unwrap());
+    probe
+}
+
+#[test]
+fn poor_quality_is_reported_as_degraded_without_remediating() {
+    let mut manager = MockNetworkManager::new();
+    manager.expect_reconnect().times(0);
+    let probe = quality_probe(vec![quality_report(Duration::from_secs(3), 3)]);
+
+    let app =
+        NetworkApp::new(connected_checker(), manager).with_quality_probe(probe, QUALITY_THRESHOLDS);
+    let report = app.poll_report();
+
+    assert_eq!(
+        report.status,
+        NetworkStatus::Degraded {
+            issues: vec![
+                QualityIssue::Latency(Duration::from_secs(3)),
+                QualityIssue::Loss { percent: 30 },
+            ]
+        }
+    );
+    assert_eq!(report.quality.unwrap().loss_percent(), 30);
+    assert_eq!(report.remediation, None);
+    assert_eq!(report.status.to_string(), "Degraded (latency 3s, 30% loss)");
+}
+
+#[test]
+fn good_quality_is_reported_as_connected() {
+    let manager = MockNetworkManager::new();
+    let probe = quality_probe(vec![quality_report(Duration::from_millis(20), 0)]);
+
+    let app =
+        NetworkApp::new(connected_checker(), manager).with_quality_probe(probe, QUALITY_THRESHOLDS);
+
+    assert_eq!(app.poll(), NetworkStatus::Connected);
+}
+
+#[test]
+fn quality_is_not_measured_without_internet() {
+    let mut checker = MockInternetConnectivity::new();
+    let mut manager = MockNetworkManager::new();
+
+    checker.expect_is_connected_to_network().return_const(true);
+    checker.expect_captive_portal().return_const(None);
+    checker
+        .expect_is_connected_to_internet()
+        .return_const(false);
+    manager
+        .expect_reconnect()
+        .return_const(Err(Error::NotConnected));
+
+    let app = NetworkApp::new(checker, manager)
+        .with_quality_probe(quality_probe(Vec::new()), QUALITY_THRESHOLDS);
+
+    assert_eq!(app.check(), NetworkStatus::NetworkOnly);
+    assert_eq!(app.poll_report().quality, None);
+}
+
+#[test]
+fn check_reports_degraded_quality() {
+    let probe = quality_probe(vec![quality_report(Duration::from_millis(20), 5)]);
+
+    let app = NetworkApp::new(connected_checker(), MockNetworkManager::new())
+        .with_quality_probe(probe, QUALITY_THRESHOLDS);
+
+    assert_eq!(
+        app.check(),
+        NetworkStatus::Degraded {
+            issues: vec![QualityIssue::Loss { percent: 50 }]
+        }
+    );
+}
+
+#[test]
+fn degraded_remediation_climbs_the_ladder_and_resets_when_quality_recovers() {
+    let mut manager = MockNetworkManager::new();
+    manager
+        .expect_reconnect()
+        .times(2)
+        .return_const(Err(Error::NotConnected));
+    let poor = quality_report(Duration::from_secs(3), 0);
+    let good = quality_report(Duration::from_millis(20), 0);
+    let probe = quality_probe(vec![poor.clone(), poor.clone(), good, poor]);
+
+    let attempts = std::rc::Rc::new(std::cell::Cell::new(0));
+    let ladder = RemediationLadder::new().with_rung(CountingAction {
+        attempts: attempts.clone(),
+        result: Err(Error::Command {
+            program: "ip".to_string(),
+            code: Some(1),
+        }),
+    });
+    let app = NetworkApp::new(connected_checker(), manager)
+        .with_remediation_ladder(ladder)
+        .with_quality_probe(probe, QUALITY_THRESHOLDS)
+        .with_degraded_remediation(true);
+    let degraded = NetworkStatus::Degraded {
+        issues: vec![QualityIssue::Latency(Duration::from_secs(3))],
+    };
+
+    // Reconnect, then the second rung, keeping the degraded status as both fail.
+    let report = app.poll_report();
+    assert_eq!(report.status, degraded);
+    assert_eq!(report.remediation.unwrap().action, "reconnect");
+    assert_eq!(app.poll(), degraded);
+    assert_eq!(attempts.get(), 1);
+
+    // Good quality resets the ladder, so the next degradation starts by reconnecting again.
+    assert_eq!(app.poll(), NetworkStatus::Connected);
+    assert_eq!(app.remediation_ladder().level(), 0);
+    assert_eq!(app.poll(), degraded);
+    assert_eq!(attempts.get(), 1);
+}
+
+#[test]
+fn degraded_remediation_reports_connected_once_quality_is_verified() {
+    let mut manager = MockNetworkManager::new();
+    manager.expect_reconnect().times(1).return_const(Ok(()));
+    let probe = quality_probe(vec![
+        quality_report(Duration::from_secs(3), 0),
+        quality_report(Duration::from_millis(20), 0),
+    ]);
+
+    let app = NetworkApp::new(connected_checker(), manager)
+        .with_recovery_verification(NO_WAIT_VERIFICATION)
+        .with_quality_probe(probe, QUALITY_THRESHOLDS)
+        .with_degraded_remediation(true);
+    let report = app.poll_report();
+
+    assert_eq!(report.status, NetworkStatus::Connected);
+    assert!(report.verification.unwrap().recovered);
+    assert_eq!(
+        report.quality.unwrap().median(),
+        Some(Duration::from_millis(20))
+    );
+}
+
+#[test]
+fn degraded_remediation_stays_degraded_while_quality_is_poor() {
+    let mut manager = MockNetworkManager::new();
+    manager.expect_reconnect().times(1).return_const(Ok(()));
+    let probe = quality_probe(vec![
+        quality_report(Duration::from_secs(3), 0),
+        quality_report(Duration::from_secs(2), 0),
+    ]);
+
+    let app = NetworkApp::new(connected_checker(), manager)
+        .with_recovery_verification(NO_WAIT_VERIFICATION)
+        .with_quality_probe(probe, QUALITY_THRESHOLDS)
+        .with_degraded_remediation(true);
+    let report = app.poll_report();
+
+    assert_eq!(
+        report.status,
+        NetworkStatus::Degraded {
+            issues: vec![QualityIssue::Latency(Duration::from_secs(2))]
+        }
+    );
+    assert!(report.verification.unwrap().recovered);
+}
+
+#[test]
+fn diagnose_passes_every_layer_when_healthy() {
+    let mut diagnostics = MockNetworkDiagnostics::new();
+    diagnostics
+        .expect_check_link()
+        .return_const(LayerCheck::passed("link up on wlan0"));
+    diagnostics
+        .expect_check_address()
+        .return_const(LayerCheck::passed("wlan0 is on 192.168.2.0/24"));
+    diagnostics
+        .expect_check_gateway()
+        .return_const(LayerCheck::passed("gateway 192.168.2.1 on wlan0"));
+    diagnostics
+        .expect_check_dns()
+        .return_const(LayerCheck::passed("192.168.2.1:53 resolved"));
+
+    let app = NetworkApp::new(connected_checker(), MockNetworkManager::new())
+        .with_diagnostics(diagnostics);
+    let report = app.diagnose();
+
+    assert!(report.is_healthy());
+    assert_eq!(report.failed_layer(), None);
+    assert_eq!(
+        report
+            .layers
+            .iter()
+            .map(|result| result.layer)
+            .collect::<Vec<_>>(),
+        Layer::ALL
+    );
+    assert_eq!(
+        report.layer(Layer::Internet).unwrap().evidence,
+        "internet reachable"
+    );
+}
+
+#[test]
+fn diagnose_names_first_failing_layer_and_skips_the_rest() {
+    let mut checker = MockInternetConnectivity::new();
+    checker.expect_captive_portal().times(0);
+    checker.expect_is_connected_to_internet().times(0);
+
+    let mut diagnostics = MockNetworkDiagnostics::new();
+    diagnostics
+        .expect_check_link()
+        .return_const(LayerCheck::passed("link up on wlan0"));
+    diagnostics
+        .expect_check_address()
+        .return_const(LayerCheck::passed("wlan0 is on 192.168.2.0/24"));
+    diagnostics
+        .expect_check_gateway()
+        .return_const(LayerCheck::failed("no default route"));
+    diagnostics.expect_check_dns().times(0);
+
+    let app = NetworkApp::new(checker, MockNetworkManager::new()).with_diagnostics(diagnostics);
+    let report = app.diagnose();
+
+    assert!(!report.is_healthy());
+    assert_eq!(report.failed_layer(), Some(Layer::Gateway));
+    assert_eq!(report.first_failure().unwrap().evidence, "no default route");
+    for layer in [Layer::Dns, Layer::Internet] {
+        let result = report.layer(layer).unwrap();
+        assert_eq!(result.status, LayerStatus::Skipped);
+        assert_eq!(result.evidence, "not checked, gateway failed");
+    }
+}
+
+#[test]
+fn diagnose_without_diagnostics_skips_unavailable_layers() {
+    let mut checker = MockInternetConnectivity::new();
+    checker.expect_is_connected_to_network().return_const(true);
+    checker
+        .expect_is_connected_to_internet()
+        .return_const(false);
+    checker.expect_captive_portal().return_const(None);
+
+    let app = NetworkApp::new(checker, MockNetworkManager::new());
+    let report = app.diagnose();
+
+    assert_eq!(
+        report.layer(Layer::Link).unwrap().status,
+        LayerStatus::Passed
+    );
+    for layer in [Layer::Address, Layer::Gateway, Layer::Dns] {
+        let result = report.layer(layer).unwrap();
+        assert_eq!(result.status, LayerStatus::Skipped);
+        assert_eq!(result.evidence, "not available");
+    }
+    assert_eq!(report.failed_layer(), Some(Layer::Internet));
+    assert_eq!(
+        report.to_string().lines().last(),
+        Some("internet\tfailed\tinternet unreachable")
+    );
+}
+
+#[test]
+fn diagnose_reports_captive_portal_at_internet_layer() {
+    let mut checker = MockInternetConnectivity::new();
+    checker.expect_is_connected_to_network().return_const(true);
+    checker.expect_is_connected_to_internet().times(0);
+    checker
+        .expect_captive_portal()
+        .return_const(Some(CaptivePortal {
+            login_url: Some("http://portal.example/login".to_string()),
+        }));
+
+    let app = NetworkApp::new(checker, MockNetworkManager::new());
+    let report = app.diagnose();
+
+    assert_eq!(report.failed_layer(), Some(Layer::Internet));
+    assert_eq!(
+        report.first_failure().unwrap().evidence,
+        "captive portal, log in at http://portal.example/login"
+    );
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use common::{TestHttpServer, TestResponse};
+use internet_reloader::internet_connectivity::{
+    CaptivePortal, CaptivePortalDetector, HttpProbe, InternetConnectivity, KnownContent,
+};
+use mockall::mock;
+
+mock! {
+    InternetConnectivity {}
+    impl InternetConnectivity for InternetConnectivity {
+        fn is_connected_to_network(&self) -> bool;
+        fn is_connected_to_internet(&self) -> bool;
+    }
+}
+
+/// A server behaving like the open internet.
+fn open_internet() -> TestHttpServer {
+    TestHttpServer::start(|request| match request.path.as_str() {
+        "/generate_204" => TestResponse::new(204, ""),
+        "/hotspot-detect.html" => TestResponse::new(200, "<HTML><BODY>Success</BODY></HTML>"),
+        _ => TestResponse::new(404, ""),
+    })
+}
+
+/// A server behaving like a portal which redirects every request to its login page.
+fn redirecting_portal() -> TestHttpServer {
+    TestHttpServer::start(|_| {
+        TestResponse::new(302, "").with_header("Location", "http://portal.example/login?orig=1")
+    })
+}
+
+/// A server behaving like a portal which serves its login page in place of every response.
+fn intercepting_portal() -> TestHttpServer {
+    TestHttpServer::start(|_| TestResponse::new(200, "<html>Please log in</html>"))
+}
+
+fn detector(server: &TestHttpServer) -> CaptivePortalDetector<MockInternetConnectivity> {
+    CaptivePortalDetector::new(MockInternetConnectivity::new()).with_checks([
+        KnownContent::new(server.url("/generate_204"), 204, None),
+        KnownContent::new(server.url("/hotspot-detect.html"), 200, Some("Success")),
+    ])
+}
+
+#[test]
+fn no_portal_when_known_content_matches() {
+    let server = open_internet();
+
+    assert_eq!(detector(&server).captive_portal(), None);
+}
+
+#[test]
+fn detects_redirect_with_login_url() {
+    let server = redirecting_portal();
+
+    assert_eq!(
+        detector(&server).captive_portal(),
+        Some(CaptivePortal {
+            login_url: Some("http://portal.example/login?orig=1".to_string()),
+        })
+    );
+}
+
+#[test]
+fn detects_unexpected_body() {
+    let server = intercepting_portal();
+    let detector = CaptivePortalDetector::new(MockInternetConnectivity::new()).with_checks([
+        KnownContent::new(server.url("/hotspot-detect.html"), 200, Some("Success")),
+    ]);
+
+    assert_eq!(
+        detector.captive_portal(),
+        Some(CaptivePortal { login_url: None })
+    );
+}
+
+#[test]
+fn detects_login_page_served_instead_of_no_content() {
+    let server = intercepting_portal();
+
+    assert_eq!(
+        detector(&server).captive_portal(),
+        Some(CaptivePortal { login_url: None })
+    );
+}
+
+#[test]
+fn no_portal_when_known_content_is_unreachable() {
+    let url = open_internet().url("/generate_204");
+    let detector = CaptivePortalDetector::new(MockInternetConnectivity::new())
+        .with_checks([KnownContent::new(url, 204, None)]);
+
+    assert_eq!(detector.captive_portal(), None);
+}
+
+#[test]
+fn delegates_connectivity_checks() {
+    let mut inner = MockInternetConnectivity::new();
+    inner.expect_is_connected_to_network().return_const(true);
+    inner.expect_is_connected_to_internet().return_const(false);
+
+    let detector = CaptivePortalDetector::new(inner).with_checks([]);
+
+    assert!(detector.is_connected_to_network());
+    assert!(!detector.is_connected_to_internet());
+    assert_eq!(detector.captive_portal(), None);
+}
+
+#[test]
+fn http_probe_detects_redirect_on_probe_urls() {
+    let server = redirecting_portal();
+    let probe = HttpProbe::new([server.url("/generate_204")]).with_expected_status(204);
+
+    assert_eq!(
+        probe.captive_portal(),
+        Some(CaptivePortal {
+            login_url: Some("http://portal.example/login?orig=1".to_string()),
+        })
+    );
+}
+
+#[test]
+fn http_probe_reports_no_portal_when_a_url_succeeds() {
+    let server = open_internet();
+    let probe = HttpProbe::new([server.url("/missing"), server.url("/generate_204")])
+        .with_expected_status(204);
+
+    assert_eq!(probe.captive_portal(), None);
+}
+
+#[test]
+fn http_probe_ignores_server_errors() {
+    let server = TestHttpServer::start(|_| TestResponse::new(503, ""));
+    let probe = HttpProbe::new([server.url("/generate_204")]).with_expected_status(204);
+
+    assert_eq!(probe.captive_portal(), None);
+}
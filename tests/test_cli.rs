@@ -0,0 +1,162 @@
+mod common;
+
+use std::process::{Command, Output};
+
+use common::{TestHttpServer, TestResponse};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_internet_reloader"))
+        .args(args)
+        .env_remove("INTERNET_RELOADER_LOG_FORMAT")
+        .env_remove("INTERNET_RELOADER_CONFIG")
+        .output()
+        .unwrap()
+}
+
+fn server() -> TestHttpServer {
+    TestHttpServer::start(|request| match request.path.as_str() {
+        "/generate_204" => TestResponse::new(204, ""),
+        "/login" => TestResponse::new(302, "").with_header("Location", "http://portal/login"),
+        _ => TestResponse::new(500, "error"),
+    })
+}
+
+#[test]
+fn help_documents_exit_codes() {
+    let output = run(&["--help"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout.contains("Exit codes:"));
+    for command in [
+        "status",
+        "diagnose",
+        "watch",
+        "reconnect",
+        "probe",
+        "interfaces",
+        "report",
+    ] {
+        assert!(stdout.contains(command), "missing {command}");
+    }
+}
+
+#[test]
+fn invalid_arguments_exit_with_usage_error() {
+    assert_eq!(run(&["frobnicate"]).status.code(), Some(2));
+    assert_eq!(
+        run(&["--interval", "soon", "status"]).status.code(),
+        Some(2)
+    );
+    assert_eq!(run(&["-v", "-q", "status"]).status.code(), Some(2));
+    assert_eq!(
+        run(&["--log-format", "xml", "status"]).status.code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn http_probe_succeeds_when_any_url_responds() {
+    let server = server();
+    let failing = server.url("/error");
+    let working = server.url("/generate_204");
+
+    let output = run(&["probe", "http", "--url", &failing, "--url", &working]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout.lines().count(), 2);
+    assert!(stdout.contains(&format!("{working}\t204\tSuccess")));
+}
+
+#[test]
+fn http_probe_reports_network_only_when_all_urls_fail() {
+    let server = server();
+
+    let output = run(&["probe", "http", "--url", &server.url("/error")]);
+
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn http_probe_reports_captive_portal_on_redirect() {
+    let server = server();
+
+    let output = run(&["probe", "http", "--url", &server.url("/login")]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(output.status.code(), Some(5));
+    assert!(stdout.contains("http://portal/login"));
+}
+
+#[test]
+fn http_probe_uses_the_configured_urls() {
+    let server = server();
+    let working = server.url("/generate_204");
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::write(
+        &path,
+        format!("[probes.http]\nurls = [\"{working}\"]\nexpected_status = 204\n"),
+    )
+    .unwrap();
+
+    let output = run(&[
+        "--config",
+        path.to_str().unwrap(),
+        "probe",
+        "http",
+        "--url",
+        &server.url("/error"),
+    ]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout.lines().count(), 1);
+    assert!(stdout.contains(&format!("{working}\t204\tSuccess")));
+}
+
+#[test]
+fn http_probe_cannot_be_scoped_to_an_interface() {
+    let output = run(&["--interface", "wlan0", "probe", "http"]);
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn interfaces_lists_adapters() {
+    let output = run(&["interfaces"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(!String::from_utf8(output.stdout).unwrap().contains("\nlo\t"));
+}
+
+#[test]
+fn invalid_configuration_is_reported_with_its_key() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::write(&path, "[thresholds]\nconsecutive_failures = 0\n").unwrap();
+
+    let output = run(&["--config", path.to_str().unwrap(), "status"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stderr.contains("thresholds.consecutive_failures"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn quorum_probe_needs_a_quorum() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::write(&path, "").unwrap();
+
+    let output = run(&["--config", path.to_str().unwrap(), "probe", "quorum"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr.contains("No quorum configured"), "{stderr}");
+}
@@ -0,0 +1,665 @@
+use std::fs;
+use std::time::Duration;
+
+use internet_reloader::adapter::{InterfaceMatch, InterfaceSelector};
+use internet_reloader::cancellation::CancellationToken;
+use internet_reloader::config::{
+    Config, ConfigError, ConfigWatcher, InternetProbe, QualityProtocol, QuorumPolicyKind,
+    RemediationStep, WebhookFormatKind, parse_duration,
+};
+use internet_reloader::hysteresis::Hysteresis;
+use internet_reloader::internet_connectivity::{QualityThresholds, QuorumPolicy};
+use tempfile::TempDir;
+
+const FULL_CONFIG: &str = r#"
+[monitor]
+interval = "10s"
+
+[monitor.verification]
+timeout = "1m"
+interval = "500ms"
+
+[network]
+interface = "wlan1"
+
+[probes]
+internet = "system"
+
+[probes.http]
+urls = ["http://connectivitycheck.gstatic.com/generate_204", "https://example.com/"]
+expected_status = 204
+
+[probes.captive]
+enabled = false
+
+[thresholds]
+consecutive_failures = 3
+recovery_successes = 2
+
+[thresholds.failure_window]
+failures = 3
+window = 5
+
+[[remediation]]
+action = "bounce-interface"
+
+[[remediation]]
+action = "connect-to"
+profile = "Fallback"
+
+[[remediation]]
+action = "command"
+name = "restart modem"
+program = "modem-restart"
+args = ["--hard"]
+
+[[notifications.webhook]]
+url = "https://example.com/hooks/internet"
+"#;
+
+fn invalid_key(contents: &str) -> String {
+    match Config::parse(contents) {
+        Err(ConfigError::Invalid { key, .. }) => key,
+        other => panic!("expected an invalid key, got {other:?}"),
+    }
+}
+
+#[test]
+fn empty_config_uses_defaults() {
+    let config = Config::parse("").unwrap();
+
+    assert_eq!(config, Config::default());
+    assert_eq!(config.monitor.interval, Duration::from_secs(30));
+    assert_eq!(config.probes.internet, InternetProbe::System);
+    assert!(config.probes.captive.enabled);
+    assert_eq!(config.hysteresis(), Hysteresis::new());
+}
+
+#[test]
+fn full_config_is_parsed() {
+    let config = Config::parse(FULL_CONFIG).unwrap();
+
+    assert_eq!(config.monitor.interval, Duration::from_secs(10));
+    assert_eq!(
+        config.recovery_verification().timeout,
+        Duration::from_secs(60)
+    );
+    assert_eq!(
+        config.recovery_verification().interval,
+        Duration::from_millis(500)
+    );
+    assert_eq!(
+        config.interface_selector(),
+        Some(InterfaceSelector::name("wlan1"))
+    );
+    assert_eq!(config.probes.http.as_ref().unwrap().urls.len(), 2);
+    assert_eq!(
+        config.hysteresis(),
+        Hysteresis::new()
+            .with_consecutive_failures(3)
+            .with_recovery_successes(2)
+            .with_failure_window(3, 5)
+    );
+    assert_eq!(
+        config.remediation[2],
+        RemediationStep::Command {
+            name: "restart modem".to_string(),
+            program: "modem-restart".to_string(),
+            args: vec!["--hard".to_string()],
+        }
+    );
+    assert_eq!(config.remediation_ladder().rung_count(), 4);
+    assert_eq!(config.notifications.webhook.len(), 1);
+
+    let app = config.app();
+    assert_eq!(app.remediation_ladder().rung_count(), 4);
+}
+
+#[test]
+fn failure_window_alone_declares_outages() {
+    let config = Config::parse("[thresholds.failure_window]\nfailures = 3\nwindow = 5\n").unwrap();
+
+    assert_eq!(config.thresholds.consecutive_failures, None);
+    assert_eq!(
+        config.hysteresis(),
+        Hysteresis::new().with_failure_window(3, 5)
+    );
+}
+
+#[test]
+fn reload_keeps_subscribers_and_applies_other_settings() {
+    let config = Config::parse("[history]\npath = \"history.jsonl\"\n").unwrap();
+    let reloaded = Config::parse(
+        "[history]\npath = \"history.jsonl\"\n\n\
+         [[remediation]]\naction = \"command\"\nname = \"modem\"\nprogram = \"true\"\n",
+    )
+    .unwrap();
+    let moved = Config::parse("[history]\npath = \"elsewhere.jsonl\"\n").unwrap();
+
+    let app = reloaded.reload_app(config.app());
+
+    assert_eq!(app.remediation_ladder().rung_count(), 2);
+    assert!(!reloaded.subscribers_differ(&config));
+    assert!(moved.subscribers_differ(&config));
+}
+
+#[test]
+fn interface_selectors_are_parsed() {
+    let config =
+        Config::parse("[network]\ninterface = \"mac:02:00:00:00:01:00\"\nall_interfaces = true\n")
+            .unwrap();
+
+    let selector = config.interface_selector().unwrap();
+
+    assert_eq!(
+        selector.matcher(),
+        &InterfaceMatch::Mac("02:00:00:00:01:00".to_string())
+    );
+    assert!(selector.is_all());
+
+    let message = Config::parse("[network]\ninterface = \"guid:\"\n")
+        .unwrap_err()
+        .to_string();
+    assert!(message.contains("has no value"), "{message}");
+}
+
+#[test]
+fn probes_are_scoped_to_the_interface() {
+    let tcp = "[probes]\ninternet = \"tcp\"\n\n[probes.tcp]\n";
+    let http = "[probes]\ninternet = \"http\"\n\n[probes.http]\nurls = [\"http://example.com/\"]\n";
+    let quorum = "[probes.http]\nurls = [\"http://example.com/\"]\n\n[probes.quorum]\n\
+                  [[probes.quorum.members]]\nprobe = \"http\"\n";
+
+    assert!(Config::parse(http).is_ok());
+    assert_eq!(
+        invalid_key(&format!("[network]\ninterface = \"wlan1\"\n\n{http}")),
+        "probes.internet"
+    );
+    assert_eq!(
+        invalid_key(&format!(
+            "[network]\ninterface = \"wlan1\"\n\n[probes]\ninternet = \"quorum\"\n\n{quorum}"
+        )),
+        "probes.quorum.members[0].probe"
+    );
+
+    let scoped = format!("[network]\ninterface = \"wlan1\"\n\n{tcp}");
+    match cfg!(any(target_os = "linux", target_os = "windows")) {
+        true => {
+            let config = Config::parse(&scoped).unwrap();
+            assert_eq!(config.probe_device(), Some("wlan1".to_string()));
+            assert_eq!(Config::parse(tcp).unwrap().probe_device(), None);
+        }
+        false => assert_eq!(invalid_key(&scoped), "probes.internet"),
+    }
+}
+
+#[test]
+fn quorum_probe_is_parsed() {
+    let config = Config::parse(
+        r#"
+[probes]
+internet = "quorum"
+
+[probes.http]
+urls = ["http://connectivitycheck.gstatic.com/generate_204"]
+expected_status = 204
+
+[probes.dns]
+
+[probes.quorum]
+policy = "weighted"
+required = 3
+
+[[probes.quorum.members]]
+probe = "http"
+weight = 2
+
+[[probes.quorum.members]]
+probe = "dns"
+
+[[probes.quorum.members]]
+probe = "system"
+"#,
+    )
+    .unwrap();
+
+    let quorum = config.probes.quorum.as_ref().unwrap();
+    assert_eq!(quorum.policy, QuorumPolicyKind::Weighted);
+    assert_eq!(quorum.members[1].weight, 1);
+    assert_eq!(
+        config.quorum_probe(quorum).policy(),
+        QuorumPolicy::Weighted(3)
+    );
+}
+
+#[test]
+fn invalid_quorums_are_reported_by_key() {
+    const MEMBERS: &str = "[[probes.quorum.members]]\nprobe = \"system\"\n\n[[probes.quorum.members]]\nprobe = \"system\"\n";
+    let quorum = |settings: &str| format!("[probes.quorum]\n{settings}\n{MEMBERS}");
+
+    assert_eq!(
+        invalid_key("[probes]\ninternet = \"quorum\"\n"),
+        "probes.internet"
+    );
+    assert_eq!(
+        invalid_key("[probes.quorum]\nmembers = []\n"),
+        "probes.quorum.members"
+    );
+    assert_eq!(
+        invalid_key("[[probes.quorum.members]]\nprobe = \"http\"\n"),
+        "probes.quorum.members[0].probe"
+    );
+    assert_eq!(
+        invalid_key(&quorum("policy = \"k-of-n\"")),
+        "probes.quorum.required"
+    );
+    assert_eq!(
+        invalid_key(&quorum("policy = \"k-of-n\"\nrequired = 3")),
+        "probes.quorum.required"
+    );
+    assert_eq!(
+        invalid_key(&quorum("policy = \"any\"\nrequired = 1")),
+        "probes.quorum.required"
+    );
+    assert!(Config::parse(&quorum("policy = \"weighted\"\nrequired = 2")).is_ok());
+}
+
+#[test]
+fn huge_quorum_weights_do_not_overflow() {
+    let config = Config::parse(
+        r#"
+[probes.quorum]
+policy = "weighted"
+required = 4294967295
+
+[[probes.quorum.members]]
+probe = "system"
+weight = 4294967295
+
+[[probes.quorum.members]]
+probe = "system"
+weight = 4294967295
+"#,
+    );
+
+    assert!(config.is_ok(), "{config:?}");
+}
+
+#[test]
+fn tcp_probe_is_parsed() {
+    let config = Config::parse(
+        r#"
+[probes]
+internet = "tcp"
+
+[probes.tcp]
+endpoints = ["dns.google:853", "[2001:db8::1]:443"]
+attempt_delay = "100ms"
+"#,
+    )
+    .unwrap();
+
+    let tcp = config.probes.tcp.as_ref().unwrap();
+    assert_eq!(config.probes.internet, InternetProbe::Tcp);
+    assert_eq!(tcp.endpoints.len(), 2);
+    assert_eq!(tcp.timeout, Duration::from_secs(3));
+    assert_eq!(tcp.attempt_delay, Duration::from_millis(100));
+}
+
+#[test]
+fn invalid_tcp_settings_are_reported_by_key() {
+    assert_eq!(
+        invalid_key("[probes]\ninternet = \"tcp\"\n"),
+        "probes.internet"
+    );
+    assert_eq!(
+        invalid_key("[probes.tcp]\nendpoints = []\n"),
+        "probes.tcp.endpoints"
+    );
+    assert_eq!(
+        invalid_key("[probes.tcp]\nendpoints = [\"dns.google:853\", \"dns.google\"]\n"),
+        "probes.tcp.endpoints[1]"
+    );
+    assert_eq!(
+        invalid_key("[probes.tcp]\ntimeout = \"0s\"\n"),
+        "probes.tcp.timeout"
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn icmp_probe_is_parsed() {
+    let config = Config::parse(
+        r#"
+[probes]
+internet = "icmp"
+
+[probes.icmp]
+targets = ["192.0.2.1", "2001:db8::1"]
+timeout = "500ms"
+"#,
+    )
+    .unwrap();
+
+    let icmp = config.probes.icmp.as_ref().unwrap();
+    assert_eq!(config.probes.internet, InternetProbe::Icmp);
+    assert_eq!(
+        icmp.targets[1],
+        "2001:db8::1".parse::<std::net::IpAddr>().unwrap()
+    );
+    assert_eq!(icmp.count, 3);
+    assert_eq!(icmp.timeout, Duration::from_millis(500));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn invalid_icmp_settings_are_reported_by_key() {
+    assert_eq!(
+        invalid_key("[probes]\ninternet = \"icmp\"\n"),
+        "probes.internet"
+    );
+    assert_eq!(
+        invalid_key("[probes.icmp]\ntargets = []\n"),
+        "probes.icmp.targets"
+    );
+    assert_eq!(
+        invalid_key("[probes.icmp]\ncount = 0\n"),
+        "probes.icmp.count"
+    );
+    assert!(matches!(
+        Config::parse("[probes.icmp]\ntargets = [\"example.com\"]\n"),
+        Err(ConfigError::Parse { .. })
+    ));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn gateway_check_is_parsed() {
+    let config = Config::parse("[probes.gateway]\ntimeout = \"250ms\"\n").unwrap();
+
+    let gateway = config.probes.gateway.as_ref().unwrap();
+    assert_eq!(gateway.count, 2);
+    assert_eq!(gateway.timeout, Duration::from_millis(250));
+    assert_eq!(Config::default().probes.gateway, None);
+}
+
+#[test]
+fn invalid_gateway_settings_are_reported_by_key() {
+    match cfg!(target_os = "linux") {
+        true => {
+            assert_eq!(
+                invalid_key("[probes.gateway]\ncount = 0\n"),
+                "probes.gateway.count"
+            );
+            assert_eq!(
+                invalid_key("[probes.gateway]\ntimeout = \"0s\"\n"),
+                "probes.gateway.timeout"
+            );
+        }
+        false => assert_eq!(invalid_key("[probes.gateway]\n"), "probes.gateway"),
+    }
+}
+
+#[test]
+fn quality_probe_is_parsed() {
+    let config = Config::parse(
+        r#"
+[probes.quality]
+target = "192.0.2.1:80"
+count = 20
+max_latency = "500ms"
+max_loss_percent = 10
+remediate = true
+"#,
+    )
+    .unwrap();
+
+    let quality = config.probes.quality.as_ref().unwrap();
+    assert_eq!(quality.count, 20);
+    assert_eq!(quality.spacing, Duration::from_millis(100));
+    assert!(quality.remediate);
+    assert_eq!(
+        config.quality_probe(quality).1,
+        QualityThresholds {
+            max_latency: Some(Duration::from_millis(500)),
+            max_jitter: None,
+            max_loss_percent: Some(10),
+        }
+    );
+}
+
+#[test]
+fn icmp_quality_probe_is_parsed() {
+    let contents = "[probes.quality]\nprotocol = \"icmp\"\ntarget = \"192.0.2.1\"\n";
+
+    match cfg!(target_os = "linux") {
+        true => {
+            let config = Config::parse(contents).unwrap();
+            let quality = config.probes.quality.as_ref().unwrap();
+            assert_eq!(quality.protocol, QualityProtocol::Icmp);
+            assert_eq!(
+                invalid_key("[probes.quality]\nprotocol = \"icmp\"\n"),
+                "probes.quality.target"
+            );
+        }
+        false => assert_eq!(invalid_key(contents), "probes.quality.protocol"),
+    }
+}
+
+#[test]
+fn invalid_quality_settings_are_reported_by_key() {
+    assert_eq!(
+        invalid_key("[probes.quality]\ntarget = \"www.google.com\"\n"),
+        "probes.quality.target"
+    );
+    assert_eq!(
+        invalid_key("[probes.quality]\ntarget = \":443\"\n"),
+        "probes.quality.target"
+    );
+    assert_eq!(
+        invalid_key("[probes.quality]\ncount = 0\n"),
+        "probes.quality.count"
+    );
+    assert_eq!(
+        invalid_key("[probes.quality]\nmax_loss_percent = 101\n"),
+        "probes.quality.max_loss_percent"
+    );
+    assert!(Config::parse("[probes.quality]\ntarget = \"[::1]:443\"\n").is_ok());
+}
+
+#[test]
+fn parse_errors_report_the_line() {
+    let error = Config::parse("[monitor]\nintervall = \"10s\"\n").unwrap_err();
+    let message = error.to_string();
+
+    assert!(matches!(error, ConfigError::Parse { .. }));
+    assert!(message.contains("line 2"), "{message}");
+    assert!(message.contains("intervall"), "{message}");
+}
+
+#[test]
+fn invalid_durations_are_rejected() {
+    let message = Config::parse("[monitor]\ninterval = \"10 fortnights\"\n")
+        .unwrap_err()
+        .to_string();
+
+    assert!(message.contains("unknown duration unit"), "{message}");
+    assert_eq!(
+        invalid_key("[monitor]\ninterval = \"0s\"\n"),
+        "monitor.interval"
+    );
+}
+
+#[test]
+fn selected_probe_must_be_configured() {
+    assert_eq!(
+        invalid_key("[probes]\ninternet = \"dns\"\n"),
+        "probes.internet"
+    );
+}
+
+#[test]
+fn invalid_values_are_reported_by_key() {
+    assert_eq!(
+        invalid_key("[probes.http]\nurls = [\"http://a\", \"ftp://b\"]\n"),
+        "probes.http.urls[1]"
+    );
+    assert_eq!(
+        invalid_key("[probes.http]\nurls = [\"http://a\"]\nexpected_status = 42\n"),
+        "probes.http.expected_status"
+    );
+    assert_eq!(
+        invalid_key("[[probes.captive.checks]]\nurl = \"https://secure\"\n"),
+        "probes.captive.checks[0].url"
+    );
+    assert_eq!(
+        invalid_key("[thresholds.failure_window]\nfailures = 4\nwindow = 3\n"),
+        "thresholds.failure_window.window"
+    );
+    assert_eq!(
+        invalid_key("[thresholds]\nrecovery_successes = 0\n"),
+        "thresholds.recovery_successes"
+    );
+    assert_eq!(
+        invalid_key("[[remediation]]\naction = \"renew-dhcp\"\n"),
+        "remediation[0]"
+    );
+    assert_eq!(
+        invalid_key("[network]\nall_interfaces = true\n"),
+        "network.all_interfaces"
+    );
+    assert_eq!(
+        invalid_key("[[notifications.webhook]]\nurl = \"example.com\"\n"),
+        "notifications.webhook[0].url"
+    );
+}
+
+#[test]
+fn webhook_settings_are_parsed() {
+    let dir = TempDir::new().unwrap();
+    let queue = dir.path().join("webhook.jsonl");
+    let config = Config::parse(&format!(
+        r#"
+[network]
+interface = "wlan0"
+
+[[notifications.webhook]]
+url = "https://hooks.slack.com/services/T000/B000/XXXX"
+format = "slack"
+attempts = 5
+backoff = "2s"
+queue = {queue:?}
+
+[[notifications.webhook]]
+url = "https://example.com/hooks/internet"
+template = '{{"text": "{{message}}"}}'
+"#
+    ))
+    .unwrap();
+
+    let slack = &config.notifications.webhook[0];
+    assert_eq!(slack.format, WebhookFormatKind::Slack);
+    assert_eq!(slack.attempts, 5);
+    assert_eq!(slack.backoff, Duration::from_secs(2));
+    assert_eq!(slack.timeout, Duration::from_secs(10));
+    assert_eq!(slack.queue.as_deref(), Some(queue.as_path()));
+    assert_eq!(config.notifier(slack).sink().url(), slack.url);
+
+    let custom = &config.notifications.webhook[1];
+    assert_eq!(custom.format, WebhookFormatKind::Json);
+    assert_eq!(custom.attempts, 3);
+    assert_eq!(custom.queue, None);
+    assert_eq!(custom.template.as_deref(), Some(r#"{"text": "{message}"}"#));
+}
+
+#[test]
+fn invalid_webhook_settings_are_reported_by_key() {
+    let webhook = "[[notifications.webhook]]\nurl = \"https://example.com/hook\"\n";
+
+    assert_eq!(
+        invalid_key(&format!("{webhook}attempts = 0\n")),
+        "notifications.webhook[0].attempts"
+    );
+    assert_eq!(
+        invalid_key(&format!("{webhook}backoff = \"0s\"\n")),
+        "notifications.webhook[0].backoff"
+    );
+    assert_eq!(
+        invalid_key(&format!("{webhook}template = \" \"\n")),
+        "notifications.webhook[0].template"
+    );
+    assert_eq!(
+        invalid_key(&format!(
+            "{webhook}queue = \"/tmp/q.jsonl\"\n{webhook}queue = \"/tmp/q.jsonl\"\n"
+        )),
+        "notifications.webhook[1].queue"
+    );
+}
+
+#[test]
+fn history_is_recorded_to_the_configured_file() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("history.jsonl");
+    let config = Config::parse(&format!("[history]\npath = {path:?}\n")).unwrap();
+
+    assert_eq!(config.history.path.as_deref(), Some(path.as_path()));
+    assert_eq!(Config::default().history.path, None);
+}
+
+#[test]
+fn load_reports_missing_file() {
+    let dir = TempDir::new().unwrap();
+
+    let error = Config::load(dir.path().join("missing.toml")).unwrap_err();
+
+    assert!(matches!(error, ConfigError::Io { .. }));
+}
+
+#[test]
+fn parse_duration_accepts_units() {
+    assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+    assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+    assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+    assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+    assert_eq!(parse_duration("7d"), Ok(Duration::from_secs(604_800)));
+    assert!(parse_duration("soon").is_err());
+}
+
+#[test]
+fn watcher_detects_file_changes_once() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(&path, "").unwrap();
+
+    let mut watcher = ConfigWatcher::new(&path);
+    assert!(!watcher.reload_requested());
+
+    fs::write(&path, "[monitor]\ninterval = \"5s\"\n").unwrap();
+    assert!(watcher.reload_requested());
+    assert!(!watcher.reload_requested());
+
+    watcher.request_reload();
+    assert!(watcher.reload_requested());
+}
+
+#[test]
+fn watcher_wait_stops_when_cancelled() {
+    let dir = TempDir::new().unwrap();
+    let mut watcher = ConfigWatcher::new(dir.path().join("config.toml"));
+    let token = CancellationToken::new();
+    token.cancel();
+
+    assert!(!watcher.wait(Duration::from_millis(10), &token));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn watcher_reloads_on_sighup() {
+    let dir = TempDir::new().unwrap();
+    let mut watcher = ConfigWatcher::new(dir.path().join("config.toml"));
+    watcher.watch_sighup().unwrap();
+
+    signal_hook::low_level::raise(signal_hook::consts::SIGHUP).unwrap();
+
+    assert!(watcher.reload_requested());
+}
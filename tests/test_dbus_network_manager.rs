@@ -0,0 +1,331 @@
+#![cfg(target_os = "linux")]
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use internet_reloader::adapter::InterfaceSelector;
+use internet_reloader::error::{Error, Step};
+use internet_reloader::network_manager::{DbusNetworkManager, NetworkManager};
+use zbus::blocking::Connection;
+use zbus::blocking::connection::Builder;
+use zbus::zvariant::OwnedObjectPath;
+
+const SERVICE: &str = "org.freedesktop.NetworkManager";
+const ROOT_PATH: &str = "/org/freedesktop/NetworkManager";
+
+/// A private session bus, torn down when dropped.
+struct PrivateBus {
+    daemon: Child,
+    address: String,
+}
+
+impl PrivateBus {
+    fn start() -> Self {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address=1"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("dbus-daemon must be installed to run the NetworkManager tests");
+
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().unwrap())
+            .read_line(&mut address)
+            .unwrap();
+
+        Self {
+            daemon,
+            address: address.trim().to_string(),
+        }
+    }
+
+    fn connect(&self) -> Connection {
+        Builder::address(self.address.as_str())
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+}
+
+impl Drop for PrivateBus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
+
+fn path(path: &str) -> OwnedObjectPath {
+    OwnedObjectPath::try_from(path).unwrap()
+}
+
+/// Mock of the root NetworkManager object, recording the calls it receives.
+struct MockNetworkManager {
+    active_connections: Vec<OwnedObjectPath>,
+    calls: Arc<Mutex<Vec<String>>>,
+    fail_activate: bool,
+}
+
+#[zbus::interface(name = "org.freedesktop.NetworkManager")]
+impl MockNetworkManager {
+    fn activate_connection(
+        &self,
+        connection: OwnedObjectPath,
+        device: OwnedObjectPath,
+        specific_object: OwnedObjectPath,
+    ) -> zbus::fdo::Result<OwnedObjectPath> {
+        self.calls.lock().unwrap().push(format!(
+            "activate {} {} {}",
+            connection.as_str(),
+            device.as_str(),
+            specific_object.as_str()
+        ));
+        if self.fail_activate {
+            return Err(zbus::fdo::Error::Failed("activation failed".into()));
+        }
+        Ok(path("/org/freedesktop/NetworkManager/ActiveConnection/9"))
+    }
+
+    fn deactivate_connection(&self, active_connection: OwnedObjectPath) {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("deactivate {}", active_connection.as_str()));
+    }
+
+    #[zbus(property)]
+    fn active_connections(&self) -> Vec<OwnedObjectPath> {
+        self.active_connections.clone()
+    }
+}
+
+/// Mock of an active connection object.
+struct MockActiveConnection {
+    id: String,
+    connection_type: String,
+    connection: OwnedObjectPath,
+    devices: Vec<OwnedObjectPath>,
+}
+
+#[zbus::interface(name = "org.freedesktop.NetworkManager.Connection.Active")]
+impl MockActiveConnection {
+    #[zbus(property)]
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    #[zbus(property, name = "Type")]
+    fn connection_type(&self) -> String {
+        self.connection_type.clone()
+    }
+
+    #[zbus(property)]
+    fn connection(&self) -> OwnedObjectPath {
+        self.connection.clone()
+    }
+
+    #[zbus(property)]
+    fn devices(&self) -> Vec<OwnedObjectPath> {
+        self.devices.clone()
+    }
+}
+
+/// Mock of a device object, named `wlan<index>` after the connection it belongs to.
+struct MockDevice {
+    interface: String,
+    hw_address: String,
+    driver: String,
+}
+
+#[zbus::interface(name = "org.freedesktop.NetworkManager.Device")]
+impl MockDevice {
+    #[zbus(property)]
+    fn interface(&self) -> String {
+        self.interface.clone()
+    }
+
+    #[zbus(property)]
+    fn hw_address(&self) -> String {
+        self.hw_address.clone()
+    }
+
+    #[zbus(property)]
+    fn driver(&self) -> String {
+        self.driver.clone()
+    }
+}
+
+fn active_connection(index: u32, connection_type: &str) -> (String, MockActiveConnection) {
+    (
+        format!("{ROOT_PATH}/ActiveConnection/{index}"),
+        MockActiveConnection {
+            id: format!("Connection {index}"),
+            connection_type: connection_type.to_string(),
+            connection: path(&format!("{ROOT_PATH}/Settings/{index}")),
+            devices: vec![path(&format!("{ROOT_PATH}/Devices/{index}"))],
+        },
+    )
+}
+
+/// Starts a mock NetworkManager service on `bus`, returning its connection and recorded calls.
+fn serve(
+    bus: &PrivateBus,
+    active: Vec<(String, MockActiveConnection)>,
+    fail_activate: bool,
+) -> (Connection, Arc<Mutex<Vec<String>>>) {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let root = MockNetworkManager {
+        active_connections: active.iter().map(|(p, _)| path(p)).collect(),
+        calls: calls.clone(),
+        fail_activate,
+    };
+
+    let mut builder = Builder::address(bus.address.as_str())
+        .unwrap()
+        .name(SERVICE)
+        .unwrap()
+        .serve_at(ROOT_PATH, root)
+        .unwrap();
+    for (object_path, connection) in active {
+        let index = object_path.rsplit('/').next().unwrap().to_string();
+        let device = MockDevice {
+            interface: format!("wlan{index}"),
+            hw_address: format!("02:00:00:00:0{index}:00"),
+            driver: "iwlwifi".to_string(),
+        };
+        builder = builder
+            .serve_at(connection.devices[0].clone(), device)
+            .unwrap()
+            .serve_at(object_path, connection)
+            .unwrap();
+    }
+
+    (builder.build().unwrap(), calls)
+}
+
+#[test]
+fn reconnect_reactivates_active_wireless_connection() {
+    let bus = PrivateBus::start();
+    let (_service, calls) = serve(
+        &bus,
+        vec![
+            active_connection(1, "802-3-ethernet"),
+            active_connection(2, "802-11-wireless"),
+        ],
+        false,
+    );
+
+    let manager = DbusNetworkManager::with_connection(bus.connect());
+
+    assert_eq!(manager.reconnect(), Ok(()));
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![
+            format!("deactivate {ROOT_PATH}/ActiveConnection/2"),
+            format!("activate {ROOT_PATH}/Settings/2 {ROOT_PATH}/Devices/2 /"),
+        ]
+    );
+}
+
+#[test]
+fn reconnect_only_reactivates_selected_device() {
+    let bus = PrivateBus::start();
+    let (_service, calls) = serve(
+        &bus,
+        vec![
+            active_connection(1, "802-11-wireless"),
+            active_connection(2, "802-11-wireless"),
+        ],
+        false,
+    );
+
+    let manager = DbusNetworkManager::with_connection(bus.connect())
+        .with_interface("mac:02:00:00:00:02:00".parse().unwrap());
+
+    assert_eq!(manager.reconnect(), Ok(()));
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![
+            format!("deactivate {ROOT_PATH}/ActiveConnection/2"),
+            format!("activate {ROOT_PATH}/Settings/2 {ROOT_PATH}/Devices/2 /"),
+        ]
+    );
+}
+
+#[test]
+fn reconnect_reactivates_every_matching_device() {
+    let bus = PrivateBus::start();
+    let (_service, calls) = serve(
+        &bus,
+        vec![
+            active_connection(1, "802-11-wireless"),
+            active_connection(2, "802-11-wireless"),
+        ],
+        false,
+    );
+
+    let manager = DbusNetworkManager::with_connection(bus.connect()).with_interface(
+        "description:iwlwifi"
+            .parse::<InterfaceSelector>()
+            .unwrap()
+            .with_all(true),
+    );
+
+    assert_eq!(manager.reconnect(), Ok(()));
+    assert_eq!(calls.lock().unwrap().len(), 4);
+}
+
+#[test]
+fn reconnect_fails_when_selected_device_is_not_connected() {
+    let bus = PrivateBus::start();
+    let (_service, calls) = serve(&bus, vec![active_connection(1, "802-11-wireless")], false);
+
+    let manager = DbusNetworkManager::with_connection(bus.connect())
+        .with_interface(InterfaceSelector::name("wlan7"));
+
+    assert_eq!(manager.reconnect(), Err(Error::NotConnected));
+    assert!(calls.lock().unwrap().is_empty());
+}
+
+#[test]
+fn reconnect_fails_without_active_wireless_connection() {
+    let bus = PrivateBus::start();
+    let (_service, calls) = serve(&bus, vec![active_connection(1, "802-3-ethernet")], false);
+
+    let manager = DbusNetworkManager::with_connection(bus.connect());
+
+    assert_eq!(manager.reconnect(), Err(Error::NotConnected));
+    assert!(calls.lock().unwrap().is_empty());
+}
+
+#[test]
+fn reconnect_fails_when_activation_fails() {
+    let bus = PrivateBus::start();
+    let (_service, calls) = serve(&bus, vec![active_connection(1, "802-11-wireless")], true);
+
+    let manager = DbusNetworkManager::with_connection(bus.connect());
+
+    assert_eq!(
+        manager.reconnect(),
+        Err(Error::Backend {
+            step: Step::Connect,
+            code: Some("org.freedesktop.DBus.Error.Failed".to_string()),
+            message: "activation failed".to_string(),
+        })
+    );
+    assert_eq!(calls.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn reconnect_fails_when_service_is_missing() {
+    let bus = PrivateBus::start();
+
+    let manager = DbusNetworkManager::with_connection(bus.connect());
+    let error = manager.reconnect().unwrap_err();
+
+    assert_eq!(error.step(), Some(Step::FindInterface));
+    assert!(matches!(
+        error,
+        Error::Backend { code: Some(code), .. } if code == "org.freedesktop.DBus.Error.ServiceUnknown"
+    ));
+}
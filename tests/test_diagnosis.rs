@@ -0,0 +1,248 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::fs;
+use std::net::{SocketAddr, UdpSocket};
+use std::thread;
+use std::time::Duration;
+
+use common::{
+    ARP_HEADER, ROUTE_HEADER, add_interface, arp_entry, default_route, subnet_route, write_proc,
+};
+use internet_reloader::adapter::InterfaceSelector;
+use internet_reloader::diagnosis::{InterfaceAddress, LinuxDiagnostics, NetworkDiagnostics};
+use internet_reloader::internet_connectivity::DnsProbe;
+use tempfile::TempDir;
+
+/// Returns `cidr`, such as `192.168.2.5/24`, as an address of `iface`.
+fn address(iface: &str, cidr: &str) -> InterfaceAddress {
+    let (address, prefix_len) = cidr.split_once('/').unwrap();
+    InterfaceAddress {
+        interface: iface.to_string(),
+        address: address.parse().unwrap(),
+        prefix_len: prefix_len.parse().unwrap(),
+    }
+}
+
+/// A fixture with `wlan0` up, addressed and routed through a resolved gateway.
+fn healthy_root() -> TempDir {
+    let root = TempDir::new().unwrap();
+    add_interface(root.path(), "wlan0", "up", "1", "1");
+    write_proc(
+        root.path(),
+        "route",
+        Some(ROUTE_HEADER),
+        &[&default_route("wlan0", "0003"), &subnet_route("wlan0")],
+    );
+    write_proc(
+        root.path(),
+        "arp",
+        Some(ARP_HEADER),
+        &[&arp_entry("192.168.2.1", "0x2", "wlan0")],
+    );
+    root
+}
+
+/// Answers a single DNS query on loopback with an `A` record.
+fn answering_dns_server() -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = socket.local_addr().unwrap();
+    thread::spawn(move || {
+        let mut buffer = [0u8; 512];
+        let (len, peer) = socket.recv_from(&mut buffer).unwrap();
+        let mut response = buffer[..len].to_vec();
+        response[2..4].copy_from_slice(&0x8180u16.to_be_bytes());
+        response[7] = 1;
+        response.extend_from_slice(&[0xC0, 12, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 192, 0, 2, 10]);
+        socket.send_to(&response, peer).unwrap();
+    });
+    addr
+}
+
+#[test]
+fn healthy_layers_pass_with_evidence() {
+    let root = healthy_root();
+    let diagnostics = LinuxDiagnostics::with_root(root.path())
+        .with_addresses([address("wlan0", "192.168.2.5/24")]);
+
+    let link = diagnostics.check_link();
+    let address = diagnostics.check_address();
+    let gateway = diagnostics.check_gateway();
+
+    assert!(link.passed);
+    assert_eq!(link.evidence, "link up on wlan0");
+    assert!(address.passed);
+    assert_eq!(address.evidence, "wlan0 is on 192.168.2.5/24");
+    assert!(gateway.passed);
+    assert_eq!(
+        gateway.evidence,
+        "gateway 192.168.2.1 on wlan0 resolved to 02:fc:00:00:00:05"
+    );
+}
+
+#[test]
+fn link_fails_when_no_interface_is_up() {
+    let root = healthy_root();
+    fs::write(root.path().join("sys/class/net/wlan0/operstate"), "down\n").unwrap();
+
+    let check = LinuxDiagnostics::with_root(root.path()).check_link();
+
+    assert!(!check.passed);
+    assert_eq!(check.evidence, "no interface has a link");
+}
+
+#[test]
+fn link_is_scoped_to_the_selected_interface() {
+    let root = healthy_root();
+    add_interface(root.path(), "eth0", "down", "1", "1");
+
+    let check = LinuxDiagnostics::with_root(root.path())
+        .with_interface(InterfaceSelector::name("eth0"))
+        .check_link();
+
+    assert!(!check.passed);
+    assert_eq!(check.evidence, "no link on the interfaces selected by eth0");
+}
+
+#[test]
+fn address_fails_with_only_link_local_addresses() {
+    let root = healthy_root();
+
+    let check = LinuxDiagnostics::with_root(root.path())
+        .with_addresses([
+            address("wlan0", "169.254.12.7/16"),
+            address("wlan0", "fe80::1/64"),
+            address("eth0", "192.168.2.5/24"),
+        ])
+        .check_address();
+
+    assert!(!check.passed);
+    assert_eq!(check.evidence, "no address assigned on wlan0");
+}
+
+#[test]
+fn global_ipv6_address_counts_as_assigned() {
+    let root = healthy_root();
+
+    let check = LinuxDiagnostics::with_root(root.path())
+        .with_addresses([
+            address("wlan0", "fe80::1/64"),
+            address("wlan0", "2001:db8::2/64"),
+        ])
+        .check_address();
+
+    assert!(check.passed);
+    assert_eq!(check.evidence, "wlan0 is on 2001:db8::2/64");
+}
+
+#[test]
+fn addresses_are_listed_by_getifaddrs() {
+    let root = TempDir::new().unwrap();
+    add_interface(root.path(), "lo", "unknown", "1", "1");
+
+    let check = LinuxDiagnostics::with_root(root.path()).check_address();
+
+    assert!(check.passed, "{}", check.evidence);
+    assert!(
+        check.evidence.starts_with("lo is on 127.0.0.1/8"),
+        "{}",
+        check.evidence
+    );
+}
+
+#[test]
+fn gateway_fails_without_default_route() {
+    let root = healthy_root();
+    write_proc(
+        root.path(),
+        "route",
+        Some(ROUTE_HEADER),
+        &[&subnet_route("wlan0")],
+    );
+
+    let check = LinuxDiagnostics::with_root(root.path()).check_gateway();
+
+    assert!(!check.passed);
+    assert_eq!(check.evidence, "no default route");
+}
+
+#[test]
+fn gateway_fails_when_neighbour_is_incomplete() {
+    let root = healthy_root();
+    write_proc(
+        root.path(),
+        "arp",
+        Some(ARP_HEADER),
+        &[&arp_entry("192.168.2.1", "0x0", "wlan0")],
+    );
+
+    let check = LinuxDiagnostics::with_root(root.path())
+        .with_gateway_timeout(Duration::from_millis(100))
+        .check_gateway();
+
+    assert!(!check.passed);
+    assert!(
+        check.evidence.starts_with("gateway 192.168.2.1 on wlan0 "),
+        "{}",
+        check.evidence
+    );
+}
+
+#[test]
+fn ipv6_gateway_is_pinged_through_its_interface() {
+    let root = healthy_root();
+    add_interface(root.path(), "lo", "up", "1", "1");
+    write_proc(root.path(), "route", Some(ROUTE_HEADER), &[]);
+    write_proc(
+        root.path(),
+        "ipv6_route",
+        None,
+        &[
+            "00000000000000000000000000000000 00 00000000000000000000000000000000 00 00000000000000000000000000000001 00000400 00000001 00000000 00000003       lo",
+        ],
+    );
+
+    let check = LinuxDiagnostics::with_root(root.path()).check_gateway();
+
+    assert!(check.passed, "{}", check.evidence);
+    assert!(check.evidence.starts_with("gateway ::1 on lo replied in"));
+}
+
+#[test]
+fn dns_uses_resolv_conf_under_root() {
+    let root = healthy_root();
+
+    let check = LinuxDiagnostics::with_root(root.path()).check_dns();
+
+    assert!(!check.passed);
+    assert_eq!(check.evidence, "no DNS servers configured");
+}
+
+#[test]
+fn dns_passes_when_a_server_resolves() {
+    let server = answering_dns_server();
+    let diagnostics = LinuxDiagnostics::with_root(healthy_root().path())
+        .with_dns_probe(DnsProbe::with_servers([server]).with_timeout(Duration::from_secs(2)));
+
+    let check = diagnostics.check_dns();
+
+    assert!(check.passed, "{}", check.evidence);
+    assert!(check.evidence.starts_with(&format!("{server} resolved in")));
+}
+
+#[test]
+fn dns_fails_with_each_server_outcome() {
+    let unreachable = UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let diagnostics = LinuxDiagnostics::with_root(healthy_root().path()).with_dns_probe(
+        DnsProbe::with_servers([unreachable]).with_timeout(Duration::from_millis(100)),
+    );
+
+    let check = diagnostics.check_dns();
+
+    assert!(!check.passed);
+    assert!(check.evidence.starts_with(&format!("{unreachable}: ")));
+}
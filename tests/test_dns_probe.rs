@@ -0,0 +1,284 @@
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use internet_reloader::internet_connectivity::{
+    DnsOutcome, DnsProbe, DnsRecordType, DnsTransport, InternetConnectivity,
+};
+use tempfile::NamedTempFile;
+
+const ANSWER: Ipv4Addr = Ipv4Addr::new(93, 184, 216, 34);
+
+/// How the fake server responds over each transport.
+#[derive(Clone, Copy)]
+enum Behaviour {
+    Answer,
+    NxDomain,
+    TruncatedOverUdp,
+    SilentOverUdp,
+}
+
+/// A fake DNS server listening on UDP and TCP on the same loopback port.
+struct FakeDnsServer {
+    addr: SocketAddr,
+    queries: Arc<Mutex<Vec<(DnsTransport, String)>>>,
+    stop: Arc<AtomicBool>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl FakeDnsServer {
+    fn start(behaviour: Behaviour) -> Self {
+        let udp = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = udp.local_addr().unwrap();
+        let tcp = TcpListener::bind(addr).unwrap();
+        udp.set_read_timeout(Some(Duration::from_millis(20)))
+            .unwrap();
+        tcp.set_nonblocking(true).unwrap();
+
+        let queries = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let udp_thread = {
+            let (queries, stop) = (queries.clone(), stop.clone());
+            std::thread::spawn(move || {
+                let mut buffer = [0u8; 512];
+                while !stop.load(Ordering::Relaxed) {
+                    let Ok((len, peer)) = udp.recv_from(&mut buffer) else {
+                        continue;
+                    };
+                    let query = &buffer[..len];
+                    queries
+                        .lock()
+                        .unwrap()
+                        .push((DnsTransport::Udp, query_name(query)));
+                    let response = match behaviour {
+                        Behaviour::Answer => response(query, 0, false),
+                        Behaviour::NxDomain => response(query, 3, false),
+                        Behaviour::TruncatedOverUdp => response(query, 0, true),
+                        Behaviour::SilentOverUdp => continue,
+                    };
+                    udp.send_to(&response, peer).unwrap();
+                }
+            })
+        };
+
+        let tcp_thread = {
+            let (queries, stop) = (queries.clone(), stop.clone());
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let Ok((mut stream, _)) = tcp.accept() else {
+                        std::thread::sleep(Duration::from_millis(5));
+                        continue;
+                    };
+                    stream.set_nonblocking(false).unwrap();
+                    let mut length = [0u8; 2];
+                    stream.read_exact(&mut length).unwrap();
+                    let mut query = vec![0u8; u16::from_be_bytes(length) as usize];
+                    stream.read_exact(&mut query).unwrap();
+                    queries
+                        .lock()
+                        .unwrap()
+                        .push((DnsTransport::Tcp, query_name(&query)));
+
+                    let response = response(&query, 0, false);
+                    stream
+                        .write_all(&(response.len() as u16).to_be_bytes())
+                        .unwrap();
+                    stream.write_all(&response).unwrap();
+                }
+            })
+        };
+
+        Self {
+            addr,
+            queries,
+            stop,
+            threads: vec![udp_thread, tcp_thread],
+        }
+    }
+
+    fn queries(&self) -> Vec<(DnsTransport, String)> {
+        self.queries.lock().unwrap().clone()
+    }
+}
+
+impl Drop for FakeDnsServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        for thread in self.threads.drain(..) {
+            thread.join().unwrap();
+        }
+    }
+}
+
+/// Returns the dotted name of the first question in `query`.
+fn query_name(query: &[u8]) -> String {
+    let mut labels = Vec::new();
+    let mut offset = 12;
+    while query[offset] != 0 {
+        let len = query[offset] as usize;
+        labels.push(String::from_utf8_lossy(&query[offset + 1..offset + 1 + len]).into_owned());
+        offset += 1 + len;
+    }
+    labels.join(".")
+}
+
+/// Builds a response to `query` with a single compressed `A` answer, unless `rcode` is an error.
+fn response(query: &[u8], rcode: u8, truncated: bool) -> Vec<u8> {
+    let question_end = query.len();
+    let answers: u16 = if rcode == 0 && !truncated { 1 } else { 0 };
+
+    let mut message = query[..2].to_vec();
+    let flags = 0x8180 | rcode as u16 | if truncated { 0x0200 } else { 0 };
+    message.extend_from_slice(&flags.to_be_bytes());
+    message.extend_from_slice(&[0, 1]);
+    message.extend_from_slice(&answers.to_be_bytes());
+    message.extend_from_slice(&[0, 0, 0, 0]);
+    message.extend_from_slice(&query[12..question_end]);
+
+    if answers == 1 {
+        // An unrelated CNAME-typed record first, to check non-address records are skipped.
+        message.extend_from_slice(&[0xC0, 12, 0, 5, 0, 1, 0, 0, 0, 60, 0, 2, 0xC0, 12]);
+        message[7] = 2;
+        message.extend_from_slice(&[0xC0, 12, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4]);
+        message.extend_from_slice(&ANSWER.octets());
+    }
+    message
+}
+
+#[test]
+fn resolves_over_udp() {
+    let server = FakeDnsServer::start(Behaviour::Answer);
+    let probe = DnsProbe::with_servers([server.addr]).with_query("example.com");
+
+    let results = probe.probe();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].server, server.addr);
+    assert_eq!(results[0].transport, Some(DnsTransport::Udp));
+    assert_eq!(
+        results[0].outcome,
+        DnsOutcome::Resolved(vec![IpAddr::V4(ANSWER)])
+    );
+    assert_eq!(
+        server.queries(),
+        vec![(DnsTransport::Udp, "example.com".to_string())]
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn queries_through_the_selected_device() {
+    let server = FakeDnsServer::start(Behaviour::TruncatedOverUdp);
+
+    let loopback = DnsProbe::with_servers([server.addr]).with_device("lo");
+    let missing = DnsProbe::with_servers([server.addr])
+        .with_device("missing0")
+        .with_timeout(Duration::from_millis(100));
+
+    assert!(loopback.is_connected_to_internet());
+    assert!(!missing.is_connected_to_internet());
+}
+
+#[test]
+fn falls_back_to_tcp_when_truncated() {
+    let server = FakeDnsServer::start(Behaviour::TruncatedOverUdp);
+    let probe = DnsProbe::with_servers([server.addr]);
+
+    let results = probe.probe();
+
+    assert_eq!(results[0].transport, Some(DnsTransport::Tcp));
+    assert!(results[0].is_success());
+    assert_eq!(server.queries().len(), 2);
+}
+
+#[test]
+fn falls_back_to_tcp_when_udp_times_out() {
+    let server = FakeDnsServer::start(Behaviour::SilentOverUdp);
+    let probe = DnsProbe::with_servers([server.addr]).with_timeout(Duration::from_millis(200));
+
+    let results = probe.probe();
+
+    assert_eq!(results[0].transport, Some(DnsTransport::Tcp));
+    assert!(results[0].is_success());
+}
+
+#[test]
+fn reports_response_code_on_nxdomain() {
+    let server = FakeDnsServer::start(Behaviour::NxDomain);
+    let probe = DnsProbe::with_servers([server.addr]);
+
+    let results = probe.probe();
+
+    assert_eq!(results[0].outcome, DnsOutcome::ResponseCode(3));
+    assert!(!probe.is_connected_to_internet());
+}
+
+#[test]
+fn reports_per_server_results() {
+    let failing = FakeDnsServer::start(Behaviour::NxDomain);
+    let working = FakeDnsServer::start(Behaviour::Answer);
+    let probe =
+        DnsProbe::with_servers([failing.addr, working.addr]).with_record_type(DnsRecordType::A);
+
+    let results = probe.probe();
+
+    assert!(!results[0].is_success());
+    assert!(results[1].is_success());
+    assert!(probe.is_connected_to_internet());
+}
+
+#[test]
+fn fails_when_server_is_unreachable() {
+    let addr = FakeDnsServer::start(Behaviour::Answer).addr;
+    let probe = DnsProbe::with_servers([addr]).with_timeout(Duration::from_millis(100));
+
+    let results = probe.probe();
+
+    assert_eq!(results[0].transport, None);
+    assert!(matches!(results[0].outcome, DnsOutcome::Failed(_)));
+    assert!(!probe.is_connected_to_internet());
+}
+
+#[test]
+fn reads_servers_from_resolv_conf() {
+    let mut resolv_conf = NamedTempFile::new().unwrap();
+    writeln!(
+        resolv_conf,
+        "# Generated by NetworkManager\nsearch lan\nnameserver 192.168.43.1\nnameserver fe80::1%2\nnameserver fe80::2%missing0\noptions edns0"
+    )
+    .unwrap();
+
+    let probe = DnsProbe::with_resolv_conf(resolv_conf.path());
+
+    assert_eq!(
+        probe.servers(),
+        vec![
+            "192.168.43.1:53".parse::<SocketAddr>().unwrap(),
+            "[fe80::1%2]:53".parse::<SocketAddr>().unwrap(),
+        ]
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn resolv_conf_zones_are_resolved_to_interfaces() {
+    let mut resolv_conf = NamedTempFile::new().unwrap();
+    writeln!(resolv_conf, "nameserver fe80::1%lo").unwrap();
+
+    let servers = DnsProbe::with_resolv_conf(resolv_conf.path()).servers();
+
+    match servers.as_slice() {
+        [SocketAddr::V6(server)] => {
+            assert_eq!(
+                server.ip(),
+                &"fe80::1".parse::<std::net::Ipv6Addr>().unwrap()
+            );
+            assert_ne!(server.scope_id(), 0);
+        }
+        servers => panic!("unexpected servers {servers:?}"),
+    }
+}
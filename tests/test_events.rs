@@ -0,0 +1,325 @@
+use mockall::mock;
+
+use std::thread;
+use std::time::Duration;
+
+use internet_reloader::app::{NetworkApp, NetworkStatus, RecoveryVerification, VerificationResult};
+use internet_reloader::error::{Error, Result};
+use internet_reloader::events::{ChannelSubscriber, EventSubscriber, NetworkEvent, ProbeKind};
+use internet_reloader::internet_connectivity::{CaptivePortal, InternetConnectivity};
+use internet_reloader::network_manager::NetworkManager;
+use internet_reloader::remediation::RemediationAttempt;
+
+mock! {
+    NetworkManager {}
+    impl NetworkManager for NetworkManager {
+        fn reconnect(&self) -> Result<()>;
+    }
+}
+
+mock! {
+    InternetConnectivity {}
+    impl InternetConnectivity for InternetConnectivity {
+        fn is_connected_to_network(&self) -> bool;
+        fn is_connected_to_internet(&self) -> bool;
+        fn captive_portal(&self) -> Option<CaptivePortal>;
+    }
+}
+
+mock! {
+    EventSubscriber {}
+    impl EventSubscriber for EventSubscriber {
+        fn on_event(&self, event: &NetworkEvent);
+    }
+}
+
+/// Verification which probes once, without waiting.
+const NO_WAIT_VERIFICATION: RecoveryVerification = RecoveryVerification {
+    timeout: Duration::ZERO,
+    interval: Duration::ZERO,
+};
+
+fn checker(network: bool, internet: bool) -> MockInternetConnectivity {
+    let mut checker = MockInternetConnectivity::new();
+    checker
+        .expect_is_connected_to_network()
+        .return_const(network);
+    checker
+        .expect_is_connected_to_internet()
+        .return_const(internet);
+    checker.expect_captive_portal().return_const(None);
+    checker
+}
+
+fn probe(probe: ProbeKind, passed: bool) -> NetworkEvent {
+    NetworkEvent::ProbeResult { probe, passed }
+}
+
+#[test]
+fn poll_reports_each_probe_and_the_first_status() {
+    let (subscriber, events) = ChannelSubscriber::channel();
+    let app =
+        NetworkApp::new(checker(true, true), MockNetworkManager::new()).with_subscriber(subscriber);
+
+    app.poll();
+    app.poll();
+
+    let events: Vec<NetworkEvent> = events.try_iter().collect();
+    assert_eq!(
+        events,
+        vec![
+            NetworkEvent::PollStarted,
+            probe(ProbeKind::Network, true),
+            probe(ProbeKind::CaptivePortal, true),
+            probe(ProbeKind::Internet, true),
+            NetworkEvent::StatusChanged {
+                from: None,
+                to: NetworkStatus::Connected,
+            },
+            NetworkEvent::PollStarted,
+            probe(ProbeKind::Network, true),
+            probe(ProbeKind::CaptivePortal, true),
+            probe(ProbeKind::Internet, true),
+        ]
+    );
+}
+
+#[test]
+fn status_change_reports_previous_status() {
+    let mut checker = MockInternetConnectivity::new();
+    let mut network = [true, false].into_iter();
+    checker
+        .expect_is_connected_to_network()
+        .returning(move || network.next().unwrap());
+    checker.expect_is_connected_to_internet().return_const(true);
+    checker.expect_captive_portal().return_const(None);
+
+    let (subscriber, events) = ChannelSubscriber::channel();
+    let app = NetworkApp::new(checker, MockNetworkManager::new()).with_subscriber(subscriber);
+    app.poll();
+    app.poll();
+
+    let changes: Vec<NetworkEvent> = events
+        .try_iter()
+        .filter(|event| matches!(event, NetworkEvent::StatusChanged { .. }))
+        .collect();
+    assert_eq!(
+        changes[1],
+        NetworkEvent::StatusChanged {
+            from: Some(NetworkStatus::Connected),
+            to: NetworkStatus::Disconnected,
+        }
+    );
+    assert_eq!(
+        changes[1].to_string(),
+        "status changed from Connected to Disconnected"
+    );
+}
+
+#[test]
+fn resumed_app_does_not_report_the_outage_again() {
+    let mut manager = MockNetworkManager::new();
+    manager
+        .expect_reconnect()
+        .return_const(Err(Error::NotConnected));
+    let (subscriber, events) = ChannelSubscriber::channel();
+    let previous = NetworkApp::new(checker(true, false), manager).with_subscriber(subscriber);
+    previous.poll();
+
+    let mut manager = MockNetworkManager::new();
+    manager
+        .expect_reconnect()
+        .return_const(Err(Error::NotConnected));
+    let app = NetworkApp::new(checker(true, false), manager).resume(previous);
+    app.poll();
+
+    let changes = events
+        .try_iter()
+        .filter(|event| matches!(event, NetworkEvent::StatusChanged { .. }))
+        .count();
+    assert_eq!(changes, 1);
+}
+
+#[test]
+fn remediation_is_reported_before_and_after_it_runs() {
+    let mut manager = MockNetworkManager::new();
+    manager.expect_reconnect().return_const(Ok(()));
+
+    let (subscriber, events) = ChannelSubscriber::channel();
+    let app = NetworkApp::new(checker(true, false), manager)
+        .with_recovery_verification(NO_WAIT_VERIFICATION)
+        .with_subscriber(subscriber);
+    app.poll();
+
+    let events: Vec<NetworkEvent> = events.try_iter().collect();
+    assert_eq!(events[3], probe(ProbeKind::Internet, false));
+    assert_eq!(
+        events[4],
+        NetworkEvent::StatusChanged {
+            from: None,
+            to: NetworkStatus::NetworkOnly,
+        }
+    );
+    assert_eq!(
+        events[5],
+        NetworkEvent::RemediationAttempted {
+            rung: 0,
+            action: "reconnect".to_string(),
+        }
+    );
+    let NetworkEvent::RemediationResult {
+        attempt,
+        verification: Some(verification),
+    } = &events[6]
+    else {
+        panic!("expected a verified remediation, got {:?}", events[6]);
+    };
+    assert!(attempt.is_success());
+    assert!(!verification.recovered);
+    assert_eq!(events.len(), 7);
+}
+
+#[test]
+fn recovery_by_remediation_is_reported_after_the_outage() {
+    let mut checker = MockInternetConnectivity::new();
+    let mut internet = [false, true].into_iter();
+    checker.expect_is_connected_to_network().return_const(true);
+    checker
+        .expect_is_connected_to_internet()
+        .returning(move || internet.next().unwrap());
+    checker.expect_captive_portal().return_const(None);
+    let mut manager = MockNetworkManager::new();
+    manager.expect_reconnect().return_const(Ok(()));
+
+    let (subscriber, events) = ChannelSubscriber::channel();
+    let app = NetworkApp::new(checker, manager)
+        .with_recovery_verification(NO_WAIT_VERIFICATION)
+        .with_subscriber(subscriber);
+    app.poll();
+
+    let changes: Vec<NetworkEvent> = events
+        .try_iter()
+        .filter(|event| matches!(event, NetworkEvent::StatusChanged { .. }))
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            NetworkEvent::StatusChanged {
+                from: None,
+                to: NetworkStatus::NetworkOnly,
+            },
+            NetworkEvent::StatusChanged {
+                from: Some(NetworkStatus::NetworkOnly),
+                to: NetworkStatus::Connected,
+            },
+        ]
+    );
+}
+
+#[test]
+fn failed_remediation_is_reported_without_verification() {
+    let mut manager = MockNetworkManager::new();
+    manager
+        .expect_reconnect()
+        .return_const(Err(Error::NotConnected));
+
+    let (subscriber, events) = ChannelSubscriber::channel();
+    let app = NetworkApp::new(checker(true, false), manager).with_subscriber(subscriber);
+    app.poll();
+
+    let result = events
+        .try_iter()
+        .find(|event| matches!(event, NetworkEvent::RemediationResult { .. }))
+        .unwrap();
+    assert_eq!(
+        result,
+        NetworkEvent::RemediationResult {
+            attempt: RemediationAttempt {
+                rung: 0,
+                action: "reconnect".to_string(),
+                result: Err(Error::NotConnected),
+            },
+            verification: None,
+        }
+    );
+}
+
+#[test]
+fn every_subscriber_receives_every_event() {
+    let mut first = MockEventSubscriber::new();
+    first.expect_on_event().times(5).return_const(());
+    let (second, events) = ChannelSubscriber::channel();
+
+    let app = NetworkApp::new(checker(true, true), MockNetworkManager::new())
+        .with_subscriber(first)
+        .with_subscriber(second);
+    app.poll();
+
+    assert_eq!(events.try_iter().count(), 5);
+}
+
+#[test]
+fn channel_subscriber_forwards_events_to_another_thread() {
+    let (subscriber, events) = ChannelSubscriber::channel();
+    let consumer = thread::spawn(move || events.iter().collect::<Vec<_>>());
+
+    {
+        let app = NetworkApp::new(checker(false, false), MockNetworkManager::new())
+            .with_subscriber(subscriber);
+        app.poll();
+    }
+
+    let events = consumer.join().unwrap();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[1].to_string(), "network check failed");
+}
+
+#[test]
+fn channel_subscriber_ignores_dropped_receiver() {
+    let (subscriber, events) = ChannelSubscriber::channel();
+    drop(events);
+
+    subscriber.on_event(&NetworkEvent::PollStarted);
+}
+
+#[test]
+fn remediation_results_are_described() {
+    let attempt = |result| RemediationAttempt {
+        rung: 1,
+        action: "renew-dhcp wlan0".to_string(),
+        result,
+    };
+    let verification = |recovered| {
+        Some(VerificationResult {
+            recovered,
+            elapsed: Duration::from_secs(4),
+            probes: 2,
+        })
+    };
+
+    let restored = NetworkEvent::RemediationResult {
+        attempt: attempt(Ok(())),
+        verification: verification(true),
+    };
+    let unrestored = NetworkEvent::RemediationResult {
+        attempt: attempt(Ok(())),
+        verification: verification(false),
+    };
+
+    assert_eq!(
+        restored.to_string(),
+        "renew-dhcp wlan0 restored the internet in 4s"
+    );
+    assert_eq!(
+        unrestored.to_string(),
+        "renew-dhcp wlan0 succeeded, but the internet did not return"
+    );
+    assert_eq!(
+        NetworkEvent::RemediationAttempted {
+            rung: 1,
+            action: "renew-dhcp wlan0".to_string(),
+        }
+        .to_string(),
+        "attempting renew-dhcp wlan0 (rung 1)"
+    );
+}
@@ -0,0 +1,222 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::net::IpAddr;
+use std::time::Duration;
+
+use common::{ARP_HEADER, ROUTE_HEADER, arp_entry, ipv6_default_route, route, write_proc};
+use internet_reloader::adapter::InterfaceSelector;
+use internet_reloader::internet_connectivity::{Gateway, GatewayProbe, InternetConnectivity};
+use tempfile::TempDir;
+
+/// A checker reporting fixed results.
+struct Fixed {
+    network: bool,
+    internet: bool,
+}
+
+impl InternetConnectivity for Fixed {
+    fn is_connected_to_network(&self) -> bool {
+        self.network
+    }
+
+    fn is_connected_to_internet(&self) -> bool {
+        self.internet
+    }
+}
+
+const CONNECTED: Fixed = Fixed {
+    network: true,
+    internet: false,
+};
+
+/// A fixture whose only default route leaves `iface` through `gateway`, given in little-endian hex.
+fn root_with_gateway(iface: &str, gateway: &str) -> TempDir {
+    let root = TempDir::new().unwrap();
+    write_proc(
+        root.path(),
+        "route",
+        Some(ROUTE_HEADER),
+        &[&route(iface, "00000000", gateway, "0003", "00000000")],
+    );
+    root
+}
+
+fn gateway(iface: &str, address: &str) -> Gateway {
+    Gateway {
+        interface: iface.to_string(),
+        address: address.parse().unwrap(),
+    }
+}
+
+#[test]
+fn reads_default_gateways_from_both_routing_tables() {
+    let root = TempDir::new().unwrap();
+    write_proc(
+        root.path(),
+        "route",
+        Some(ROUTE_HEADER),
+        &[
+            &route("wlan0", "0002A8C0", "00000000", "0001", "00FFFFFF"),
+            &route("wlan0", "00000000", "0102A8C0", "0003", "00000000"),
+            &route("eth0", "00000000", "0101A8C0", "0002", "00000000"),
+        ],
+    );
+    write_proc(
+        root.path(),
+        "ipv6_route",
+        None,
+        &[
+            &ipv6_default_route("wlan0", "fe800000000000000000000000000001", "00000003"),
+            &ipv6_default_route("wlan0", "00000000000000000000000000000000", "00000001"),
+        ],
+    );
+
+    let probe = GatewayProbe::new(CONNECTED).with_root(root.path());
+
+    assert_eq!(
+        probe.gateways(),
+        vec![gateway("wlan0", "192.168.2.1"), gateway("wlan0", "fe80::1")]
+    );
+}
+
+#[test]
+fn only_selected_interfaces_are_considered() {
+    let root = TempDir::new().unwrap();
+    write_proc(
+        root.path(),
+        "route",
+        Some(ROUTE_HEADER),
+        &[
+            &route("wlan0", "00000000", "0102A8C0", "0003", "00000000"),
+            &route("eth0", "00000000", "0101A8C0", "0003", "00000000"),
+        ],
+    );
+
+    let probe = GatewayProbe::new(CONNECTED)
+        .with_root(root.path())
+        .with_interface(InterfaceSelector::name("eth0"));
+
+    assert_eq!(probe.gateways(), vec![gateway("eth0", "192.168.1.1")]);
+}
+
+#[test]
+fn neighbour_must_be_complete_and_on_the_gateway_interface() {
+    let root = TempDir::new().unwrap();
+    write_proc(
+        root.path(),
+        "arp",
+        Some(ARP_HEADER),
+        &[
+            &arp_entry("192.168.2.1", "0x2", "eth0"),
+            &arp_entry("192.168.1.1", "0x0", "wlan0"),
+            &arp_entry("192.168.3.1", "0x2", "wlan0"),
+        ],
+    );
+
+    let probe = GatewayProbe::new(CONNECTED).with_root(root.path());
+
+    assert_eq!(probe.neighbour(&gateway("wlan0", "192.168.2.1")), None);
+    assert_eq!(probe.neighbour(&gateway("wlan0", "192.168.1.1")), None);
+    assert_eq!(
+        probe.neighbour(&gateway("wlan0", "192.168.3.1")),
+        Some("02:fc:00:00:00:05".to_string())
+    );
+    assert_eq!(probe.neighbour(&gateway("wlan0", "fe80::1")), None);
+}
+
+#[test]
+fn gateway_replying_to_pings_is_reachable() {
+    let root = root_with_gateway("lo", "0100007F");
+    let probe = GatewayProbe::new(CONNECTED).with_root(root.path());
+
+    let results = probe.probe();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].gateway, gateway("lo", "127.0.0.1"));
+    assert!(results[0].is_reachable(), "{:?}", results[0].ping);
+    assert!(results[0].rtt().is_some());
+    assert!(probe.is_connected_to_network());
+}
+
+#[test]
+fn silent_gateway_is_unreachable() {
+    // 198.51.100.1 is reserved for documentation, so nothing answers through the loopback.
+    let root = root_with_gateway("lo", "016433C6");
+    let probe = GatewayProbe::new(CONNECTED)
+        .with_root(root.path())
+        .with_count(1)
+        .with_timeout(Duration::from_millis(100));
+
+    let result = probe.check(&probe.gateways()[0]);
+
+    assert_eq!(
+        result.gateway.address,
+        "198.51.100.1".parse::<IpAddr>().unwrap()
+    );
+    assert!(result.ping.socket.is_some());
+    assert!(!result.is_reachable());
+    assert!(!probe.is_connected_to_network());
+}
+
+#[test]
+fn resolved_neighbour_counts_when_gateway_ignores_echo_requests() {
+    let root = root_with_gateway("lo", "016433C6");
+    write_proc(
+        root.path(),
+        "arp",
+        Some(ARP_HEADER),
+        &[&arp_entry("198.51.100.1", "0x2", "lo")],
+    );
+    let probe = GatewayProbe::new(CONNECTED)
+        .with_root(root.path())
+        .with_count(1)
+        .with_timeout(Duration::from_millis(100));
+
+    let result = probe.check(&probe.gateways()[0]);
+
+    assert!(result.ping.socket.is_some());
+    assert!(!result.ping.is_success());
+    assert_eq!(result.neighbour.as_deref(), Some("02:fc:00:00:00:05"));
+    assert!(result.is_reachable());
+    assert!(probe.is_connected_to_network());
+}
+
+#[test]
+fn resolved_neighbour_counts_when_no_icmp_socket_can_be_opened() {
+    // Binding to an interface which does not exist fails, as ICMP sockets being denied would.
+    let root = root_with_gateway("missing0", "0102A8C0");
+    let probe = GatewayProbe::new(CONNECTED).with_root(root.path());
+    let gateway = gateway("missing0", "192.168.2.1");
+
+    let unresolved = probe.check(&gateway);
+    write_proc(
+        root.path(),
+        "arp",
+        Some(ARP_HEADER),
+        &[&arp_entry("192.168.2.1", "0x2", "missing0")],
+    );
+    let resolved = probe.check(&gateway);
+
+    assert_eq!(unresolved.ping.socket, None);
+    assert!(unresolved.ping.error.is_some());
+    assert!(!unresolved.is_reachable());
+    assert!(resolved.is_reachable());
+    assert!(probe.is_connected_to_network());
+}
+
+#[test]
+fn network_requires_inner_network_and_a_default_route() {
+    let root = root_with_gateway("lo", "0100007F");
+    let disconnected = GatewayProbe::new(Fixed {
+        network: false,
+        internet: true,
+    })
+    .with_root(root.path());
+    let no_route = GatewayProbe::new(CONNECTED).with_root(TempDir::new().unwrap().path());
+
+    assert!(!disconnected.is_connected_to_network());
+    assert!(disconnected.is_connected_to_internet());
+    assert!(!no_route.is_connected_to_network());
+}
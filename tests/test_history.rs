@@ -0,0 +1,350 @@
+use std::fs;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use internet_reloader::app::NetworkApp;
+use internet_reloader::diagnosis::{Layer, LayerCheck, NetworkDiagnostics};
+use internet_reloader::error::{Error, Result};
+use internet_reloader::history::{
+    HistoryEvent, HistoryLog, HistoryRecord, HistoryRecorder, outages,
+};
+use internet_reloader::internet_connectivity::{CaptivePortal, InternetConnectivity};
+use internet_reloader::network_manager::NetworkManager;
+use mockall::mock;
+use tempfile::TempDir;
+
+mock! {
+    NetworkManager {}
+    impl NetworkManager for NetworkManager {
+        fn reconnect(&self) -> Result<()>;
+    }
+}
+
+mock! {
+    InternetConnectivity {}
+    impl InternetConnectivity for InternetConnectivity {
+        fn is_connected_to_network(&self) -> bool;
+        fn is_connected_to_internet(&self) -> bool;
+        fn captive_portal(&self) -> Option<CaptivePortal>;
+    }
+}
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+/// A status change while monitoring, from a healthy status to an outage or the other way round.
+fn status(timestamp: u64, to: &str, outage: bool) -> HistoryRecord {
+    let from = match outage {
+        true => "Connected",
+        false => "NetworkOnly",
+    };
+    HistoryRecord {
+        timestamp,
+        event: HistoryEvent::StatusChanged {
+            from: Some(from.to_string()),
+            to: to.to_string(),
+            outage,
+            evidence: vec![format!("checked at {timestamp}")],
+        },
+    }
+}
+
+fn remediation(timestamp: u64, action: &str) -> HistoryRecord {
+    HistoryRecord {
+        timestamp,
+        event: HistoryEvent::Remediation {
+            rung: 0,
+            action: action.to_string(),
+            error: None,
+            recovered: Some(false),
+            verification_secs: Some(30),
+        },
+    }
+}
+
+mock! {
+    NetworkDiagnostics {}
+    impl NetworkDiagnostics for NetworkDiagnostics {
+        fn check_link(&self) -> LayerCheck;
+        fn check_address(&self) -> LayerCheck;
+        fn check_gateway(&self) -> LayerCheck;
+        fn check_dns(&self) -> LayerCheck;
+    }
+}
+
+/// A log in a new directory, with the outages 100-160 and 1000-1300 recorded.
+fn log_with_outages(dir: &TempDir) -> HistoryLog {
+    let log = HistoryLog::new(dir.path().join("history.jsonl"));
+    for record in [
+        status(10, "Connected", false),
+        status(100, "NetworkOnly", true),
+        status(160, "Connected", false),
+        status(1000, "Disconnected", true),
+        status(1300, "Degraded", false),
+    ] {
+        log.append(&record).unwrap();
+    }
+    log
+}
+
+#[test]
+fn recorder_appends_status_changes_and_remediations_with_evidence() {
+    let dir = TempDir::new().unwrap();
+    let log = HistoryLog::new(dir.path().join("state/history.jsonl"));
+
+    let mut checker = MockInternetConnectivity::new();
+    let mut internet = [false, true].into_iter();
+    checker.expect_is_connected_to_network().return_const(true);
+    checker
+        .expect_is_connected_to_internet()
+        .returning(move || internet.next().unwrap());
+    checker.expect_captive_portal().return_const(None);
+    let mut manager = MockNetworkManager::new();
+    manager
+        .expect_reconnect()
+        .return_const(Err(Error::NotConnected));
+
+    let app = NetworkApp::new(checker, manager).with_subscriber(HistoryRecorder::new(log.clone()));
+    app.poll();
+    app.poll();
+
+    let events: Vec<HistoryEvent> = log
+        .records()
+        .unwrap()
+        .into_iter()
+        .map(|record| record.event)
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            HistoryEvent::StatusChanged {
+                from: None,
+                to: "NetworkOnly".to_string(),
+                outage: true,
+                evidence: vec![
+                    "network check passed".to_string(),
+                    "captive portal check passed".to_string(),
+                    "internet check failed".to_string(),
+                ],
+            },
+            HistoryEvent::Remediation {
+                rung: 0,
+                action: "reconnect".to_string(),
+                error: Some("no active wireless connection".to_string()),
+                recovered: None,
+                verification_secs: None,
+            },
+            HistoryEvent::StatusChanged {
+                from: Some("NetworkOnly".to_string()),
+                to: "Connected".to_string(),
+                outage: false,
+                evidence: vec![
+                    "network check passed".to_string(),
+                    "captive portal check passed".to_string(),
+                    "internet check passed".to_string(),
+                ],
+            },
+        ]
+    );
+}
+
+#[test]
+fn recorder_appends_the_diagnosis_of_an_outage() {
+    let dir = TempDir::new().unwrap();
+    let log = HistoryLog::new(dir.path().join("history.jsonl"));
+
+    let mut checker = MockInternetConnectivity::new();
+    checker.expect_is_connected_to_network().return_const(true);
+    checker
+        .expect_is_connected_to_internet()
+        .return_const(false);
+    checker.expect_captive_portal().return_const(None);
+    let mut diagnostics = MockNetworkDiagnostics::new();
+    diagnostics
+        .expect_check_link()
+        .returning(|| LayerCheck::passed("wlan0 is up"));
+    diagnostics
+        .expect_check_address()
+        .returning(|| LayerCheck::passed("wlan0 has 192.168.1.20"));
+    diagnostics
+        .expect_check_gateway()
+        .returning(|| LayerCheck::passed("192.168.1.1 reachable"));
+    diagnostics
+        .expect_check_dns()
+        .returning(|| LayerCheck::failed("no server replied"));
+    let mut manager = MockNetworkManager::new();
+    manager
+        .expect_reconnect()
+        .return_const(Err(Error::NotConnected));
+
+    let app = NetworkApp::new(checker, manager)
+        .with_diagnostics(diagnostics)
+        .with_subscriber(HistoryRecorder::new(log.clone()));
+    app.poll();
+    app.poll();
+
+    let records = log.records().unwrap();
+    let diagnoses: Vec<&HistoryEvent> = records
+        .iter()
+        .map(|record| &record.event)
+        .filter(|event| matches!(event, HistoryEvent::Diagnosis { .. }))
+        .collect();
+    assert_eq!(
+        diagnoses,
+        vec![&HistoryEvent::Diagnosis {
+            failed_layer: Some(Layer::Dns),
+            evidence: vec![
+                "link passed: wlan0 is up".to_string(),
+                "address passed: wlan0 has 192.168.1.20".to_string(),
+                "gateway passed: 192.168.1.1 reachable".to_string(),
+                "dns failed: no server replied".to_string(),
+            ],
+        }]
+    );
+
+    let outages = outages(&records);
+    assert_eq!(outages.len(), 1);
+    assert_eq!(outages[0].failed_layer, Some(Layer::Dns));
+    assert_eq!(
+        outages[0].evidence.last(),
+        Some(&"dns failed: no server replied".to_string())
+    );
+    assert_eq!(outages[0].remediations.len(), 2);
+}
+
+#[test]
+fn records_are_appended_as_json_lines() {
+    let dir = TempDir::new().unwrap();
+    let log = HistoryLog::new(dir.path().join("history.jsonl"));
+
+    log.append(&status(100, "NetworkOnly", true)).unwrap();
+    log.append(&remediation(130, "renew-dhcp wlan0")).unwrap();
+
+    let contents = fs::read_to_string(log.path()).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(
+        lines[0],
+        r#"{"timestamp":100,"event":"status_changed","from":"Connected","to":"NetworkOnly","outage":true,"evidence":["checked at 100"]}"#
+    );
+    assert_eq!(
+        lines[1],
+        r#"{"timestamp":130,"event":"remediation","rung":0,"action":"renew-dhcp wlan0","error":null,"recovered":false,"verification_secs":30}"#
+    );
+}
+
+#[test]
+fn missing_log_is_empty_and_invalid_lines_are_skipped() {
+    let dir = TempDir::new().unwrap();
+    let log = HistoryLog::new(dir.path().join("history.jsonl"));
+    assert_eq!(log.records().unwrap(), Vec::new());
+
+    log.append(&status(100, "NetworkOnly", true)).unwrap();
+    let mut contents = fs::read_to_string(log.path()).unwrap();
+    contents.push_str("{\"timestamp\":12");
+    fs::write(log.path(), contents).unwrap();
+    log.append(&status(160, "Connected", false)).unwrap();
+
+    let timestamps: Vec<u64> = log
+        .records()
+        .unwrap()
+        .iter()
+        .map(|record| record.timestamp)
+        .collect();
+    assert_eq!(timestamps, vec![100]);
+}
+
+#[test]
+fn outages_span_from_the_first_outage_status_to_the_next_healthy_one() {
+    let records = [
+        status(100, "NetworkOnly", true),
+        remediation(110, "reconnect"),
+        status(120, "Disconnected", true),
+        remediation(130, "renew-dhcp wlan0"),
+        status(160, "Connected", false),
+        remediation(200, "reconnect"),
+        status(300, "CaptivePortal", true),
+    ];
+
+    let outages = outages(&records);
+
+    assert_eq!(outages.len(), 2);
+    assert_eq!(outages[0].started, at(100));
+    assert_eq!(outages[0].ended, Some(at(160)));
+    assert_eq!(outages[0].status, "NetworkOnly");
+    assert_eq!(outages[0].evidence, vec!["checked at 100".to_string()]);
+    assert_eq!(
+        outages[0].remediations,
+        vec![records[1].clone(), records[3].clone()]
+    );
+    assert_eq!(outages[0].duration(at(1000)), Duration::from_secs(60));
+
+    assert_eq!(outages[1].status, "CaptivePortal");
+    assert_eq!(outages[1].ended, None);
+    assert_eq!(outages[1].duration(at(1000)), Duration::from_secs(700));
+}
+
+#[test]
+fn restarting_monitoring_ends_the_open_outage_at_the_last_record() {
+    let restarted = HistoryRecord {
+        timestamp: 5000,
+        event: HistoryEvent::StatusChanged {
+            from: None,
+            to: "NetworkOnly".to_string(),
+            outage: true,
+            evidence: Vec::new(),
+        },
+    };
+    let records = [
+        status(100, "NetworkOnly", true),
+        remediation(130, "reconnect"),
+        restarted,
+        status(5060, "Connected", false),
+    ];
+
+    let outages = outages(&records);
+
+    assert_eq!(outages.len(), 2);
+    assert_eq!(outages[0].ended, Some(at(130)));
+    assert_eq!(outages[1].started, at(5000));
+    assert_eq!(outages[1].ended, Some(at(5060)));
+}
+
+#[test]
+fn outages_and_downtime_are_limited_to_the_range() {
+    let dir = TempDir::new().unwrap();
+    let log = log_with_outages(&dir);
+
+    let all = log.outages(at(0), at(2000)).unwrap();
+    let later = log.outages(at(160), at(2000)).unwrap();
+
+    assert_eq!(all.len(), 2);
+    assert_eq!(later.len(), 1);
+    assert_eq!(later[0].status, "Disconnected");
+    assert_eq!(
+        log.downtime(at(0), at(2000)).unwrap(),
+        Duration::from_secs(360)
+    );
+    assert_eq!(
+        log.downtime(at(150), at(1100)).unwrap(),
+        Duration::from_secs(110)
+    );
+    assert_eq!(log.downtime(at(400), at(900)).unwrap(), Duration::ZERO);
+}
+
+#[test]
+fn ongoing_outage_counts_until_now() {
+    let dir = TempDir::new().unwrap();
+    let log = HistoryLog::new(dir.path().join("history.jsonl"));
+    let now = SystemTime::now();
+    let started = now.duration_since(UNIX_EPOCH).unwrap().as_secs() - 30;
+    log.append(&status(started, "Disconnected", true)).unwrap();
+
+    let downtime = log
+        .downtime(at(0), now + Duration::from_secs(3600))
+        .unwrap();
+
+    assert!(
+        downtime >= Duration::from_secs(30) && downtime < Duration::from_secs(40),
+        "{downtime:?}"
+    );
+}
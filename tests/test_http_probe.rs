@@ -0,0 +1,167 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{TestHttpServer, TestResponse};
+use internet_reloader::app::{NetworkApp, NetworkStatus};
+use internet_reloader::error::Result;
+use internet_reloader::internet_connectivity::{
+    CaptivePortal, HttpOutcome, HttpProbe, InternetAccess, InternetConnectivity,
+};
+use internet_reloader::network_manager::NetworkManager;
+use mockall::mock;
+
+mock! {
+    NetworkManager {}
+    impl NetworkManager for NetworkManager {
+        fn reconnect(&self) -> Result<()>;
+    }
+}
+
+mock! {
+    InternetConnectivity {}
+    impl InternetConnectivity for InternetConnectivity {
+        fn is_connected_to_network(&self) -> bool;
+        fn is_connected_to_internet(&self) -> bool;
+    }
+}
+
+fn server() -> TestHttpServer {
+    TestHttpServer::start(|request| match request.path.as_str() {
+        "/generate_204" => TestResponse::new(204, ""),
+        "/ok" => TestResponse::new(200, "<html>Success</html>"),
+        "/login" => TestResponse::new(302, "").with_header("Location", "http://portal/login"),
+        _ => TestResponse::new(404, "not found"),
+    })
+}
+
+#[test]
+fn succeeds_on_expected_status() {
+    let server = server();
+    let probe = HttpProbe::new([server.url("/generate_204")]).with_expected_status(204);
+
+    let results = probe.probe();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].status, Some(204));
+    assert_eq!(results[0].outcome, HttpOutcome::Success);
+    assert!(probe.is_connected_to_internet());
+}
+
+#[test]
+fn fails_on_unexpected_status() {
+    let server = server();
+    let probe = HttpProbe::new([server.url("/ok")]).with_expected_status(204);
+
+    let results = probe.probe();
+
+    assert_eq!(results[0].status, Some(200));
+    assert_eq!(results[0].outcome, HttpOutcome::UnexpectedStatus);
+    assert!(!probe.is_connected_to_internet());
+}
+
+#[test]
+fn does_not_follow_redirects() {
+    let server = server();
+    let probe = HttpProbe::new([server.url("/login")]);
+
+    let results = probe.probe();
+
+    assert_eq!(results[0].status, Some(302));
+    assert_eq!(results[0].outcome, HttpOutcome::UnexpectedStatus);
+}
+
+#[test]
+fn checks_expected_body() {
+    let server = server();
+
+    let matching = HttpProbe::new([server.url("/ok")]).with_expected_body("Success");
+    let mismatching = HttpProbe::new([server.url("/ok")]).with_expected_body("Welcome");
+
+    assert_eq!(matching.probe()[0].outcome, HttpOutcome::Success);
+    assert_eq!(mismatching.probe()[0].outcome, HttpOutcome::UnexpectedBody);
+    assert!(!mismatching.is_connected_to_internet());
+}
+
+#[test]
+fn succeeds_if_any_url_succeeds() {
+    let server = server();
+    let probe = HttpProbe::new([server.url("/missing"), server.url("/ok")]);
+
+    let results = probe.probe();
+
+    assert_eq!(results[0].outcome, HttpOutcome::UnexpectedStatus);
+    assert!(results[1].is_success());
+    assert!(probe.is_connected_to_internet());
+}
+
+#[test]
+fn reports_failure_when_unreachable() {
+    let url = {
+        let server = server();
+        server.url("/ok")
+    };
+    let probe = HttpProbe::new([url]).with_timeout(Duration::from_secs(1));
+
+    let results = probe.probe();
+
+    assert_eq!(results[0].status, None);
+    assert!(matches!(results[0].outcome, HttpOutcome::Failed(_)));
+    assert!(!probe.is_connected_to_internet());
+}
+
+#[test]
+fn times_out_on_slow_server() {
+    let server = TestHttpServer::start(|_| {
+        std::thread::sleep(Duration::from_millis(500));
+        TestResponse::new(200, "")
+    });
+    let probe = HttpProbe::new([server.url("/")]).with_timeout(Duration::from_millis(100));
+
+    let results = probe.probe();
+
+    assert!(matches!(results[0].outcome, HttpOutcome::Failed(_)));
+    assert!(results[0].elapsed < Duration::from_millis(500));
+}
+
+#[test]
+fn delegates_network_check() {
+    let mut network = MockInternetConnectivity::new();
+    network.expect_is_connected_to_network().return_const(false);
+
+    let probe = HttpProbe::new(Vec::<String>::new()).with_network(network);
+
+    assert!(!probe.is_connected_to_network());
+    assert!(!probe.is_connected_to_internet());
+}
+
+#[test]
+fn internet_access_answers_from_one_request_per_url() {
+    let server = server();
+    let probe = HttpProbe::new([server.url("/login"), server.url("/missing")]);
+
+    let access = probe.internet_access();
+
+    assert_eq!(
+        access,
+        InternetAccess::CaptivePortal(CaptivePortal {
+            login_url: Some("http://portal/login".to_string()),
+        })
+    );
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn app_check_requests_each_url_once() {
+    let server = server();
+    let mut network = MockInternetConnectivity::new();
+    network.expect_is_connected_to_network().return_const(true);
+    let probe = HttpProbe::new([server.url("/missing"), server.url("/generate_204")])
+        .with_network(network)
+        .with_expected_status(204);
+
+    let status = NetworkApp::new(probe, MockNetworkManager::new()).check();
+
+    assert_eq!(status, NetworkStatus::Connected);
+    assert_eq!(server.requests().len(), 2);
+}
@@ -0,0 +1,117 @@
+use internet_reloader::hysteresis::{Debouncer, Hysteresis, LinkState, Transition};
+
+const DOWN: Option<Transition> = Some(Transition {
+    from: LinkState::Up,
+    to: LinkState::Down,
+});
+
+const UP: Option<Transition> = Some(Transition {
+    from: LinkState::Down,
+    to: LinkState::Up,
+});
+
+fn record_all(debouncer: &mut Debouncer, results: &[bool]) -> Vec<Option<Transition>> {
+    results.iter().map(|r| debouncer.record(*r)).collect()
+}
+
+#[test]
+fn default_settings_do_not_debounce() {
+    let mut debouncer = Debouncer::new(Hysteresis::default());
+
+    assert_eq!(record_all(&mut debouncer, &[false, true]), vec![DOWN, UP]);
+}
+
+#[test]
+fn outage_requires_consecutive_failures() {
+    let mut debouncer = Debouncer::new(Hysteresis::new().with_consecutive_failures(3));
+
+    assert_eq!(
+        record_all(&mut debouncer, &[false, false, true, false, false]),
+        vec![None; 5]
+    );
+    assert_eq!(debouncer.state(), LinkState::Up);
+    assert_eq!(debouncer.record(false), DOWN);
+    assert_eq!(debouncer.state(), LinkState::Down);
+}
+
+#[test]
+fn outage_declared_by_failure_window() {
+    let mut debouncer = Debouncer::new(Hysteresis::new().with_failure_window(3, 5));
+
+    assert_eq!(
+        record_all(&mut debouncer, &[false, true, false, true, false]),
+        vec![None, None, None, None, DOWN]
+    );
+}
+
+#[test]
+fn failure_window_alone_ignores_single_failures() {
+    let mut debouncer = Debouncer::new(Hysteresis::new().with_failure_window(2, 3));
+
+    assert_eq!(
+        record_all(&mut debouncer, &[false, true, true, false, true]),
+        vec![None; 5]
+    );
+    assert_eq!(debouncer.record(false), DOWN);
+}
+
+#[test]
+fn consecutive_failures_and_failure_window_both_declare_outages() {
+    let settings = Hysteresis::new()
+        .with_consecutive_failures(2)
+        .with_failure_window(3, 5);
+
+    let mut consecutive = Debouncer::new(settings);
+    assert_eq!(
+        record_all(&mut consecutive, &[true, false, false]),
+        vec![None, None, DOWN]
+    );
+    let mut window = Debouncer::new(settings);
+    assert_eq!(
+        record_all(&mut window, &[false, true, false, true, false]),
+        vec![None, None, None, None, DOWN]
+    );
+}
+
+#[test]
+fn failure_window_forgets_old_results() {
+    let mut debouncer = Debouncer::new(Hysteresis::new().with_failure_window(2, 3));
+
+    assert_eq!(
+        record_all(&mut debouncer, &[false, true, true, false, true, true]),
+        vec![None; 6]
+    );
+}
+
+#[test]
+fn recovery_requires_consecutive_successes() {
+    let mut debouncer = Debouncer::new(Hysteresis::new().with_recovery_successes(2));
+
+    assert_eq!(
+        record_all(&mut debouncer, &[false, true, false, true, true]),
+        vec![DOWN, None, None, None, UP]
+    );
+}
+
+#[test]
+fn window_is_cleared_after_recovery() {
+    let mut debouncer = Debouncer::new(Hysteresis::new().with_failure_window(2, 4));
+
+    assert_eq!(
+        record_all(&mut debouncer, &[false, false, true, false]),
+        vec![None, DOWN, UP, None]
+    );
+}
+
+#[test]
+fn changing_settings_keeps_the_state() {
+    let mut debouncer = Debouncer::new(Hysteresis::new().with_recovery_successes(2));
+    debouncer.record(false);
+    let mut debouncer = debouncer.with_settings(Hysteresis::new().with_recovery_successes(3));
+
+    assert_eq!(debouncer.state(), LinkState::Down);
+    assert_eq!(
+        record_all(&mut debouncer, &[true, true, true]),
+        vec![None, None, UP]
+    );
+}
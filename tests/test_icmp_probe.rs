@@ -0,0 +1,132 @@
+#![cfg(target_os = "linux")]
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+use internet_reloader::internet_connectivity::{
+    ConnectionQuality, IcmpProbe, IcmpSocketKind, IcmpTargetResult, InternetConnectivity,
+};
+
+const LOOPBACK_V4: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+const LOOPBACK_V6: IpAddr = IpAddr::V6(Ipv6Addr::LOCALHOST);
+
+fn loopback_probe(targets: impl IntoIterator<Item = IpAddr>) -> IcmpProbe {
+    IcmpProbe::new(targets)
+        .with_count(3)
+        .with_timeout(Duration::from_secs(1))
+}
+
+fn assert_replied(result: &IcmpTargetResult, count: usize) {
+    assert!(result.socket.is_some(), "no socket: {:?}", result.error);
+    assert_eq!(result.replies.len(), count);
+    assert!(
+        result.replies.iter().all(Option::is_some),
+        "missing replies: {result:?}"
+    );
+    assert!(result.is_success());
+}
+
+#[test]
+fn pings_ipv4_loopback() {
+    let result = loopback_probe([LOOPBACK_V4]).ping(LOOPBACK_V4);
+
+    assert_replied(&result, 3);
+    assert!(
+        result
+            .replies
+            .iter()
+            .all(|rtt| rtt.unwrap() < Duration::from_secs(1))
+    );
+}
+
+#[test]
+fn pings_ipv6_loopback() {
+    let result = loopback_probe([LOOPBACK_V6]).ping(LOOPBACK_V6);
+
+    assert_replied(&result, 3);
+}
+
+#[test]
+fn concurrent_probes_only_match_their_own_replies() {
+    let results: Vec<IcmpTargetResult> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..4)
+            .map(|_| scope.spawn(|| loopback_probe([LOOPBACK_V4]).with_count(5).probe()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    assert_eq!(results.len(), 4);
+    for result in &results {
+        assert_replied(result, 5);
+    }
+}
+
+#[test]
+fn probe_reports_every_target_in_order() {
+    let results = loopback_probe([LOOPBACK_V6, LOOPBACK_V4])
+        .with_count(1)
+        .probe();
+
+    let targets: Vec<IpAddr> = results.iter().map(|result| result.target).collect();
+    assert_eq!(targets, vec![LOOPBACK_V6, LOOPBACK_V4]);
+    assert!(results.iter().all(IcmpTargetResult::is_success));
+}
+
+#[test]
+fn loopback_reply_counts_as_internet() {
+    let probe = loopback_probe([LOOPBACK_V4]).with_count(1);
+
+    assert!(probe.is_connected_to_internet());
+}
+
+#[test]
+fn unanswered_requests_time_out() {
+    // 198.51.100.0/24 is reserved for documentation, so nothing replies.
+    let target = IpAddr::V4(Ipv4Addr::new(198, 51, 100, 1));
+    let probe = IcmpProbe::new([target])
+        .with_count(2)
+        .with_timeout(Duration::from_millis(100));
+
+    let result = probe.ping(target);
+
+    assert_eq!(result.replies, vec![None, None]);
+    assert!(!result.is_success());
+    assert!(!probe.is_connected_to_internet());
+}
+
+#[test]
+fn quality_is_measured_by_pinging_the_first_target() {
+    let report = loopback_probe([LOOPBACK_V4, LOOPBACK_V6]).measure();
+
+    assert_eq!(report.sent, 3);
+    assert_eq!(report.received(), 3);
+    assert_eq!(report.loss_percent(), 0);
+}
+
+#[test]
+fn unanswered_requests_count_as_lost_quality_samples() {
+    let report = IcmpProbe::new([IpAddr::V4(Ipv4Addr::new(198, 51, 100, 1))])
+        .with_count(2)
+        .with_timeout(Duration::from_millis(100))
+        .measure();
+
+    assert_eq!(report.sent, 2);
+    assert_eq!(report.loss_percent(), 100);
+}
+
+#[test]
+fn probe_without_targets_is_not_connected() {
+    let probe = IcmpProbe::new([]);
+
+    assert!(probe.probe().is_empty());
+    assert!(!probe.is_connected_to_internet());
+}
+
+#[test]
+fn socket_kinds_are_printed() {
+    assert_eq!(IcmpSocketKind::Datagram.to_string(), "datagram");
+    assert_eq!(IcmpSocketKind::Raw.to_string(), "raw");
+}
@@ -0,0 +1,127 @@
+use internet_reloader::adapter::{Adapter, InterfaceMatch, InterfaceSelector};
+
+fn adapter(name: &str, mac: &str, description: &str, guid: Option<&str>) -> Adapter {
+    Adapter {
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        mac: Some(mac.to_string()),
+        guid: guid.map(str::to_string),
+        up: true,
+        wireless: true,
+    }
+}
+
+fn adapters() -> Vec<Adapter> {
+    vec![
+        adapter(
+            "wlan0",
+            "02:00:00:00:01:00",
+            "Intel(R) Wi-Fi 6 AX201",
+            Some("{6B29FC40-CA47-1067-B31D-00DD010662DA}"),
+        ),
+        adapter(
+            "wlan1",
+            "02:00:00:00:02:00",
+            "Realtek USB Wireless LAN",
+            None,
+        ),
+        adapter(
+            "wlan2",
+            "02:00:00:00:03:00",
+            "Realtek USB Wireless LAN",
+            None,
+        ),
+    ]
+}
+
+fn selected_names(selector: &InterfaceSelector) -> Vec<String> {
+    let adapters = adapters();
+    selector
+        .select(&adapters)
+        .into_iter()
+        .map(|adapter| adapter.name.clone())
+        .collect()
+}
+
+#[test]
+fn parses_each_kind_of_selector() {
+    assert_eq!(
+        "wlan0".parse::<InterfaceSelector>().unwrap().matcher(),
+        &InterfaceMatch::Name("wlan0".to_string())
+    );
+    assert_eq!(
+        "name:Wi-Fi".parse::<InterfaceSelector>().unwrap().matcher(),
+        &InterfaceMatch::Name("Wi-Fi".to_string())
+    );
+    assert_eq!(
+        "mac:02-00-00-00-01-00"
+            .parse::<InterfaceSelector>()
+            .unwrap()
+            .matcher(),
+        &InterfaceMatch::Mac("02-00-00-00-01-00".to_string())
+    );
+    assert_eq!(
+        "description:Realtek"
+            .parse::<InterfaceSelector>()
+            .unwrap()
+            .matcher(),
+        &InterfaceMatch::Description("Realtek".to_string())
+    );
+    assert_eq!(
+        "guid:{6b29fc40-ca47-1067-b31d-00dd010662da}"
+            .parse::<InterfaceSelector>()
+            .unwrap()
+            .matcher(),
+        &InterfaceMatch::Guid("{6b29fc40-ca47-1067-b31d-00dd010662da}".to_string())
+    );
+    assert!("mac:".parse::<InterfaceSelector>().is_err());
+    assert!("".parse::<InterfaceSelector>().is_err());
+}
+
+#[test]
+fn matches_by_name_mac_description_and_guid() {
+    let select = |selector: &str| selected_names(&selector.parse().unwrap());
+
+    assert_eq!(select("wlan1"), vec!["wlan1"]);
+    assert_eq!(select("mac:02-00-00-00-02-00"), vec!["wlan1"]);
+    assert_eq!(select("MAC:02:00:00:00:02:00"), Vec::<String>::new());
+    assert_eq!(select("description:intel"), vec!["wlan0"]);
+    assert_eq!(
+        select("guid:6b29fc40-ca47-1067-b31d-00dd010662da"),
+        vec!["wlan0"]
+    );
+    assert!(select("wlan9").is_empty());
+}
+
+#[test]
+fn selects_first_match_unless_all() {
+    let selector: InterfaceSelector = "description:Realtek".parse().unwrap();
+
+    assert_eq!(selected_names(&selector), vec!["wlan1"]);
+    assert_eq!(
+        selected_names(&selector.with_all(true)),
+        vec!["wlan1", "wlan2"]
+    );
+}
+
+#[test]
+fn single_name_is_only_known_for_one_named_interface() {
+    assert_eq!(
+        InterfaceSelector::name("wlan0").single_name(),
+        Some("wlan0")
+    );
+    assert_eq!(
+        InterfaceSelector::name("wlan0")
+            .with_all(true)
+            .single_name(),
+        None
+    );
+    assert_eq!(
+        InterfaceSelector::new(InterfaceMatch::Description("Intel".to_string())).single_name(),
+        None
+    );
+    assert_eq!(
+        InterfaceSelector::name("wlan0").names().unwrap(),
+        vec!["wlan0"]
+    );
+}
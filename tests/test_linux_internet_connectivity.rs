@@ -0,0 +1,172 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use std::fs;
+use std::net::TcpListener;
+
+use common::{ROUTE_HEADER, add_interface, default_route, subnet_route, write_proc};
+use internet_reloader::adapter::InterfaceSelector;
+use internet_reloader::internet_connectivity::{InternetConnectivity, LinuxInternetConnectivity};
+use tempfile::TempDir;
+
+#[test]
+fn connected_when_default_route_interface_is_up() {
+    let root = TempDir::new().unwrap();
+    add_interface(root.path(), "lo", "unknown", "1", "772");
+    add_interface(root.path(), "wlan0", "up", "1", "1");
+    write_proc(
+        root.path(),
+        "route",
+        Some(ROUTE_HEADER),
+        &[&default_route("wlan0", "0003"), &subnet_route("wlan0")],
+    );
+
+    let checker = LinuxInternetConnectivity::with_root(root.path());
+
+    assert_eq!(checker.interfaces_up(), vec!["wlan0"]);
+    assert_eq!(checker.default_route_interfaces(), vec!["wlan0"]);
+    assert!(checker.is_connected_to_network());
+}
+
+#[test]
+fn unknown_operstate_with_carrier_counts_as_up() {
+    let root = TempDir::new().unwrap();
+    add_interface(root.path(), "tun0", "unknown", "1", "65534");
+    write_proc(
+        root.path(),
+        "route",
+        Some(ROUTE_HEADER),
+        &[&default_route("tun0", "0001")],
+    );
+
+    let checker = LinuxInternetConnectivity::with_root(root.path());
+
+    assert!(checker.is_connected_to_network());
+}
+
+#[test]
+fn disconnected_when_default_route_interface_is_down() {
+    let root = TempDir::new().unwrap();
+    add_interface(root.path(), "wlan0", "down", "0", "1");
+    add_interface(root.path(), "eth0", "up", "1", "1");
+    write_proc(
+        root.path(),
+        "route",
+        Some(ROUTE_HEADER),
+        &[&default_route("wlan0", "0003"), &subnet_route("eth0")],
+    );
+
+    let checker = LinuxInternetConnectivity::with_root(root.path());
+
+    assert_eq!(checker.interfaces_up(), vec!["eth0"]);
+    assert!(!checker.is_connected_to_network());
+}
+
+#[test]
+fn disconnected_without_default_route() {
+    let root = TempDir::new().unwrap();
+    add_interface(root.path(), "wlan0", "up", "1", "1");
+    write_proc(
+        root.path(),
+        "route",
+        Some(ROUTE_HEADER),
+        &[&subnet_route("wlan0")],
+    );
+
+    let checker = LinuxInternetConnectivity::with_root(root.path());
+
+    assert!(checker.default_route_interfaces().is_empty());
+    assert!(!checker.is_connected_to_network());
+}
+
+#[test]
+fn ignores_default_route_that_is_not_up() {
+    let root = TempDir::new().unwrap();
+    add_interface(root.path(), "wlan0", "up", "1", "1");
+    write_proc(
+        root.path(),
+        "route",
+        Some(ROUTE_HEADER),
+        &[&default_route("wlan0", "0002")],
+    );
+
+    let checker = LinuxInternetConnectivity::with_root(root.path());
+
+    assert!(!checker.is_connected_to_network());
+}
+
+#[test]
+fn disconnected_when_root_is_missing() {
+    let root = TempDir::new().unwrap();
+
+    let checker = LinuxInternetConnectivity::with_root(root.path().join("missing"));
+
+    assert!(checker.interfaces_up().is_empty());
+    assert!(!checker.is_connected_to_network());
+}
+
+#[test]
+fn network_check_is_scoped_to_selected_interface() {
+    let root = TempDir::new().unwrap();
+    add_interface(root.path(), "eth0", "up", "1", "1");
+    add_interface(root.path(), "wlan0", "down", "0", "1");
+    write_proc(
+        root.path(),
+        "route",
+        Some(ROUTE_HEADER),
+        &[
+            &default_route("eth0", "0003"),
+            &default_route("wlan0", "0003"),
+        ],
+    );
+
+    let wired = LinuxInternetConnectivity::with_root(root.path());
+    let wireless = LinuxInternetConnectivity::with_root(root.path())
+        .with_interface(InterfaceSelector::name("wlan0"));
+
+    assert!(wired.is_connected_to_network());
+    assert_eq!(
+        wireless.selected_interfaces(),
+        Some(vec!["wlan0".to_string()])
+    );
+    assert!(!wireless.is_connected_to_network());
+}
+
+#[test]
+fn selected_interfaces_are_matched_against_sysfs() {
+    let root = TempDir::new().unwrap();
+    add_interface(root.path(), "eth0", "up", "1", "1");
+    add_interface(root.path(), "wlan0", "up", "1", "1");
+    add_interface(root.path(), "wlan1", "up", "1", "1");
+    fs::write(
+        root.path().join("sys/class/net/wlan1/address"),
+        "02:00:00:00:02:00\n",
+    )
+    .unwrap();
+
+    let checker = LinuxInternetConnectivity::with_root(root.path())
+        .with_interface("mac:02:00:00:00:02:00".parse().unwrap());
+
+    assert_eq!(
+        checker.selected_interfaces(),
+        Some(vec!["wlan1".to_string()])
+    );
+}
+
+#[test]
+fn internet_check_connects_through_selected_interface() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let host = listener.local_addr().unwrap().to_string();
+    let root = TempDir::new().unwrap();
+
+    let loopback = LinuxInternetConnectivity::with_root(root.path())
+        .with_interface(InterfaceSelector::name("lo"))
+        .with_internet_host(host.clone());
+    let missing = LinuxInternetConnectivity::with_root(root.path())
+        .with_interface(InterfaceSelector::name("missing0"))
+        .with_internet_host(host);
+
+    assert!(loopback.is_connected_to_internet());
+    assert!(!missing.is_connected_to_internet());
+}
@@ -0,0 +1,66 @@
+use internet_reloader::logging::{self, LogFormat};
+use tracing::Level;
+
+#[test]
+fn log_format_parses_names() {
+    assert_eq!("text".parse(), Ok(LogFormat::Text));
+    assert_eq!("JSON".parse(), Ok(LogFormat::Json));
+    assert_eq!("journald".parse(), Ok(LogFormat::Journald));
+    assert_eq!("syslog".parse(), Ok(LogFormat::Syslog));
+    assert!("xml".parse::<LogFormat>().is_err());
+}
+
+#[test]
+fn log_format_round_trips_through_display() {
+    for format in [
+        LogFormat::Text,
+        LogFormat::Json,
+        LogFormat::Journald,
+        LogFormat::Syslog,
+    ] {
+        assert_eq!(format.to_string().parse(), Ok(format));
+    }
+}
+
+#[test]
+fn init_fails_once_a_subscriber_is_installed() {
+    logging::init(LogFormat::Json, Level::DEBUG).unwrap();
+
+    assert!(logging::init(LogFormat::Text, Level::INFO).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn syslog_messages_carry_the_severity_and_tag() {
+    use std::os::unix::net::UnixDatagram;
+
+    use internet_reloader::logging::SyslogWriter;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("log");
+    let daemon = UnixDatagram::bind(&path).unwrap();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(SyslogWriter::connect(&path).unwrap().with_tag("reloader"))
+        .with_ansi(false)
+        .without_time()
+        .with_level(false)
+        .with_target(false)
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::warn!(status = "NetworkOnly", "Connection lost");
+        tracing::error!("Reconnect failed");
+    });
+
+    let mut buffer = [0u8; 1024];
+    let mut receive = || {
+        let len = daemon.recv(&mut buffer).unwrap();
+        String::from_utf8_lossy(&buffer[..len]).into_owned()
+    };
+    let pid = std::process::id();
+    assert_eq!(
+        receive(),
+        format!("<28>reloader[{pid}]: Connection lost status=\"NetworkOnly\"")
+    );
+    assert_eq!(receive(), format!("<27>reloader[{pid}]: Reconnect failed"));
+}
@@ -1,3 +1,5 @@
+#![cfg(target_os = "windows")]
+
 #[cfg(test)]
 use std::ffi::c_void;
 
@@ -110,5 +112,5 @@ impl WlanApi for MockWlanApi {
 fn test_reconnect_success() {
     let manager = WindowsNetworkManager::<MockWlanApi>::new();
     let result = manager.reconnect();
-    assert!(result);
-}
\ No newline at end of file
+    assert_eq!(result, Ok(()));
+}
@@ -0,0 +1,329 @@
+mod common;
+
+use std::fs;
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use common::{TestHttpServer, TestResponse};
+use internet_reloader::app::{NetworkApp, NetworkStatus};
+use internet_reloader::error::{Error, Result};
+use internet_reloader::events::{EventSubscriber, NetworkEvent};
+use internet_reloader::internet_connectivity::{CaptivePortal, InternetConnectivity};
+use internet_reloader::network_manager::NetworkManager;
+use internet_reloader::notifications::{
+    DeliveryError, Notification, NotificationKind, NotificationQueue, NotificationSink, Notifier,
+    RetryPolicy, WebhookFormat, WebhookSink,
+};
+use mockall::mock;
+use serde_json::Value;
+use tempfile::TempDir;
+
+mock! {
+    NetworkManager {}
+    impl NetworkManager for NetworkManager {
+        fn reconnect(&self) -> Result<()>;
+    }
+}
+
+mock! {
+    InternetConnectivity {}
+    impl InternetConnectivity for InternetConnectivity {
+        fn is_connected_to_network(&self) -> bool;
+        fn is_connected_to_internet(&self) -> bool;
+        fn captive_portal(&self) -> Option<CaptivePortal>;
+    }
+}
+
+/// Retries immediately, so tests do not wait.
+const NO_WAIT_RETRY: RetryPolicy = RetryPolicy {
+    attempts: 3,
+    backoff: Duration::ZERO,
+    max_backoff: Duration::ZERO,
+};
+
+fn notification(event: NotificationKind) -> Notification {
+    Notification {
+        event,
+        status: "NetworkOnly".to_string(),
+        interface: Some("wlan0".to_string()),
+        timestamp: 1_700_000_000,
+        duration_secs: Some(65),
+        action: Some("reconnect".to_string()),
+        error: Some("no active \"wireless\" connection".to_string()),
+    }
+}
+
+fn status_changed(from: Option<NetworkStatus>, to: NetworkStatus) -> NetworkEvent {
+    NetworkEvent::StatusChanged { from, to }
+}
+
+fn json(body: &str) -> Value {
+    serde_json::from_str(body).unwrap()
+}
+
+/// A URL on a loopback port nothing listens on, as if the internet were unavailable.
+fn unreachable_url() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}/hook", listener.local_addr().unwrap())
+}
+
+#[test]
+fn outage_remediation_and_recovery_are_posted_as_json() {
+    let server = TestHttpServer::start(|_| TestResponse::new(200, "ok"));
+
+    let mut checker = MockInternetConnectivity::new();
+    let mut internet = [false, true].into_iter();
+    checker.expect_is_connected_to_network().return_const(true);
+    checker
+        .expect_is_connected_to_internet()
+        .returning(move || internet.next().unwrap());
+    checker.expect_captive_portal().return_const(None);
+    let mut manager = MockNetworkManager::new();
+    manager
+        .expect_reconnect()
+        .return_const(Err(Error::NotConnected));
+
+    let notifier = Notifier::new(WebhookSink::new(server.url("/hook"))).with_interface("wlan0");
+    let app = NetworkApp::new(checker, manager).with_subscriber(notifier);
+    app.poll();
+    app.poll();
+
+    let requests = server.requests();
+    let bodies: Vec<Value> = requests.iter().map(|request| json(&request.body)).collect();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].method, "POST");
+    assert!(
+        requests[0]
+            .headers
+            .contains(&("content-type".to_string(), "application/json".to_string()))
+    );
+
+    assert_eq!(bodies[0]["event"], "outage_started");
+    assert_eq!(bodies[0]["status"], "NetworkOnly");
+    assert_eq!(bodies[0]["interface"], "wlan0");
+    assert_eq!(bodies[0]["duration_secs"], Value::Null);
+    assert_eq!(
+        bodies[0]["message"],
+        "Internet outage on wlan0, status is NetworkOnly"
+    );
+
+    assert_eq!(bodies[1]["event"], "remediation_attempted");
+    assert_eq!(bodies[1]["action"], "reconnect");
+    assert_eq!(bodies[1]["error"], "no active wireless connection");
+    assert_eq!(bodies[1]["duration_secs"], 0);
+
+    assert_eq!(bodies[2]["event"], "recovered");
+    assert_eq!(bodies[2]["status"], "Connected");
+    assert_eq!(bodies[2]["error"], Value::Null);
+    assert!(bodies[2]["timestamp"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn only_outage_boundaries_are_notified() {
+    let server = TestHttpServer::start(|_| TestResponse::new(204, ""));
+    let notifier = Notifier::new(WebhookSink::new(server.url("/hook")));
+
+    notifier.on_event(&status_changed(None, NetworkStatus::Connected));
+    notifier.on_event(&NetworkEvent::PollStarted);
+    notifier.on_event(&status_changed(
+        Some(NetworkStatus::Connected),
+        NetworkStatus::Disconnected,
+    ));
+    notifier.on_event(&status_changed(
+        Some(NetworkStatus::Disconnected),
+        NetworkStatus::CaptivePortal { login_url: None },
+    ));
+    notifier.on_event(&status_changed(
+        Some(NetworkStatus::CaptivePortal { login_url: None }),
+        NetworkStatus::Degraded { issues: Vec::new() },
+    ));
+
+    let events: Vec<Value> = server
+        .requests()
+        .iter()
+        .map(|request| json(&request.body)["event"].clone())
+        .collect();
+    assert_eq!(events, vec!["outage_started", "recovered"]);
+}
+
+#[test]
+fn chat_formats_post_the_message() {
+    let notification = notification(NotificationKind::RemediationAttempted);
+    let message = "reconnect on wlan0 failed after 65s: no active \"wireless\" connection";
+
+    let slack = json(&WebhookFormat::Slack.body(&notification));
+    let discord = json(&WebhookFormat::Discord.body(&notification));
+    let teams = json(&WebhookFormat::Teams.body(&notification));
+
+    assert_eq!(slack, serde_json::json!({ "text": message }));
+    assert_eq!(discord, serde_json::json!({ "content": message }));
+    assert_eq!(teams["@type"], "MessageCard");
+    assert_eq!(teams["title"], "Internet remediation attempted");
+    assert_eq!(teams["text"], message);
+}
+
+#[test]
+fn template_placeholders_are_escaped() {
+    let sink = WebhookSink::new("http://example.com/hook").with_template(
+        r#"{"summary": "{event}: {error}", "iface": "{interface}", "after": "{duration}", "other": "{unknown}"}"#,
+    );
+
+    let body = json(&sink.body(&notification(NotificationKind::RemediationAttempted)));
+
+    assert_eq!(
+        body["summary"],
+        "remediation attempted: no active \"wireless\" connection"
+    );
+    assert_eq!(body["iface"], "wlan0");
+    assert_eq!(body["after"], "65s");
+    assert_eq!(body["other"], "{unknown}");
+}
+
+#[test]
+fn server_errors_are_retried() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let server = {
+        let calls = calls.clone();
+        TestHttpServer::start(move |_| match calls.fetch_add(1, Ordering::SeqCst) {
+            0 => TestResponse::new(503, "busy"),
+            1 => TestResponse::new(429, "slow down"),
+            _ => TestResponse::new(200, "ok"),
+        })
+    };
+    let notifier =
+        Notifier::new(WebhookSink::new(server.url("/hook"))).with_retry_policy(NO_WAIT_RETRY);
+
+    assert!(notifier.notify(notification(NotificationKind::OutageStarted)));
+
+    assert_eq!(server.requests().len(), 3);
+    assert_eq!(notifier.queued(), 0);
+}
+
+#[test]
+fn rejected_notifications_are_dropped_without_retrying() {
+    let server = TestHttpServer::start(|_| TestResponse::new(400, "bad payload"));
+    let notifier =
+        Notifier::new(WebhookSink::new(server.url("/hook"))).with_retry_policy(NO_WAIT_RETRY);
+
+    assert!(notifier.notify(notification(NotificationKind::OutageStarted)));
+
+    assert_eq!(server.requests().len(), 1);
+    assert_eq!(notifier.queued(), 0);
+}
+
+#[test]
+fn undelivered_notifications_are_queued_until_the_internet_returns() {
+    let offline = Arc::new(AtomicBool::new(true));
+    let server = {
+        let offline = offline.clone();
+        TestHttpServer::start(move |_| match offline.load(Ordering::SeqCst) {
+            true => TestResponse::new(502, "offline"),
+            false => TestResponse::new(200, "ok"),
+        })
+    };
+    let notifier =
+        Notifier::new(WebhookSink::new(server.url("/hook"))).with_retry_policy(NO_WAIT_RETRY);
+
+    notifier.on_event(&status_changed(None, NetworkStatus::NetworkOnly));
+    notifier.on_event(&status_changed(
+        Some(NetworkStatus::NetworkOnly),
+        NetworkStatus::Disconnected,
+    ));
+    assert_eq!(notifier.queued(), 1);
+
+    offline.store(false, Ordering::SeqCst);
+    notifier.on_event(&status_changed(
+        Some(NetworkStatus::Disconnected),
+        NetworkStatus::Connected,
+    ));
+
+    let delivered: Vec<Value> = server
+        .requests()
+        .iter()
+        .skip(NO_WAIT_RETRY.attempts as usize)
+        .map(|request| json(&request.body)["event"].clone())
+        .collect();
+    assert_eq!(delivered, vec!["outage_started", "recovered"]);
+    assert_eq!(notifier.queued(), 0);
+}
+
+#[test]
+fn queue_survives_a_restart() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("state/webhook.jsonl");
+
+    let offline = Notifier::new(WebhookSink::new(unreachable_url()))
+        .with_retry_policy(RetryPolicy {
+            attempts: 1,
+            ..NO_WAIT_RETRY
+        })
+        .with_queue(NotificationQueue::open(&path).unwrap());
+    assert!(!offline.notify(notification(NotificationKind::OutageStarted)));
+    assert!(!offline.notify(notification(NotificationKind::RemediationAttempted)));
+    drop(offline);
+
+    let server = TestHttpServer::start(|_| TestResponse::new(200, "ok"));
+    let queue = NotificationQueue::open(&path).unwrap();
+    assert_eq!(queue.len(), 2);
+    let online = Notifier::new(WebhookSink::new(server.url("/hook"))).with_queue(queue);
+
+    online.on_event(&status_changed(None, NetworkStatus::Connected));
+    assert_eq!(server.requests().len(), 2);
+    assert_eq!(online.queued(), 0);
+    assert!(NotificationQueue::open(&path).unwrap().is_empty());
+}
+
+#[test]
+fn queue_drops_oldest_when_full_and_skips_invalid_lines() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("webhook.jsonl");
+    let recovered = serde_json::to_string(&notification(NotificationKind::Recovered)).unwrap();
+    fs::write(&path, format!("not json\n\n{recovered}\n")).unwrap();
+
+    let mut queue = NotificationQueue::open(&path).unwrap().with_capacity(2);
+    assert_eq!(queue.len(), 1);
+    queue.push(notification(NotificationKind::OutageStarted));
+    queue.push(notification(NotificationKind::RemediationAttempted));
+
+    let events: Vec<NotificationKind> = NotificationQueue::open(&path)
+        .unwrap()
+        .iter()
+        .map(|notification| notification.event)
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            NotificationKind::OutageStarted,
+            NotificationKind::RemediationAttempted
+        ]
+    );
+}
+
+#[test]
+fn unreachable_webhook_fails_to_deliver() {
+    let sink = WebhookSink::new(unreachable_url()).with_timeout(Duration::from_secs(1));
+
+    let result = sink.send(&notification(NotificationKind::OutageStarted));
+
+    assert!(matches!(result, Err(DeliveryError::Failed(_))));
+    assert!(result.unwrap_err().is_retryable());
+}
+
+#[test]
+fn backoff_doubles_up_to_the_maximum() {
+    let retry = RetryPolicy {
+        attempts: 5,
+        backoff: Duration::from_secs(1),
+        max_backoff: Duration::from_secs(5),
+    };
+
+    assert_eq!(retry.delay(0), Duration::from_secs(1));
+    assert_eq!(retry.delay(1), Duration::from_secs(2));
+    assert_eq!(retry.delay(2), Duration::from_secs(4));
+    assert_eq!(retry.delay(3), Duration::from_secs(5));
+    assert!(!DeliveryError::Status(404).is_retryable());
+    assert!(DeliveryError::Status(408).is_retryable());
+    assert!(DeliveryError::Status(500).is_retryable());
+}
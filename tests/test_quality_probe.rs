@@ -0,0 +1,133 @@
+use std::net::TcpListener;
+use std::time::Duration;
+
+use internet_reloader::internet_connectivity::{
+    ConnectionQuality, QualityIssue, QualityProbe, QualityReport, QualityThresholds,
+};
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+/// A burst of ten samples, three of which were lost.
+fn lossy_report() -> QualityReport {
+    QualityReport::from_samples([
+        Some(ms(100)),
+        None,
+        Some(ms(300)),
+        Some(ms(200)),
+        None,
+        Some(ms(400)),
+        Some(ms(100)),
+        None,
+        Some(ms(500)),
+        Some(ms(2600)),
+    ])
+}
+
+#[test]
+fn report_summarizes_samples() {
+    let report = lossy_report();
+
+    assert_eq!(report.sent, 10);
+    assert_eq!(report.received(), 7);
+    assert_eq!(report.loss_percent(), 30);
+    assert_eq!(report.min(), Some(ms(100)));
+    assert_eq!(report.max(), Some(ms(2600)));
+    assert_eq!(report.mean(), Some(ms(600)));
+    assert_eq!(report.median(), Some(ms(300)));
+    assert_eq!(report.percentile(90), Some(ms(2600)));
+    assert_eq!(report.percentile(0), Some(ms(100)));
+    // |300-100| + |200-300| + |400-200| + |100-400| + |500-100| + |2600-500| = 3300, over 6 pairs.
+    assert_eq!(report.jitter(), Some(ms(550)));
+}
+
+#[test]
+fn report_without_replies_has_no_round_trip_times() {
+    let report = QualityReport::from_samples([None, None]);
+
+    assert_eq!(report.loss_percent(), 100);
+    assert_eq!(report.median(), None);
+    assert_eq!(report.jitter(), None);
+    assert_eq!(report.to_string(), "0/2 replies, 100% loss");
+    assert_eq!(QualityReport::default().loss_percent(), 0);
+}
+
+#[test]
+fn thresholds_report_every_issue() {
+    let thresholds = QualityThresholds {
+        max_latency: Some(ms(250)),
+        max_jitter: Some(ms(500)),
+        max_loss_percent: Some(20),
+    };
+
+    assert_eq!(
+        thresholds.issues(&lossy_report()),
+        vec![
+            QualityIssue::Latency(ms(300)),
+            QualityIssue::Jitter(ms(550)),
+            QualityIssue::Loss { percent: 30 },
+        ]
+    );
+}
+
+#[test]
+fn thresholds_within_limits_report_nothing() {
+    let thresholds = QualityThresholds {
+        max_latency: Some(ms(300)),
+        max_jitter: Some(ms(550)),
+        max_loss_percent: Some(30),
+    };
+
+    assert!(thresholds.issues(&lossy_report()).is_empty());
+    assert!(
+        QualityThresholds::default()
+            .issues(&lossy_report())
+            .is_empty()
+    );
+}
+
+#[test]
+fn issues_are_printed() {
+    assert_eq!(QualityIssue::Latency(ms(300)).to_string(), "latency 300ms");
+    assert_eq!(QualityIssue::Loss { percent: 30 }.to_string(), "30% loss");
+}
+
+#[test]
+fn probe_times_connections_to_listener() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let probe = QualityProbe::new(listener.local_addr().unwrap().to_string())
+        .with_count(3)
+        .with_spacing(Duration::ZERO);
+
+    let report = probe.measure();
+
+    assert_eq!(report.sent, 3);
+    assert_eq!(report.loss_percent(), 0);
+    assert!(report.max().unwrap() < Duration::from_secs(1));
+}
+
+#[test]
+fn probe_counts_refused_connections_as_replies() {
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let probe = QualityProbe::new(addr.to_string())
+        .with_count(2)
+        .with_spacing(Duration::ZERO);
+
+    assert_eq!(probe.measure().received(), 2);
+}
+
+#[test]
+fn unresolvable_target_loses_every_sample() {
+    let probe = QualityProbe::new("not a target")
+        .with_count(2)
+        .with_spacing(Duration::ZERO);
+
+    let report = probe.measure();
+
+    assert_eq!(report.sent, 2);
+    assert_eq!(report.loss_percent(), 100);
+}
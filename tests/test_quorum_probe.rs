@@ -0,0 +1,147 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use internet_reloader::internet_connectivity::{InternetConnectivity, QuorumPolicy, QuorumProbe};
+
+/// A probe with a fixed result, taking `delay` to decide.
+struct FakeProbe {
+    connected: bool,
+    delay: Duration,
+}
+
+impl FakeProbe {
+    fn new(connected: bool) -> Self {
+        Self {
+            connected,
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+impl InternetConnectivity for FakeProbe {
+    fn is_connected_to_network(&self) -> bool {
+        true
+    }
+
+    fn is_connected_to_internet(&self) -> bool {
+        thread::sleep(self.delay);
+        self.connected
+    }
+}
+
+/// A quorum of `http` (weight 3) and `dns` (weight 1) succeeding, and `tcp` (weight 2) failing.
+fn quorum(policy: QuorumPolicy) -> QuorumProbe<FakeProbe> {
+    QuorumProbe::new()
+        .with_network(FakeProbe::new(true))
+        .with_policy(policy)
+        .with_weighted_member("http", 3, FakeProbe::new(true))
+        .with_weighted_member("tcp", 2, FakeProbe::new(false))
+        .with_weighted_member("dns", 1, FakeProbe::new(true))
+}
+
+#[test]
+fn any_policy_needs_one_success() {
+    assert!(quorum(QuorumPolicy::Any).is_connected_to_internet());
+
+    let probe = QuorumProbe::new()
+        .with_member("http", FakeProbe::new(false))
+        .with_member("dns", FakeProbe::new(false));
+    assert!(!probe.is_connected_to_internet());
+}
+
+#[test]
+fn all_policy_needs_every_success() {
+    assert!(!quorum(QuorumPolicy::All).is_connected_to_internet());
+
+    let probe = QuorumProbe::new()
+        .with_policy(QuorumPolicy::All)
+        .with_member("http", FakeProbe::new(true))
+        .with_member("dns", FakeProbe::new(true));
+    assert!(probe.is_connected_to_internet());
+}
+
+#[test]
+fn k_of_n_policy_counts_successes() {
+    assert!(quorum(QuorumPolicy::AtLeast(2)).is_connected_to_internet());
+    assert!(!quorum(QuorumPolicy::AtLeast(3)).is_connected_to_internet());
+}
+
+#[test]
+fn weighted_policy_sums_weights_of_successes() {
+    let result = quorum(QuorumPolicy::Weighted(4)).probe();
+
+    assert_eq!(result.score, 4);
+    assert!(result.connected);
+    assert!(!quorum(QuorumPolicy::Weighted(5)).is_connected_to_internet());
+}
+
+#[test]
+fn weighted_score_saturates_instead_of_overflowing() {
+    let probe = QuorumProbe::new()
+        .with_policy(QuorumPolicy::Weighted(u32::MAX))
+        .with_weighted_member("a", u32::MAX, FakeProbe::new(true))
+        .with_weighted_member("b", u32::MAX, FakeProbe::new(true));
+
+    let result = probe.probe();
+
+    assert_eq!(result.score, u32::MAX);
+    assert!(result.connected);
+}
+
+#[test]
+fn probe_without_members_is_never_connected() {
+    let probe = QuorumProbe::new().with_policy(QuorumPolicy::All);
+
+    assert!(!probe.is_connected_to_internet());
+}
+
+#[test]
+fn members_run_in_parallel() {
+    let slow = |connected| FakeProbe {
+        connected,
+        delay: Duration::from_millis(300),
+    };
+    let probe = QuorumProbe::new()
+        .with_member("a", slow(true))
+        .with_member("b", slow(true))
+        .with_member("c", slow(false));
+
+    let start = Instant::now();
+    let result = probe.probe();
+
+    assert!(start.elapsed() < Duration::from_millis(800));
+    assert_eq!(result.successes(), 2);
+    assert!(
+        result
+            .members
+            .iter()
+            .all(|member| member.elapsed >= Duration::from_millis(300))
+    );
+}
+
+#[test]
+fn latest_result_is_kept_for_reporting() {
+    let probe = quorum(QuorumPolicy::AtLeast(2));
+    assert_eq!(probe.last_result(), None);
+
+    assert!(probe.is_connected_to_internet());
+    let result = probe.last_result().unwrap();
+
+    let outcomes: Vec<(&str, u32, bool)> = result
+        .members
+        .iter()
+        .map(|member| (member.name.as_str(), member.weight, member.connected))
+        .collect();
+    assert_eq!(
+        outcomes,
+        vec![("http", 3, true), ("tcp", 2, false), ("dns", 1, true)]
+    );
+    assert_eq!(result.successes(), 2);
+}
+
+#[test]
+fn network_check_is_delegated() {
+    let probe = QuorumProbe::new().with_network(FakeProbe::new(true));
+
+    assert!(probe.is_connected_to_network());
+}
@@ -0,0 +1,136 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use internet_reloader::error::{Error, Result, Step};
+use internet_reloader::remediation::{
+    CommandAction, RemediationAction, RemediationAttempt, RemediationLadder,
+};
+
+/// An action which records when it is attempted and returns a fixed result.
+struct RecordingAction {
+    name: &'static str,
+    success: bool,
+    log: Rc<RefCell<Vec<&'static str>>>,
+}
+
+impl RemediationAction for RecordingAction {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn remediate(&self) -> Result<()> {
+        self.log.borrow_mut().push(self.name);
+        match self.success {
+            true => Ok(()),
+            false => Err(Error::NotConnected),
+        }
+    }
+}
+
+fn action(
+    name: &'static str,
+    success: bool,
+    log: &Rc<RefCell<Vec<&'static str>>>,
+) -> RecordingAction {
+    RecordingAction {
+        name,
+        success,
+        log: log.clone(),
+    }
+}
+
+#[test]
+fn escalates_through_rungs_and_stays_on_last() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let first = action("reconnect", false, &log);
+    let ladder = RemediationLadder::new()
+        .with_rung(action("bounce", false, &log))
+        .with_rung(action("fallback", true, &log));
+
+    assert_eq!(ladder.rung_count(), 3);
+
+    let attempts: Vec<RemediationAttempt> = (0..4).map(|_| ladder.attempt(&first)).collect();
+
+    assert_eq!(
+        *log.borrow(),
+        vec!["reconnect", "bounce", "fallback", "fallback"]
+    );
+    assert_eq!(
+        attempts.iter().map(|a| a.rung).collect::<Vec<_>>(),
+        vec![0, 1, 2, 2]
+    );
+    assert_eq!(attempts[0].result, Err(Error::NotConnected));
+    assert!(attempts[2].is_success());
+    assert_eq!(attempts[1].action, "bounce");
+}
+
+#[test]
+fn reset_returns_to_first_rung() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let first = action("reconnect", false, &log);
+    let ladder = RemediationLadder::new().with_rung(action("bounce", false, &log));
+
+    ladder.attempt(&first);
+    assert_eq!(ladder.level(), 1);
+
+    ladder.reset();
+    assert_eq!(ladder.level(), 0);
+
+    ladder.attempt(&first);
+    assert_eq!(*log.borrow(), vec!["reconnect", "reconnect"]);
+}
+
+#[test]
+fn ladder_without_extra_rungs_repeats_first_rung() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let first = action("reconnect", true, &log);
+    let ladder = RemediationLadder::new();
+
+    ladder.attempt(&first);
+    ladder.attempt(&first);
+
+    assert_eq!(ladder.level(), 0);
+    assert_eq!(*log.borrow(), vec!["reconnect", "reconnect"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn command_action_succeeds_when_all_commands_succeed() {
+    let action = CommandAction::new("noop")
+        .with_command("true", Vec::<String>::new())
+        .with_command("sh", ["-c", "exit 0"]);
+
+    assert_eq!(action.name(), "noop");
+    assert_eq!(action.remediate(), Ok(()));
+}
+
+#[cfg(unix)]
+#[test]
+fn command_action_stops_at_first_failure() {
+    let marker = tempfile::NamedTempFile::new().unwrap();
+    let path = marker.path().to_str().unwrap().to_string();
+
+    let action = CommandAction::new("failing")
+        .with_command("false", Vec::<String>::new())
+        .with_command("rm", [path.as_str()]);
+
+    assert_eq!(
+        action.remediate(),
+        Err(Error::Command {
+            program: "false".to_string(),
+            code: Some(1),
+        })
+    );
+    assert!(marker.path().exists());
+}
+
+#[test]
+fn command_action_fails_when_program_is_missing() {
+    let action = CommandAction::new("missing")
+        .with_command("internet-reloader-no-such-program", Vec::<String>::new());
+
+    let error = action.remediate().unwrap_err();
+
+    assert_eq!(error.step(), Some(Step::RunCommand));
+    assert!(matches!(error, Error::Os { .. } | Error::Io { .. }));
+}
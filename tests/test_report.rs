@@ -0,0 +1,328 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use internet_reloader::diagnosis::Layer;
+use internet_reloader::history::{HistoryEvent, HistoryRecord, Outage};
+use internet_reloader::report::{FailureType, Period, ReportFormat, UptimeReport};
+
+/// 2024-03-01 00:00:00 UTC, a Friday.
+const MARCH_1: u64 = 1_709_251_200;
+
+const HOUR: u64 = 3600;
+
+const DAY: u64 = 86_400;
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+fn status(timestamp: u64, from: Option<&str>, to: &str, outage: bool) -> HistoryRecord {
+    HistoryRecord {
+        timestamp,
+        event: HistoryEvent::StatusChanged {
+            from: from.map(str::to_string),
+            to: to.to_string(),
+            outage,
+            evidence: vec!["internet check failed".to_string()],
+        },
+    }
+}
+
+/// Returns the records of monitoring starting at `start` and then seeing each of `outages`, given
+/// as their start, end, status and failed layer.
+fn history(start: u64, outages: &[(u64, Option<u64>, &str, Option<Layer>)]) -> Vec<HistoryRecord> {
+    let mut records = vec![status(start, None, "Connected", false)];
+    for &(started, ended, to, layer) in outages {
+        records.push(status(started, Some("Connected"), to, true));
+        if let Some(layer) = layer {
+            records.push(HistoryRecord {
+                timestamp: started,
+                event: HistoryEvent::Diagnosis {
+                    failed_layer: Some(layer),
+                    evidence: Vec::new(),
+                },
+            });
+        }
+        if let Some(ended) = ended {
+            records.push(status(ended, Some(to), "Connected", false));
+        }
+    }
+    records
+}
+
+fn outage(started: u64, ended: Option<u64>, status: &str, layer: Option<Layer>) -> Outage {
+    Outage {
+        started: at(started),
+        ended: ended.map(at),
+        status: status.to_string(),
+        evidence: vec!["internet check failed".to_string()],
+        failed_layer: layer,
+        remediations: Vec::new(),
+    }
+}
+
+#[test]
+fn each_period_counts_the_part_of_each_outage_within_it() {
+    let records = history(
+        MARCH_1,
+        &[(
+            MARCH_1 + 23 * HOUR,
+            Some(MARCH_1 + DAY + HOUR),
+            "NetworkOnly",
+            Some(Layer::Dns),
+        )],
+    );
+
+    let report = UptimeReport::new(&records, Period::Daily, at(MARCH_1), at(MARCH_1 + 3 * DAY));
+
+    let labels: Vec<&str> = report
+        .periods
+        .iter()
+        .map(|period| period.label.as_str())
+        .collect();
+    assert_eq!(labels, vec!["2024-03-01", "2024-03-02", "2024-03-03"]);
+    assert_eq!(report.periods[0].downtime, Duration::from_secs(HOUR));
+    assert_eq!(report.periods[1].downtime, Duration::from_secs(HOUR));
+    assert_eq!(report.periods[0].outages, 1);
+    assert_eq!(report.periods[2].outages, 0);
+    assert_eq!(report.periods[0].records, 3);
+    assert_eq!(report.periods[1].records, 1);
+    assert_eq!(report.periods[2].records, 0);
+    assert!((report.periods[0].availability() - 95.8333).abs() < 0.001);
+    assert_eq!(report.periods[2].availability(), 100.0);
+    assert_eq!(report.downtime, Duration::from_secs(2 * HOUR));
+    assert!((report.availability() - 97.2222).abs() < 0.001);
+}
+
+#[test]
+fn periods_follow_the_utc_calendar() {
+    let friday = at(MARCH_1 + 12 * HOUR);
+    let december = at(MARCH_1 + 290 * DAY);
+
+    assert_eq!(Period::Daily.start(friday), at(MARCH_1));
+    assert_eq!(Period::Weekly.start(friday), at(MARCH_1 - 4 * DAY));
+    assert_eq!(Period::Weekly.next(friday), at(MARCH_1 + 3 * DAY));
+    assert_eq!(Period::Weekly.label(friday), "2024-02-26");
+    assert_eq!(Period::Daily.label(at(MARCH_1 - DAY)), "2024-02-29");
+    assert_eq!(Period::Monthly.start(friday), at(MARCH_1));
+    assert_eq!(Period::Monthly.label(december), "2024-12");
+    assert_eq!(Period::Monthly.next(december), at(1_735_689_600));
+}
+
+#[test]
+fn partial_periods_are_limited_to_the_report() {
+    let report = UptimeReport::new(
+        &history(MARCH_1 - 2 * DAY, &[]),
+        Period::Monthly,
+        at(MARCH_1 - DAY),
+        at(MARCH_1 + 2 * DAY),
+    );
+
+    assert_eq!(report.periods.len(), 2);
+    assert_eq!(report.periods[0].label, "2024-02");
+    assert_eq!(report.periods[0].start, at(MARCH_1 - DAY));
+    assert_eq!(report.periods[0].end, at(MARCH_1));
+    assert_eq!(report.periods[1].end, at(MARCH_1 + 2 * DAY));
+}
+
+#[test]
+fn mtbf_mttr_and_longest_outage() {
+    let records = history(
+        MARCH_1,
+        &[
+            (MARCH_1, Some(MARCH_1 + HOUR), "NetworkOnly", None),
+            (
+                MARCH_1 + DAY,
+                Some(MARCH_1 + DAY + 3 * HOUR),
+                "Disconnected",
+                None,
+            ),
+            (MARCH_1 + 2 * DAY - HOUR / 2, None, "NetworkOnly", None),
+        ],
+    );
+
+    let report = UptimeReport::new(&records, Period::Daily, at(MARCH_1), at(MARCH_1 + 2 * DAY));
+
+    let uptime = Duration::from_secs(2 * DAY - 4 * HOUR - HOUR / 2);
+    assert_eq!(report.uptime(), uptime);
+    assert_eq!(report.mtbf(), Some(uptime / 3));
+    assert_eq!(report.mttr(), Some(Duration::from_secs(2 * HOUR)));
+    assert_eq!(report.longest_outage(), Some(&report.outages[1]));
+    assert_eq!(report.outages[1].started, at(MARCH_1 + DAY));
+}
+
+#[test]
+fn no_outages_means_full_availability() {
+    let records = history(
+        MARCH_1 - DAY,
+        &[(MARCH_1 - DAY, Some(MARCH_1 - HOUR), "Disconnected", None)],
+    );
+
+    let report = UptimeReport::new(&records, Period::Weekly, at(MARCH_1), at(MARCH_1 + DAY));
+
+    assert!(report.outages.is_empty());
+    assert_eq!(report.availability(), 100.0);
+    assert_eq!(report.mtbf(), None);
+    assert_eq!(report.mttr(), None);
+    assert_eq!(report.longest_outage(), None);
+    assert!(report.to_string().contains("MTBF            -\n"));
+}
+
+#[test]
+fn failure_type_comes_from_the_diagnosis_or_the_status() {
+    let cases = [
+        (
+            "CaptivePortal (http://portal/login)",
+            Some(Layer::Internet),
+            FailureType::CaptivePortal,
+        ),
+        ("NetworkOnly", Some(Layer::Gateway), FailureType::Gateway),
+        ("NetworkOnly", Some(Layer::Dns), FailureType::Dns),
+        ("NetworkOnly", Some(Layer::Internet), FailureType::Upstream),
+        ("Disconnected", Some(Layer::Address), FailureType::Link),
+        ("Disconnected", None, FailureType::Link),
+        ("NetworkOnly", None, FailureType::Upstream),
+    ];
+
+    for (status, layer, expected) in cases {
+        assert_eq!(
+            FailureType::of(&outage(MARCH_1, None, status, layer)),
+            expected,
+            "{status} {layer:?}"
+        );
+    }
+}
+
+#[test]
+fn failures_are_broken_down_by_type() {
+    let records = history(
+        MARCH_1,
+        &[
+            (MARCH_1, Some(MARCH_1 + 60), "NetworkOnly", Some(Layer::Dns)),
+            (
+                MARCH_1 + HOUR,
+                Some(MARCH_1 + HOUR + 30),
+                "CaptivePortal",
+                None,
+            ),
+            (
+                MARCH_1 + 2 * HOUR,
+                Some(MARCH_1 + 2 * HOUR + 90),
+                "NetworkOnly",
+                Some(Layer::Dns),
+            ),
+        ],
+    );
+
+    let report = UptimeReport::new(&records, Period::Daily, at(MARCH_1), at(MARCH_1 + DAY));
+
+    let failures: Vec<(FailureType, usize, Duration)> = report
+        .failures
+        .iter()
+        .map(|failure| (failure.failure, failure.outages, failure.downtime))
+        .collect();
+    assert_eq!(
+        failures,
+        vec![
+            (FailureType::Dns, 2, Duration::from_secs(150)),
+            (FailureType::CaptivePortal, 1, Duration::from_secs(30)),
+        ]
+    );
+}
+
+#[test]
+fn csv_has_period_summary_and_failure_tables() {
+    let records = history(
+        MARCH_1,
+        &[(
+            MARCH_1 + HOUR,
+            Some(MARCH_1 + 2 * HOUR),
+            "NetworkOnly",
+            Some(Layer::Gateway),
+        )],
+    );
+    let report = UptimeReport::new(&records, Period::Daily, at(MARCH_1), at(MARCH_1 + 2 * DAY));
+
+    let csv = report.render(ReportFormat::Csv);
+    let tables: Vec<Vec<&str>> = csv
+        .trim_end()
+        .split("\n\n")
+        .map(|table| table.lines().collect())
+        .collect();
+
+    assert_eq!(tables.len(), 3);
+    assert_eq!(
+        tables[0],
+        vec![
+            "period,start,end,availability,downtime_secs,outages,records",
+            "2024-03-01,2024-03-01T00:00:00Z,2024-03-02T00:00:00Z,95.8333,3600,1,4",
+            "2024-03-02,2024-03-02T00:00:00Z,2024-03-03T00:00:00Z,100.0000,0,0,0",
+        ]
+    );
+    assert_eq!(tables[1][0], "metric,value");
+    assert!(tables[1].contains(&"outages,1"));
+    assert!(tables[1].contains(&"mttr_secs,3600"));
+    assert!(tables[1].contains(&"longest_outage_start,2024-03-01T01:00:00Z"));
+    assert_eq!(
+        tables[2],
+        vec!["failure_type,outages,downtime_secs", "gateway,1,3600"]
+    );
+}
+
+#[test]
+fn html_is_self_contained_and_escaped() {
+    let mut records = history(MARCH_1, &[(MARCH_1, None, "NetworkOnly", Some(Layer::Dns))]);
+    if let HistoryEvent::Diagnosis { evidence, .. } = &mut records[2].event {
+        evidence.push("dns failed: <no server> & \"nothing\"".to_string());
+    }
+    let report = UptimeReport::new(&records, Period::Daily, at(MARCH_1), at(MARCH_1 + HOUR));
+
+    let html = report.render(ReportFormat::Html);
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<style>"));
+    assert!(!html.contains("<script"));
+    assert!(!html.contains("<link"));
+    assert!(html.contains("dns failed: &lt;no server&gt; &amp; &quot;nothing&quot;"));
+    assert!(html.contains("(ongoing)"));
+    assert!(html.trim_end().ends_with("</html>"));
+}
+
+#[test]
+fn report_starts_at_the_first_record() {
+    let records = history(
+        MARCH_1 + DAY + 12 * HOUR,
+        &[(
+            MARCH_1 + DAY + 18 * HOUR,
+            Some(MARCH_1 + DAY + 21 * HOUR),
+            "Disconnected",
+            None,
+        )],
+    );
+
+    let report = UptimeReport::new(&records, Period::Daily, at(MARCH_1), at(MARCH_1 + 3 * DAY));
+
+    assert_eq!(report.from, at(MARCH_1 + DAY + 12 * HOUR));
+    let periods: Vec<(&str, SystemTime, usize)> = report
+        .periods
+        .iter()
+        .map(|period| (period.label.as_str(), period.start, period.records))
+        .collect();
+    assert_eq!(
+        periods,
+        vec![
+            ("2024-03-02", at(MARCH_1 + DAY + 12 * HOUR), 3),
+            ("2024-03-03", at(MARCH_1 + 2 * DAY), 0),
+        ]
+    );
+    assert!((report.periods[0].availability() - 75.0).abs() < 0.001);
+    assert!((report.availability() - 91.6667).abs() < 0.001);
+}
+
+#[test]
+fn report_without_records_covers_no_time() {
+    let report = UptimeReport::new(&[], Period::Daily, at(MARCH_1), at(MARCH_1 + DAY));
+
+    assert_eq!(report.from, report.to);
+    assert!(report.periods.is_empty());
+    assert!(report.outages.is_empty());
+}
@@ -0,0 +1,161 @@
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+use internet_reloader::internet_connectivity::{
+    InternetConnectivity, TcpConnectProbe, TcpEndpointResult,
+};
+
+/// Returns a loopback address nothing listens on, so connections are refused.
+fn refused_address() -> SocketAddr {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+}
+
+/// Returns a listener which accepts no more connections, and its address.
+///
+/// The accept queue is shrunk and filled, so the kernel drops further handshakes and connecting
+/// hangs until it times out.
+#[cfg(target_os = "linux")]
+fn unresponsive_listener() -> (TcpListener, TcpStream, SocketAddr) {
+    use std::os::fd::AsRawFd;
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    assert_eq!(unsafe { libc::listen(listener.as_raw_fd(), 0) }, 0);
+    let address = listener.local_addr().unwrap();
+    let queued = TcpStream::connect(address).unwrap();
+    (listener, queued, address)
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn connects_through_the_selected_device() {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let loopback = TcpConnectProbe::new([endpoint.clone()]).with_device("lo");
+    let missing = TcpConnectProbe::new([endpoint.clone()]).with_device("missing0");
+
+    assert!(loopback.connect(&endpoint).error.is_none());
+    assert!(missing.connect(&endpoint).error.is_some());
+}
+
+#[test]
+fn connects_to_ipv4_listener() {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let address = listener.local_addr().unwrap();
+    let probe = TcpConnectProbe::new([address.to_string()]);
+
+    let result = probe.connect(&address.to_string());
+
+    assert_eq!(result.endpoint, address.to_string());
+    assert_eq!(result.address, Some(address));
+    assert!(result.elapsed < Duration::from_secs(1));
+    assert_eq!(result.error, None);
+}
+
+#[test]
+fn connects_to_ipv6_listener() {
+    let listener = TcpListener::bind((Ipv6Addr::LOCALHOST, 0)).unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let results = TcpConnectProbe::new([address.to_string()]).probe();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].address, Some(address));
+}
+
+#[test]
+fn refused_connection_is_a_failure() {
+    let address = refused_address();
+
+    let result = TcpConnectProbe::new([address.to_string()]).connect(&address.to_string());
+
+    assert!(!result.is_success());
+    assert!(result.error.is_some());
+}
+
+#[test]
+fn unresolvable_endpoint_is_a_failure() {
+    let result = TcpConnectProbe::new(["no port"]).connect("no port");
+
+    assert_eq!(result.address, None);
+    assert!(result.error.is_some());
+}
+
+#[test]
+fn race_tries_next_address_when_one_is_refused() {
+    let listener = TcpListener::bind((Ipv6Addr::LOCALHOST, 0)).unwrap();
+    let address = listener.local_addr().unwrap();
+    let probe =
+        TcpConnectProbe::new(Vec::<String>::new()).with_attempt_delay(Duration::from_secs(5));
+
+    let started = Instant::now();
+    let (winner, _) = probe
+        .race(&[refused_address(), refused_address(), address])
+        .unwrap();
+
+    assert_eq!(winner, address);
+    // A refused attempt starts the next one immediately, without waiting for the attempt delay.
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn race_starts_next_address_while_attempt_hangs() {
+    let (_unresponsive, _queued, hanging) = unresponsive_listener();
+    let listener = TcpListener::bind((Ipv6Addr::LOCALHOST, 0)).unwrap();
+    let address = listener.local_addr().unwrap();
+    let probe = TcpConnectProbe::new(Vec::<String>::new())
+        .with_timeout(Duration::from_secs(5))
+        .with_attempt_delay(Duration::from_millis(50));
+
+    let started = Instant::now();
+    let (winner, handshake) = probe.race(&[hanging, address]).unwrap();
+
+    assert_eq!(winner, address);
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert!(handshake < started.elapsed());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn race_times_out_when_no_handshake_completes() {
+    let (_unresponsive, _queued, hanging) = unresponsive_listener();
+    let probe =
+        TcpConnectProbe::new([hanging.to_string()]).with_timeout(Duration::from_millis(200));
+
+    let started = Instant::now();
+    let result = probe.connect(&hanging.to_string());
+
+    assert!(!result.is_success());
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn race_without_addresses_fails() {
+    assert!(TcpConnectProbe::default().race(&[]).is_err());
+}
+
+#[test]
+fn internet_check_records_the_endpoint_which_succeeded() {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let address = listener.local_addr().unwrap();
+    let probe = TcpConnectProbe::new([refused_address().to_string(), address.to_string()]);
+
+    assert_eq!(probe.last_success(), None);
+    assert!(probe.is_connected_to_internet());
+
+    let success: TcpEndpointResult = probe.last_success().unwrap();
+    assert_eq!(success.endpoint, address.to_string());
+    assert_eq!(success.address, Some(address));
+}
+
+#[test]
+fn internet_check_fails_when_every_endpoint_fails() {
+    let probe = TcpConnectProbe::new([refused_address().to_string()]);
+
+    assert!(!probe.is_connected_to_internet());
+    assert_eq!(probe.last_success(), None);
+}
@@ -0,0 +1,243 @@
+#![cfg(target_os = "linux")]
+
+use std::os::unix::net::UnixDatagram;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use internet_reloader::adapter::{InterfaceMatch, InterfaceSelector};
+use internet_reloader::error::{Error, Step};
+use internet_reloader::network_manager::{
+    NetworkManager, WpaNetwork, WpaSupplicantInterfaces, WpaSupplicantNetworkManager,
+};
+use tempfile::TempDir;
+
+const ASSOCIATED_STATUS: &str = "bssid=02:00:00:00:01:00\nfreq=2412\nssid=PhoneHotspot\nid=3\nmode=station\nwpa_state=COMPLETED\nip_address=192.168.43.12\n";
+const SCANNING_STATUS: &str = "wpa_state=SCANNING\n";
+
+/// A fake wpa_supplicant control socket recording the commands it receives.
+struct FakeWpaSupplicant {
+    _dir: TempDir,
+    path: PathBuf,
+    commands: Arc<Mutex<Vec<String>>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FakeWpaSupplicant {
+    fn start(reply: fn(&str) -> String) -> Self {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("wlan0");
+        let socket = UnixDatagram::bind(&path).unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_millis(20)))
+            .unwrap();
+
+        let commands = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let commands = commands.clone();
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                let mut buffer = [0u8; 4096];
+                while !stop.load(Ordering::Relaxed) {
+                    let Ok((len, sender)) = socket.recv_from(&mut buffer) else {
+                        continue;
+                    };
+                    let command = String::from_utf8_lossy(&buffer[..len]).into_owned();
+                    let sender = sender.as_pathname().unwrap().to_path_buf();
+                    let reply = reply(&command);
+                    // Record the command before replying, so it is seen once the reply arrives.
+                    commands.lock().unwrap().push(command);
+
+                    // Interleave an unsolicited event to check it is skipped.
+                    socket
+                        .send_to(b"<3>CTRL-EVENT-SCAN-STARTED ", &sender)
+                        .unwrap();
+                    socket.send_to(reply.as_bytes(), &sender).unwrap();
+                }
+            })
+        };
+
+        Self {
+            _dir: dir,
+            path,
+            commands,
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    fn manager(&self) -> WpaSupplicantNetworkManager {
+        WpaSupplicantNetworkManager::with_socket_path(&self.path)
+            .with_timeout(Duration::from_secs(1))
+    }
+
+    fn commands(&self) -> Vec<String> {
+        self.commands.lock().unwrap().clone()
+    }
+}
+
+impl Drop for FakeWpaSupplicant {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.join().unwrap();
+        }
+    }
+}
+
+fn associated(command: &str) -> String {
+    match command {
+        "STATUS" => ASSOCIATED_STATUS.to_string(),
+        _ => "OK\n".to_string(),
+    }
+}
+
+#[test]
+fn reconnect_disconnects_then_reconnects() {
+    let fake = FakeWpaSupplicant::start(associated);
+
+    assert_eq!(fake.manager().reconnect(), Ok(()));
+    assert_eq!(fake.commands(), vec!["STATUS", "DISCONNECT", "RECONNECT"]);
+}
+
+#[test]
+fn current_network_reports_id_and_ssid() {
+    let fake = FakeWpaSupplicant::start(associated);
+
+    let network = fake.manager().current_network().unwrap();
+
+    assert_eq!(
+        network,
+        Some(WpaNetwork {
+            id: 3,
+            ssid: "PhoneHotspot".to_string(),
+        })
+    );
+}
+
+#[test]
+fn current_network_is_none_when_not_associated() {
+    let fake = FakeWpaSupplicant::start(|command| match command {
+        "STATUS" => SCANNING_STATUS.to_string(),
+        _ => "OK\n".to_string(),
+    });
+
+    assert_eq!(fake.manager().current_network().unwrap(), None);
+    assert_eq!(fake.manager().reconnect(), Ok(()));
+}
+
+#[test]
+fn select_network_sends_network_id() {
+    let fake = FakeWpaSupplicant::start(associated);
+
+    fake.manager().select_network(3).unwrap();
+
+    assert_eq!(fake.commands(), vec!["SELECT_NETWORK 3"]);
+}
+
+#[test]
+fn reconnect_fails_when_command_fails() {
+    let fake = FakeWpaSupplicant::start(|command| match command {
+        "STATUS" => ASSOCIATED_STATUS.to_string(),
+        "RECONNECT" => "FAIL\n".to_string(),
+        _ => "OK\n".to_string(),
+    });
+
+    assert_eq!(
+        fake.manager().reconnect(),
+        Err(Error::Backend {
+            step: Step::Connect,
+            code: Some("FAIL".to_string()),
+            message: "RECONNECT returned FAIL".to_string(),
+        })
+    );
+}
+
+#[test]
+fn reconnect_fails_without_control_socket() {
+    let dir = TempDir::new().unwrap();
+    let manager = WpaSupplicantNetworkManager::with_socket_path(dir.path().join("wlan0"));
+
+    // Connecting to a missing socket fails with ENOENT.
+    assert_eq!(
+        manager.reconnect(),
+        Err(Error::Os {
+            step: Step::QueryConnection,
+            code: 2,
+        })
+    );
+}
+
+fn add_wireless_interface(root: &std::path::Path, name: &str, address: &str) {
+    let dir = root.join("sys/class/net").join(name);
+    std::fs::create_dir_all(dir.join("wireless")).unwrap();
+    std::fs::write(dir.join("address"), format!("{address}\n")).unwrap();
+}
+
+#[test]
+fn selected_interfaces_are_reconnected_through_their_sockets() {
+    let fake = FakeWpaSupplicant::start(associated);
+    let root = TempDir::new().unwrap();
+    add_wireless_interface(root.path(), "wlan0", "02:00:00:00:01:00");
+    add_wireless_interface(root.path(), "wlan1", "02:00:00:00:02:00");
+
+    let manager = WpaSupplicantInterfaces::new("mac:02:00:00:00:01:00".parse().unwrap())
+        .with_root(root.path())
+        .with_control_directory(fake.path.parent().unwrap())
+        .with_timeout(Duration::from_secs(1));
+
+    assert_eq!(manager.interfaces(), Ok(vec!["wlan0".to_string()]));
+    assert_eq!(manager.reconnect(), Ok(()));
+    assert_eq!(fake.commands(), vec!["STATUS", "DISCONNECT", "RECONNECT"]);
+}
+
+#[test]
+fn all_selected_interfaces_are_reconnected_even_if_one_fails() {
+    let fake = FakeWpaSupplicant::start(associated);
+    let root = TempDir::new().unwrap();
+    let driver = root.path().join("drivers/rtw88");
+    std::fs::create_dir_all(&driver).unwrap();
+    for name in ["wlan-a", "wlan0"] {
+        add_wireless_interface(root.path(), name, "02:00:00:00:01:00");
+        let device = root.path().join("sys/class/net").join(name).join("device");
+        std::fs::create_dir(&device).unwrap();
+        std::os::unix::fs::symlink(&driver, device.join("driver")).unwrap();
+    }
+
+    let manager = WpaSupplicantInterfaces::new(
+        InterfaceSelector::new(InterfaceMatch::Description("RTW88".to_string())).with_all(true),
+    )
+    .with_root(root.path())
+    .with_control_directory(fake.path.parent().unwrap())
+    .with_timeout(Duration::from_secs(1));
+
+    assert_eq!(
+        manager.interfaces(),
+        Ok(vec!["wlan-a".to_string(), "wlan0".to_string()])
+    );
+    // wlan-a has no control socket, but wlan0 is still reconnected.
+    assert_eq!(
+        manager.reconnect(),
+        Err(Error::Os {
+            step: Step::QueryConnection,
+            code: 2,
+        })
+    );
+    assert_eq!(fake.commands(), vec!["STATUS", "DISCONNECT", "RECONNECT"]);
+}
+
+#[test]
+fn selecting_no_wireless_interface_fails() {
+    let root = TempDir::new().unwrap();
+    std::fs::create_dir_all(root.path().join("sys/class/net/eth0")).unwrap();
+
+    let manager =
+        WpaSupplicantInterfaces::new(InterfaceSelector::name("eth0")).with_root(root.path());
+
+    assert_eq!(manager.reconnect(), Err(Error::NoInterface));
+}